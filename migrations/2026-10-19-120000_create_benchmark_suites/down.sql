@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE benchmark_suite_bin_files;
+DROP TABLE benchmark_suites;
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE benchmark_suites (
+    id TEXT PRIMARY KEY NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE benchmark_suite_bin_files (
+    suite_id TEXT NOT NULL REFERENCES benchmark_suites(id),
+    bin_file_id TEXT NOT NULL REFERENCES bin_files(id),
+    weight FLOAT NOT NULL DEFAULT 1.0,
+    PRIMARY KEY (suite_id, bin_file_id)
+);
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE organizations DROP COLUMN cpi_map;
\ No newline at end of file
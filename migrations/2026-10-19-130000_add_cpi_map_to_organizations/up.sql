@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE organizations ADD COLUMN cpi_map TEXT;
\ No newline at end of file
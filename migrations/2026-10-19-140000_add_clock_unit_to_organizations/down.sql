@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE organizations DROP COLUMN clock_unit;
\ No newline at end of file
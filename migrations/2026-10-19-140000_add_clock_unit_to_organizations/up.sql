@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE organizations ADD COLUMN clock_unit TEXT NOT NULL DEFAULT 's';
\ No newline at end of file
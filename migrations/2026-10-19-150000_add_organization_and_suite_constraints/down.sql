@@ -0,0 +1,42 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE organizations_without_constraints (
+    id TEXT PRIMARY KEY NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    clock FLOAT NOT NULL,
+    cpi_instruction_r FLOAT NOT NULL,
+    cpi_instruction_i FLOAT NOT NULL,
+    cpi_instruction_l FLOAT NOT NULL,
+    cpi_instruction_s FLOAT NOT NULL,
+    cpi_instruction_b FLOAT NOT NULL,
+    cpi_instruction_u FLOAT NOT NULL,
+    cpi_instruction_j FLOAT NOT NULL,
+    cpi_map TEXT,
+    clock_unit TEXT NOT NULL DEFAULT 's'
+);
+
+INSERT INTO organizations_without_constraints (
+    id, created_at, clock,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map, clock_unit
+)
+SELECT
+    id, created_at, clock,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map, clock_unit
+FROM organizations;
+
+DROP TABLE organizations;
+ALTER TABLE organizations_without_constraints RENAME TO organizations;
+
+CREATE TABLE suite_bin_files_without_constraints (
+    suite_id TEXT NOT NULL REFERENCES benchmark_suites(id),
+    bin_file_id TEXT NOT NULL REFERENCES bin_files(id),
+    weight FLOAT NOT NULL DEFAULT 1.0,
+    PRIMARY KEY (suite_id, bin_file_id)
+);
+
+INSERT INTO suite_bin_files_without_constraints (suite_id, bin_file_id, weight)
+SELECT suite_id, bin_file_id, weight FROM benchmark_suite_bin_files;
+
+DROP TABLE benchmark_suite_bin_files;
+ALTER TABLE suite_bin_files_without_constraints RENAME TO benchmark_suite_bin_files;
@@ -0,0 +1,42 @@
+-- Your SQL goes here
+CREATE TABLE organizations_with_constraints (
+    id TEXT PRIMARY KEY NOT NULL CHECK (length(trim(id)) > 0),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    clock FLOAT NOT NULL CHECK (clock > 0),
+    clock_unit TEXT NOT NULL DEFAULT 's' CHECK (clock_unit IN ('ps', 'ns', 'us', 'ms', 's', 'hz', 'khz', 'mhz', 'ghz')),
+    cpi_instruction_r FLOAT NOT NULL CHECK (cpi_instruction_r > 0),
+    cpi_instruction_i FLOAT NOT NULL CHECK (cpi_instruction_i > 0),
+    cpi_instruction_l FLOAT NOT NULL CHECK (cpi_instruction_l > 0),
+    cpi_instruction_s FLOAT NOT NULL CHECK (cpi_instruction_s > 0),
+    cpi_instruction_b FLOAT NOT NULL CHECK (cpi_instruction_b > 0),
+    cpi_instruction_u FLOAT NOT NULL CHECK (cpi_instruction_u > 0),
+    cpi_instruction_j FLOAT NOT NULL CHECK (cpi_instruction_j > 0),
+    cpi_map TEXT CHECK (cpi_map IS NULL OR json_valid(cpi_map))
+);
+
+INSERT INTO organizations_with_constraints (
+    id, created_at, clock, clock_unit,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map
+)
+SELECT
+    id, created_at, clock, clock_unit,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map
+FROM organizations;
+
+DROP TABLE organizations;
+ALTER TABLE organizations_with_constraints RENAME TO organizations;
+
+CREATE TABLE suite_bin_files_with_constraints (
+    suite_id TEXT NOT NULL REFERENCES benchmark_suites(id),
+    bin_file_id TEXT NOT NULL REFERENCES bin_files(id),
+    weight FLOAT NOT NULL DEFAULT 1.0 CHECK (weight > 0),
+    PRIMARY KEY (suite_id, bin_file_id)
+);
+
+INSERT INTO suite_bin_files_with_constraints (suite_id, bin_file_id, weight)
+SELECT suite_id, bin_file_id, weight FROM benchmark_suite_bin_files;
+
+DROP TABLE benchmark_suite_bin_files;
+ALTER TABLE suite_bin_files_with_constraints RENAME TO benchmark_suite_bin_files;
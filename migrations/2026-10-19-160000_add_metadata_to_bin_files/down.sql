@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE bin_files DROP COLUMN instruction_count;
+ALTER TABLE bin_files DROP COLUMN hash;
+ALTER TABLE bin_files DROP COLUMN format;
+ALTER TABLE bin_files DROP COLUMN original_filename;
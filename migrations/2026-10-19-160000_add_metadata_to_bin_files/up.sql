@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE bin_files ADD COLUMN original_filename TEXT;
+ALTER TABLE bin_files ADD COLUMN format TEXT NOT NULL DEFAULT 'binary_text';
+-- Filled in by the application for files stored before this migration
+ALTER TABLE bin_files ADD COLUMN hash TEXT NOT NULL DEFAULT '';
+ALTER TABLE bin_files ADD COLUMN instruction_count INTEGER NOT NULL DEFAULT 0;
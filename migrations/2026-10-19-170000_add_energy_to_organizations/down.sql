@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE organizations DROP COLUMN static_power;
+ALTER TABLE organizations DROP COLUMN energy_map;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE organizations ADD COLUMN energy_map TEXT CHECK (energy_map IS NULL OR json_valid(energy_map));
+ALTER TABLE organizations ADD COLUMN static_power FLOAT NOT NULL DEFAULT 0 CHECK (static_power >= 0);
\ No newline at end of file
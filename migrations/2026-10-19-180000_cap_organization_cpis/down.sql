@@ -0,0 +1,31 @@
+-- This file should undo anything in `up.sql`
+CREATE TABLE organizations_without_capped_cpis (
+    id TEXT PRIMARY KEY NOT NULL CHECK (length(trim(id)) > 0),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    clock FLOAT NOT NULL CHECK (clock > 0),
+    clock_unit TEXT NOT NULL DEFAULT 's' CHECK (clock_unit IN ('ps', 'ns', 'us', 'ms', 's', 'hz', 'khz', 'mhz', 'ghz')),
+    cpi_instruction_r FLOAT NOT NULL CHECK (cpi_instruction_r > 0),
+    cpi_instruction_i FLOAT NOT NULL CHECK (cpi_instruction_i > 0),
+    cpi_instruction_l FLOAT NOT NULL CHECK (cpi_instruction_l > 0),
+    cpi_instruction_s FLOAT NOT NULL CHECK (cpi_instruction_s > 0),
+    cpi_instruction_b FLOAT NOT NULL CHECK (cpi_instruction_b > 0),
+    cpi_instruction_u FLOAT NOT NULL CHECK (cpi_instruction_u > 0),
+    cpi_instruction_j FLOAT NOT NULL CHECK (cpi_instruction_j > 0),
+    cpi_map TEXT CHECK (cpi_map IS NULL OR json_valid(cpi_map)),
+    energy_map TEXT CHECK (energy_map IS NULL OR json_valid(energy_map)),
+    static_power FLOAT NOT NULL DEFAULT 0 CHECK (static_power >= 0)
+);
+
+INSERT INTO organizations_without_capped_cpis (
+    id, created_at, clock, clock_unit,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map, energy_map, static_power
+)
+SELECT
+    id, created_at, clock, clock_unit,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map, energy_map, static_power
+FROM organizations;
+
+DROP TABLE organizations;
+ALTER TABLE organizations_without_capped_cpis RENAME TO organizations;
@@ -0,0 +1,35 @@
+-- Your SQL goes here
+-- The CPIs the service accepts (organization::MAX_CPI). SQLite checks cannot look inside cpi_map,
+-- the service still checks its values. Rows saved with a larger CPI keep the largest one, which
+-- the simulations already used
+CREATE TABLE organizations_with_capped_cpis (
+    id TEXT PRIMARY KEY NOT NULL CHECK (length(trim(id)) > 0),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    clock FLOAT NOT NULL CHECK (clock > 0),
+    clock_unit TEXT NOT NULL DEFAULT 's' CHECK (clock_unit IN ('ps', 'ns', 'us', 'ms', 's', 'hz', 'khz', 'mhz', 'ghz')),
+    cpi_instruction_r FLOAT NOT NULL CHECK (cpi_instruction_r > 0 AND cpi_instruction_r <= 1000),
+    cpi_instruction_i FLOAT NOT NULL CHECK (cpi_instruction_i > 0 AND cpi_instruction_i <= 1000),
+    cpi_instruction_l FLOAT NOT NULL CHECK (cpi_instruction_l > 0 AND cpi_instruction_l <= 1000),
+    cpi_instruction_s FLOAT NOT NULL CHECK (cpi_instruction_s > 0 AND cpi_instruction_s <= 1000),
+    cpi_instruction_b FLOAT NOT NULL CHECK (cpi_instruction_b > 0 AND cpi_instruction_b <= 1000),
+    cpi_instruction_u FLOAT NOT NULL CHECK (cpi_instruction_u > 0 AND cpi_instruction_u <= 1000),
+    cpi_instruction_j FLOAT NOT NULL CHECK (cpi_instruction_j > 0 AND cpi_instruction_j <= 1000),
+    cpi_map TEXT CHECK (cpi_map IS NULL OR json_valid(cpi_map)),
+    energy_map TEXT CHECK (energy_map IS NULL OR json_valid(energy_map)),
+    static_power FLOAT NOT NULL DEFAULT 0 CHECK (static_power >= 0)
+);
+
+INSERT INTO organizations_with_capped_cpis (
+    id, created_at, clock, clock_unit,
+    cpi_instruction_r, cpi_instruction_i, cpi_instruction_l, cpi_instruction_s,
+    cpi_instruction_b, cpi_instruction_u, cpi_instruction_j, cpi_map, energy_map, static_power
+)
+SELECT
+    id, created_at, clock, clock_unit,
+    min(cpi_instruction_r, 1000), min(cpi_instruction_i, 1000), min(cpi_instruction_l, 1000),
+    min(cpi_instruction_s, 1000), min(cpi_instruction_b, 1000), min(cpi_instruction_u, 1000),
+    min(cpi_instruction_j, 1000), cpi_map, energy_map, static_power
+FROM organizations;
+
+DROP TABLE organizations;
+ALTER TABLE organizations_with_capped_cpis RENAME TO organizations;
@@ -0,0 +1,211 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::de::DeserializeOwned;
+
+use riscv_engine::{
+    organization::{Organization, OrganizationDefinition},
+    performance_calculator::design_space::{self, DesignPoint, SweepValues},
+    program::{Dump, Program},
+};
+
+// Reads an organization from a .toml or .json file, with the same fields as the organizations API
+pub fn load_organization(path: &Path) -> Result<Organization, String> {
+    let organization: OrganizationDefinition = parse_file(path, "organization")?;
+
+    let errors = organization.validate();
+    if !errors.is_empty() {
+        return Err(format_field_errors(path, errors));
+    }
+
+    Ok(Organization::from(organization))
+}
+
+// Reads a bin dump (binary or hexadecimal text), its id is the filename without the extension
+pub fn load_program(path: &Path) -> Result<Program, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+
+    let id = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Dump::parse(&content)
+        .and_then(|dump| Program::from_text(&id, &dump.text))
+        .map_err(|errors| format_field_errors(path, errors))
+}
+
+// Reads the bin dumps of a suite with their weights, every weight being 1 when none is given
+pub fn load_suite(bin_files: &[PathBuf], weights: &[f32]) -> Result<Vec<(Program, f32)>, String> {
+    let mut errors = Vec::new();
+    if !weights.is_empty() && weights.len() != bin_files.len() {
+        errors.push((
+            "--weights".to_string(),
+            "must have one weight per bin file".to_string(),
+        ));
+    }
+    if weights
+        .iter()
+        .any(|weight| !(weight.is_finite() && *weight > 0.0))
+    {
+        errors.push((
+            "--weights".to_string(),
+            "must be greater than zero".to_string(),
+        ));
+    }
+    check_arguments(errors)?;
+
+    bin_files
+        .iter()
+        .enumerate()
+        .map(|(index, bin_file)| {
+            let weight = weights.get(index).copied().unwrap_or(1.0);
+            load_program(bin_file).map(|program| (program, weight))
+        })
+        .collect()
+}
+
+// Reads the sweep parameters from a .toml or .json file, the same map as the parameters of the
+// sweep API, and expands them into design points
+pub fn load_design_points(path: &Path) -> Result<Vec<DesignPoint>, String> {
+    let parameters: BTreeMap<String, SweepValues> = parse_file(path, "sweep parameters")?;
+
+    design_space::design_points(&parameters).map_err(|errors| {
+        let errors = errors
+            .into_iter()
+            .map(|(name, message)| match name.is_empty() {
+                true => ("parameters".to_string(), message),
+                false => (name, message),
+            })
+            .collect();
+        format_field_errors(path, errors)
+    })
+}
+
+// Same checks as the API, errors come as (flag, message)
+pub fn check_arguments(errors: Vec<(String, String)>) -> Result<(), String> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = "Invalid arguments:".to_string();
+    for (flag, error) in errors {
+        message.push_str(&format!("\n  {}: {}", flag, error));
+    }
+
+    Err(message)
+}
+
+// Same as check_arguments, for engine checks that name the API field, e.g. memory_ports
+pub fn check_fields(errors: Vec<(String, String)>) -> Result<(), String> {
+    check_arguments(
+        errors
+            .into_iter()
+            .map(|(field, message)| (format!("--{}", field.replace('_', "-")), message))
+            .collect(),
+    )
+}
+
+fn parse_file<T: DeserializeOwned>(path: &Path, what: &str) -> Result<T, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+
+    match extension(path).as_str() {
+        "toml" => toml::from_str(&content).map_err(|err| err.to_string()),
+        "json" => serde_json::from_str(&content).map_err(|err| err.to_string()),
+        _ => Err("expected a .toml or .json file".to_string()),
+    }
+    .map_err(|err| format!("Invalid {} {}: {}", what, path.display(), err))
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+fn format_field_errors(path: &Path, errors: Vec<(String, String)>) -> String {
+    let mut message = format!("Invalid {}:", path.display());
+    for (field, error) in errors {
+        message.push_str(&format!("\n  {}: {}", field, error));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Files of the repository root, shared with the README examples
+    fn repo_file(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(path)
+    }
+
+    #[test]
+    fn organizations_load_from_toml_and_json() {
+        let a = load_organization(&repo_file("riscv_organizations/organization_a.toml")).unwrap();
+        assert_eq!(a.id, "A");
+        assert!((a.clock_period - 1e-9).abs() < 1e-15);
+        assert_eq!(a.cpi_map["mul"], 3.0);
+
+        let b = load_organization(&repo_file("riscv_organizations/organization_b.json")).unwrap();
+        assert_eq!(b.id, "B");
+        assert!((b.clock_period - 1.5e-9).abs() < 1e-15);
+        assert_eq!(b.cpi_instruction_l, 2.0);
+    }
+
+    #[test]
+    fn other_extensions_are_rejected() {
+        let path = repo_file("riscv_bin_dump/fatorial.txt");
+
+        assert_eq!(
+            load_organization(&path).unwrap_err(),
+            format!(
+                "Invalid organization {}: expected a .toml or .json file",
+                path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn fields_become_flags() {
+        assert_eq!(
+            check_fields(vec![(
+                "memory_ports".to_string(),
+                "must be at least 1".to_string()
+            )])
+            .unwrap_err(),
+            "Invalid arguments:\n  --memory-ports: must be at least 1"
+        );
+        assert!(check_fields(Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn suite_weights_are_checked() {
+        let bin_files = [
+            repo_file("riscv_bin_dump/fatorial.txt"),
+            repo_file("riscv_bin_dump/laco5x.txt"),
+        ];
+
+        assert_eq!(
+            load_suite(&bin_files, &[1.0]).unwrap_err(),
+            "Invalid arguments:\n  --weights: must have one weight per bin file"
+        );
+        assert_eq!(
+            load_suite(&bin_files, &[1.0, 0.0]).unwrap_err(),
+            "Invalid arguments:\n  --weights: must be greater than zero"
+        );
+
+        let suite = load_suite(&bin_files, &[]).unwrap();
+        let ids: Vec<(&str, f32)> = suite
+            .iter()
+            .map(|(program, weight)| (program.id.as_str(), *weight))
+            .collect();
+        assert_eq!(ids, vec![("fatorial", 1.0), ("laco5x", 1.0)]);
+    }
+}
@@ -0,0 +1,531 @@
+pub mod input;
+pub mod output;
+
+use std::{fs, path::PathBuf};
+
+use clap::{Parser, Subcommand};
+use serde::{de::DeserializeOwned, Serialize};
+
+use riscv_engine::{
+    i18n::Locale,
+    performance_calculator::{
+        design_space::{CostMetric, SweepCalculator},
+        loop_unrolling::{self, LoopUnrollingCalculator, DEFAULT_UNROLL_FACTOR},
+        memory_hierarchy::{CacheCalculator, CacheConfig, Replacement, WritePolicy},
+        monocycle_calculator, pipeline_calculator,
+        suite_calculator::{MonocycleSuiteCalculator, PipelineSuiteCalculator},
+        superscalar::{IssueRules, SuperscalarCalculator},
+        tomasulo::{
+            TomasuloCalculator, TomasuloConfig, DEFAULT_CDB_WIDTH, DEFAULT_FUNCTIONAL_UNITS,
+            DEFAULT_RESERVATION_STATIONS, DEFAULT_ROB_SIZE,
+        },
+    },
+    program::Program,
+    report::{self, AnalysisReport, ReportFormat},
+};
+
+use self::output::{OutputFormat, Report};
+
+// riscv-perf: runs the calculators on local files, without the HTTP server and the database
+#[derive(Parser)]
+#[command(
+    name = "riscv-perf",
+    version,
+    about = "Runs the RISC-V performance calculators on local organization and bin files"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Output format
+    #[arg(short, long, value_enum, global = true, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+
+    /// Language of the result messages: en or pt-BR
+    #[arg(long, global = true, default_value = "pt-BR", value_parser = parse_locale)]
+    pub lang: Locale,
+}
+
+fn parse_locale(tag: &str) -> Result<Locale, String> {
+    Locale::parse(tag).ok_or_else(|| format!("unsupported language {}, use en or pt-BR", tag))
+}
+
+// Engine enums by their API name, e.g. "write_back" or "energy_delay_product"
+fn parse_name<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|_| format!("unknown value {}", name))
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Compares two monocycle organizations on one or more bin files
+    Monocycle {
+        /// Organization A (.toml or .json)
+        organization_a: PathBuf,
+        /// Organization B (.toml or .json)
+        organization_b: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+    },
+    /// Compares the pipeline conflict techniques of one organization on one or more bin files
+    Pipeline {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Writes the program produced by each technique to this directory
+        #[arg(long)]
+        pipeline_files: Option<PathBuf>,
+    },
+    /// Cycles per iteration of every counted loop, before and after unrolling it
+    LoopUnrolling {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Copies of the loop body, between 2 and 16
+        #[arg(long, default_value_t = DEFAULT_UNROLL_FACTOR)]
+        factor: usize,
+    },
+    /// Issue log, IPC and unused issue slots of an in-order superscalar pipeline
+    Superscalar {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Instructions issued per cycle, between 1 and 8
+        #[arg(long, default_value_t = IssueRules::default().width)]
+        width: usize,
+        /// Loads and stores issued per cycle
+        #[arg(long, default_value_t = IssueRules::default().memory_ports)]
+        memory_ports: usize,
+        /// Branches and jumps issued per cycle
+        #[arg(long, default_value_t = IssueRules::default().branch_units)]
+        branch_units: usize,
+        /// Waits for results to be written back instead of forwarding them
+        #[arg(long)]
+        no_forwarding: bool,
+    },
+    /// Cycles of each instruction on a Tomasulo pipeline with a reorder buffer
+    Tomasulo {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Reservation stations of each unit kind
+        #[arg(long, default_value_t = DEFAULT_RESERVATION_STATIONS)]
+        reservation_stations: usize,
+        /// Functional units of each unit kind
+        #[arg(long, default_value_t = DEFAULT_FUNCTIONAL_UNITS)]
+        functional_units: usize,
+        /// Reorder buffer entries
+        #[arg(long, default_value_t = DEFAULT_ROB_SIZE)]
+        rob_size: usize,
+        /// Results broadcast per cycle
+        #[arg(long, default_value_t = DEFAULT_CDB_WIDTH)]
+        cdb_width: usize,
+    },
+    /// Hit rates and AMAT of the L1 instruction and data caches over the executed trace
+    Cache {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Instruction cache size in bytes
+        #[arg(long, default_value_t = CacheConfig::default().size)]
+        icache_size: usize,
+        /// Instruction cache block size in bytes
+        #[arg(long, default_value_t = CacheConfig::default().block_size)]
+        icache_block_size: usize,
+        /// Instruction cache ways per set
+        #[arg(long, default_value_t = CacheConfig::default().associativity)]
+        icache_associativity: usize,
+        /// lru, fifo or random
+        #[arg(long, default_value = "lru", value_parser = parse_name::<Replacement>)]
+        icache_replacement: Replacement,
+        /// Instruction cache miss penalty in cycles
+        #[arg(long, default_value_t = CacheConfig::default().miss_penalty)]
+        icache_miss_penalty: usize,
+        /// Data cache size in bytes
+        #[arg(long, default_value_t = CacheConfig::default().size)]
+        dcache_size: usize,
+        /// Data cache block size in bytes
+        #[arg(long, default_value_t = CacheConfig::default().block_size)]
+        dcache_block_size: usize,
+        /// Data cache ways per set
+        #[arg(long, default_value_t = CacheConfig::default().associativity)]
+        dcache_associativity: usize,
+        /// lru, fifo or random
+        #[arg(long, default_value = "lru", value_parser = parse_name::<Replacement>)]
+        dcache_replacement: Replacement,
+        /// write_back or write_through
+        #[arg(long, default_value = "write_back", value_parser = parse_name::<WritePolicy>)]
+        dcache_write_policy: WritePolicy,
+        /// Data cache miss penalty in cycles
+        #[arg(long, default_value_t = CacheConfig::default().miss_penalty)]
+        dcache_miss_penalty: usize,
+    },
+    /// Execution time and energy of every combination of the parameter values, with the Pareto
+    /// front. Several bin files are evaluated as a suite
+    Sweep {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Parameter values (.toml or .json), the same map as the parameters of the sweep API
+        parameters: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Weight of each bin file in the suite, 1 by default
+        #[arg(long, value_delimiter = ',')]
+        weights: Vec<f32>,
+        /// Name of the suite of bin files
+        #[arg(long, default_value = "suite")]
+        name: String,
+        /// energy, energy_delay_product or average_power
+        #[arg(long, default_value = "energy", value_parser = parse_name::<CostMetric>)]
+        cost_metric: CostMetric,
+    },
+    /// Weighted means of the monocycle comparison of two organizations over a suite of bin files
+    MonocycleSuite {
+        /// Organization A (.toml or .json)
+        organization_a: PathBuf,
+        /// Organization B (.toml or .json)
+        organization_b: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Weight of each bin file in the suite, 1 by default
+        #[arg(long, value_delimiter = ',')]
+        weights: Vec<f32>,
+        /// Name of the suite
+        #[arg(long, default_value = "suite")]
+        name: String,
+    },
+    /// Weighted means of the pipeline techniques of one organization over a suite of bin files
+    PipelineSuite {
+        /// Organization (.toml or .json)
+        organization: PathBuf,
+        /// Bin dumps, in binary or hexadecimal text
+        #[arg(required = true)]
+        bin_files: Vec<PathBuf>,
+        /// Weight of each bin file in the suite, 1 by default
+        #[arg(long, value_delimiter = ',')]
+        weights: Vec<f32>,
+        /// Name of the suite
+        #[arg(long, default_value = "suite")]
+        name: String,
+    },
+}
+
+// Returns what should be printed to stdout
+pub fn run(cli: Cli) -> Result<String, String> {
+    match cli.command {
+        Command::Monocycle {
+            organization_a,
+            organization_b,
+            bin_files,
+        } => {
+            let organization_a = input::load_organization(&organization_a)?;
+            let organization_b = input::load_organization(&organization_b)?;
+
+            let mut results = Vec::new();
+            let mut analyses = Vec::new();
+            for bin_file in bin_files {
+                let program = input::load_program(&bin_file)?;
+                let mut performance = monocycle_calculator::PerformanceCalculator::monocycle_from(
+                    &organization_a,
+                    &organization_b,
+                    &program,
+                );
+                performance.localize(cli.lang);
+
+                analyses.push(report::monocycle::report(
+                    &organization_a,
+                    &organization_b,
+                    &program,
+                    &performance,
+                ));
+                results.push(performance);
+            }
+
+            render(
+                cli.format,
+                &results,
+                output::monocycle_report,
+                Some(analysis("Monocycle analysis", analyses)),
+            )
+        }
+        Command::Pipeline {
+            organization,
+            bin_files,
+            pipeline_files,
+        } => {
+            let organization = input::load_organization(&organization)?;
+
+            if let Some(pipeline_files) = &pipeline_files {
+                fs::create_dir_all(pipeline_files).map_err(|err| {
+                    format!("Failed to create {}: {}", pipeline_files.display(), err)
+                })?;
+            }
+
+            let mut results = Vec::new();
+            let mut analyses = Vec::new();
+            for bin_file in bin_files {
+                let program = input::load_program(&bin_file)?;
+                let techniques = pipeline_calculator::techniques(&program.instructions);
+
+                if let Some(pipeline_files) = &pipeline_files {
+                    pipeline_calculator::write_technique_files(pipeline_files, &techniques)
+                        .map_err(|err| {
+                            format!("Failed to write {}: {}", pipeline_files.display(), err)
+                        })?;
+                }
+
+                let performance = pipeline_calculator::PerformanceCalculator::from_techniques(
+                    &organization,
+                    &program,
+                    &techniques,
+                );
+
+                analyses.push(report::pipeline::report(
+                    &organization,
+                    &program,
+                    &techniques,
+                    &performance,
+                ));
+                results.push(performance);
+            }
+
+            render(
+                cli.format,
+                &results,
+                output::pipeline_report,
+                Some(analysis("Pipeline analysis", analyses)),
+            )
+        }
+        Command::LoopUnrolling {
+            organization,
+            bin_files,
+            factor,
+        } => {
+            if let Err(message) = loop_unrolling::check_factor(factor) {
+                input::check_arguments(vec![("--factor".to_string(), message)])?;
+            }
+
+            let organization = input::load_organization(&organization)?;
+            let results = each_program(&bin_files, |program| {
+                LoopUnrollingCalculator::unrolling_from(&organization, program, factor)
+            })?;
+
+            render(cli.format, &results, output::loop_unrolling_report, None)
+        }
+        Command::Superscalar {
+            organization,
+            bin_files,
+            width,
+            memory_ports,
+            branch_units,
+            no_forwarding,
+        } => {
+            let rules = IssueRules {
+                width,
+                memory_ports,
+                branch_units,
+                forwarding: !no_forwarding,
+            };
+            input::check_fields(rules.check())?;
+
+            let organization = input::load_organization(&organization)?;
+            let results = each_program(&bin_files, |program| {
+                SuperscalarCalculator::superscalar_from(&organization, program, rules)
+            })?;
+
+            render(cli.format, &results, output::superscalar_report, None)
+        }
+        Command::Tomasulo {
+            organization,
+            bin_files,
+            reservation_stations,
+            functional_units,
+            rob_size,
+            cdb_width,
+        } => {
+            let config = TomasuloConfig::uniform(
+                reservation_stations,
+                functional_units,
+                rob_size,
+                cdb_width,
+            );
+            input::check_fields(config.check())?;
+
+            let organization = input::load_organization(&organization)?;
+            let results = each_program(&bin_files, |program| {
+                TomasuloCalculator::tomasulo_from(&organization, program, config.clone())
+            })?;
+
+            render(cli.format, &results, output::tomasulo_report, None)
+        }
+        Command::Cache {
+            organization,
+            bin_files,
+            icache_size,
+            icache_block_size,
+            icache_associativity,
+            icache_replacement,
+            icache_miss_penalty,
+            dcache_size,
+            dcache_block_size,
+            dcache_associativity,
+            dcache_replacement,
+            dcache_write_policy,
+            dcache_miss_penalty,
+        } => {
+            let instruction_cache = CacheConfig {
+                size: icache_size,
+                block_size: icache_block_size,
+                associativity: icache_associativity,
+                replacement: icache_replacement,
+                // Never written
+                write_policy: CacheConfig::default().write_policy,
+                miss_penalty: icache_miss_penalty,
+            };
+            let data_cache = CacheConfig {
+                size: dcache_size,
+                block_size: dcache_block_size,
+                associativity: dcache_associativity,
+                replacement: dcache_replacement,
+                write_policy: dcache_write_policy,
+                miss_penalty: dcache_miss_penalty,
+            };
+
+            input::check_arguments(
+                [("icache", &instruction_cache), ("dcache", &data_cache)]
+                    .iter()
+                    .flat_map(|(prefix, config)| {
+                        config.validate().into_iter().map(move |(field, message)| {
+                            (format!("--{}-{}", prefix, field.replace('_', "-")), message)
+                        })
+                    })
+                    .collect(),
+            )?;
+
+            let organization = input::load_organization(&organization)?;
+            let results = each_program(&bin_files, |program| {
+                CacheCalculator::cache_from(&organization, program, instruction_cache, data_cache)
+            })?;
+
+            render(cli.format, &results, output::cache_report, None)
+        }
+        Command::Sweep {
+            organization,
+            parameters,
+            bin_files,
+            weights,
+            name,
+            cost_metric,
+        } => {
+            let points = input::load_design_points(&parameters)?;
+            let organization = input::load_organization(&organization)?;
+            let programs = input::load_suite(&bin_files, &weights)?;
+            // One bin file is named after itself, like bin_file_name in the API
+            let workload_name = match programs.as_slice() {
+                [(program, _)] => program.id.clone(),
+                _ => name,
+            };
+
+            let sweep = SweepCalculator::sweep_from(
+                &organization,
+                &workload_name,
+                &programs,
+                &points,
+                cost_metric,
+            );
+
+            render(cli.format, &[sweep], output::sweep_report, None)
+        }
+        Command::MonocycleSuite {
+            organization_a,
+            organization_b,
+            bin_files,
+            weights,
+            name,
+        } => {
+            let organization_a = input::load_organization(&organization_a)?;
+            let organization_b = input::load_organization(&organization_b)?;
+            let programs = input::load_suite(&bin_files, &weights)?;
+
+            let mut suite = MonocycleSuiteCalculator::monocycle_suite_from(
+                &organization_a,
+                &organization_b,
+                &name,
+                &programs,
+            )
+            .ok_or_else(|| format!("Suite {} has no bin files", name))?;
+            suite.localize(cli.lang);
+
+            render(cli.format, &[suite], output::monocycle_suite_report, None)
+        }
+        Command::PipelineSuite {
+            organization,
+            bin_files,
+            weights,
+            name,
+        } => {
+            let organization = input::load_organization(&organization)?;
+            let programs = input::load_suite(&bin_files, &weights)?;
+
+            let suite =
+                PipelineSuiteCalculator::pipeline_suite_from(&organization, &name, &programs)
+                    .ok_or_else(|| format!("Suite {} has no bin files", name))?;
+
+            render(cli.format, &[suite], output::pipeline_suite_report, None)
+        }
+    }
+}
+
+// Loads every bin file and runs one calculator on it
+fn each_program<T>(
+    bin_files: &[PathBuf],
+    calculate: impl Fn(&Program) -> T,
+) -> Result<Vec<T>, String> {
+    bin_files
+        .iter()
+        .map(|bin_file| input::load_program(bin_file).map(|program| calculate(&program)))
+        .collect()
+}
+
+fn render<T: Serialize>(
+    format: OutputFormat,
+    results: &[T],
+    report: fn(&[T]) -> Report,
+    analysis: Option<AnalysisReport>,
+) -> Result<String, String> {
+    let report_format = match format {
+        OutputFormat::Table => return Ok(report(results).to_table()),
+        OutputFormat::Json => {
+            return serde_json::to_string_pretty(results).map_err(|err| err.to_string())
+        }
+        OutputFormat::Csv => return report(results).to_csv(),
+        OutputFormat::Markdown => ReportFormat::Markdown,
+        OutputFormat::Html => ReportFormat::Html,
+    };
+
+    // Only monocycle and pipeline have a written analysis
+    analysis
+        .map(|analysis| analysis.render(report_format))
+        .ok_or_else(|| "markdown and html are only written for monocycle and pipeline".to_string())
+}
+
+// One analysis per bin file, nested under title when there are several
+fn analysis(title: &str, mut analyses: Vec<AnalysisReport>) -> AnalysisReport {
+    match analyses.len() {
+        1 => analyses.remove(0),
+        _ => AnalysisReport::combine(title, analyses),
+    }
+}
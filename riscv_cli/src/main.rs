@@ -0,0 +1,22 @@
+use std::{
+    io::{self, Write},
+    process,
+};
+
+use clap::Parser;
+use riscv_cli::{run, Cli};
+
+fn main() {
+    env_logger::init();
+
+    match run(Cli::parse()) {
+        // Ignores write errors, so piping to `head` doesn't panic on a closed stdout
+        Ok(output) => {
+            let _ = writeln!(io::stdout(), "{}", output.trim_end());
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,552 @@
+use clap::ValueEnum;
+
+use riscv_engine::{
+    performance_calculator::{
+        design_space::SweepCalculator,
+        loop_unrolling::LoopUnrollingCalculator,
+        memory_hierarchy::CacheCalculator,
+        monocycle_calculator, pipeline_calculator,
+        suite_calculator::{MonocycleSuiteCalculator, PipelineSuiteCalculator, WeightedMeans},
+        superscalar::SuperscalarCalculator,
+        tomasulo::TomasuloCalculator,
+    },
+    units,
+};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+    // Written analysis reports, see riscv_engine::report
+    Markdown,
+    Html,
+}
+
+// Flat view of a calculator result, shared by the table and CSV outputs
+pub struct Report {
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+    // Lines printed under the table, e.g. which organization is faster
+    pub notes: Vec<String>,
+}
+
+impl Report {
+    pub fn new(headers: Vec<&'static str>) -> Report {
+        Report {
+            headers,
+            rows: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|header| header.len()).collect();
+        for row in self.rows.iter() {
+            for (index, cell) in row.iter().enumerate() {
+                widths[index] = widths[index].max(cell.len());
+            }
+        }
+
+        let format_row = |cells: Vec<&str>| {
+            cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let separators: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+        let mut lines = vec![format_row(self.headers.clone())];
+        lines.push(format_row(separators.iter().map(String::as_str).collect()));
+        for row in self.rows.iter() {
+            lines.push(format_row(row.iter().map(|cell| cell.as_str()).collect()));
+        }
+
+        if !self.notes.is_empty() {
+            lines.push(String::new());
+            lines.extend(self.notes.iter().cloned());
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn to_csv(&self) -> Result<String, String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        writer
+            .write_record(&self.headers)
+            .map_err(|err| err.to_string())?;
+        for row in self.rows.iter() {
+            writer.write_record(row).map_err(|err| err.to_string())?;
+        }
+
+        let bytes = writer.into_inner().map_err(|err| err.to_string())?;
+        String::from_utf8(bytes).map_err(|err| err.to_string())
+    }
+}
+
+pub fn monocycle_report(results: &[monocycle_calculator::PerformanceCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "bin_file",
+        "organization",
+        "clock",
+        "total_cycles",
+        "average_cpi",
+        "execution_time",
+        "mips",
+        "energy",
+        "average_power",
+    ]);
+
+    for performance in results {
+        let information = &performance.basic_information;
+        let result = &performance.result;
+
+        report.rows.push(vec![
+            information.bin_file_name.clone(),
+            information.organization_a_name.clone(),
+            information.organization_a_clock.clone(),
+            result.total_cicles_organization_a.to_string(),
+            result.average_cpi_organization_a.to_string(),
+            result.formatted_execution_time_organization_a.clone(),
+            result.mips_organization_a.to_string(),
+            result.energy_organization_a.formatted_total_energy.clone(),
+            result.energy_organization_a.formatted_average_power.clone(),
+        ]);
+        report.rows.push(vec![
+            information.bin_file_name.clone(),
+            information.organization_b_name.clone(),
+            information.organization_b_clock.clone(),
+            result.total_cicles_organization_b.to_string(),
+            result.average_cpi_organization_b.to_string(),
+            result.formatted_execution_time_organization_b.clone(),
+            result.mips_organization_b.to_string(),
+            result.energy_organization_b.formatted_total_energy.clone(),
+            result.energy_organization_b.formatted_average_power.clone(),
+        ]);
+
+        report.notes.push(format!(
+            "{}: {}",
+            information.bin_file_name, result.best_performance
+        ));
+    }
+
+    report
+}
+
+pub fn pipeline_report(results: &[pipeline_calculator::PerformanceCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "bin_file",
+        "technique",
+        "total_cycles",
+        "cycles_difference",
+        "average_cpi",
+        "execution_time",
+        "mips",
+        "performance",
+        "energy",
+        "average_power",
+    ]);
+
+    for performance in results {
+        let information = &performance.basic_information;
+
+        for technique in performance.results.iter() {
+            report.rows.push(vec![
+                information.bin_file_name.clone(),
+                technique.technique_name.clone(),
+                technique.total_cicles.to_string(),
+                technique.cicles_diference.to_string(),
+                technique.average_cpi.to_string(),
+                technique.formatted_execution_time.clone(),
+                technique.mips.to_string(),
+                technique.performance.to_string(),
+                technique.energy.formatted_total_energy.clone(),
+                technique.energy.formatted_average_power.clone(),
+            ]);
+        }
+
+        report.notes.push(format!(
+            "{} on {} ({}), fastest first: {}",
+            information.bin_file_name,
+            information.organization_name,
+            information.organization_clock,
+            information.best_performance.join(", ")
+        ));
+    }
+
+    report
+}
+
+pub fn loop_unrolling_report(results: &[LoopUnrollingCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "bin_file",
+        "loop",
+        "technique",
+        "cycles_per_iteration",
+        "unrolled_cycles_per_iteration",
+        "speedup",
+    ]);
+
+    for unrolling in results {
+        for unrolled_loop in unrolling.loops.iter() {
+            for technique in unrolled_loop.results.iter() {
+                report.rows.push(vec![
+                    unrolling.bin_file_name.clone(),
+                    format!("{}..{}", unrolled_loop.head, unrolled_loop.latch),
+                    technique.technique_name.clone(),
+                    technique.cycles_per_iteration.to_string(),
+                    technique.unrolled_cycles_per_iteration.to_string(),
+                    technique.speedup.to_string(),
+                ]);
+            }
+        }
+
+        report.notes.push(format!(
+            "{}: {} loops unrolled {} times, {} instructions after unrolling",
+            unrolling.bin_file_name,
+            unrolling.loops.len(),
+            unrolling.factor,
+            unrolling.unrolled_instructions
+        ));
+    }
+
+    report
+}
+
+pub fn superscalar_report(results: &[SuperscalarCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "bin_file",
+        "width",
+        "total_cycles",
+        "scalar_cycles",
+        "speedup",
+        "ipc",
+        "execution_time",
+        "mips",
+        "energy",
+        "average_power",
+    ]);
+
+    for superscalar in results {
+        report.rows.push(vec![
+            superscalar.bin_file_name.clone(),
+            superscalar.rules.width.to_string(),
+            superscalar.total_cycles.to_string(),
+            superscalar.scalar_cycles.to_string(),
+            superscalar.speedup.to_string(),
+            superscalar.ipc.to_string(),
+            superscalar.formatted_execution_time.clone(),
+            superscalar.mips.to_string(),
+            superscalar.energy.formatted_total_energy.clone(),
+            superscalar.energy.formatted_average_power.clone(),
+        ]);
+    }
+
+    report
+}
+
+pub fn tomasulo_report(results: &[TomasuloCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "bin_file",
+        "schedule",
+        "nops",
+        "total_cycles",
+        "tomasulo_speedup",
+    ]);
+
+    for tomasulo in results {
+        report.rows.push(vec![
+            tomasulo.bin_file_name.clone(),
+            "tomasulo".to_string(),
+            "0".to_string(),
+            tomasulo.total_cycles.to_string(),
+            "1".to_string(),
+        ]);
+        for technique in tomasulo.in_order.iter() {
+            report.rows.push(vec![
+                tomasulo.bin_file_name.clone(),
+                technique.technique_name.clone(),
+                technique.nops.to_string(),
+                technique.total_cycles.to_string(),
+                technique.speedup.to_string(),
+            ]);
+        }
+
+        report.notes.push(format!(
+            "{}: IPC {}, {}, {} MIPS, {}",
+            tomasulo.bin_file_name,
+            tomasulo.ipc,
+            tomasulo.formatted_execution_time,
+            tomasulo.mips,
+            tomasulo.energy.formatted_total_energy
+        ));
+    }
+
+    report
+}
+
+pub fn cache_report(results: &[CacheCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "bin_file",
+        "cache",
+        "accesses",
+        "hits",
+        "misses",
+        "hit_rate",
+        "amat",
+        "stall_cycles",
+    ]);
+
+    for cache in results {
+        for (name, stats) in [
+            ("instruction", &cache.instruction_cache),
+            ("data", &cache.data_cache),
+        ] {
+            report.rows.push(vec![
+                cache.bin_file_name.clone(),
+                name.to_string(),
+                stats.accesses.to_string(),
+                stats.hits.to_string(),
+                stats.misses.to_string(),
+                stats.hit_rate.to_string(),
+                stats.amat.to_string(),
+                stats.stall_cycles.to_string(),
+            ]);
+        }
+
+        report.notes.push(format!(
+            "{}: {} instructions run, CPI {} without misses and {} with them, {}, {}",
+            cache.bin_file_name,
+            cache.executed_instructions,
+            cache.base_cpi,
+            cache.cpi,
+            cache.formatted_execution_time,
+            cache.energy.formatted_total_energy
+        ));
+    }
+
+    report
+}
+
+pub fn sweep_report(results: &[SweepCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "workload",
+        "parameters",
+        "total_cycles",
+        "execution_time",
+        "energy",
+        "average_power",
+        "energy_delay_product",
+        "cost",
+        "pareto_optimal",
+    ]);
+
+    for sweep in results {
+        for result in sweep.results.iter() {
+            let parameters: Vec<String> = result
+                .parameters
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect();
+
+            report.rows.push(vec![
+                sweep.workload_name.clone(),
+                parameters.join(" "),
+                result.total_cycles.to_string(),
+                result.formatted_execution_time.clone(),
+                result.formatted_total_energy.clone(),
+                result.average_power.to_string(),
+                result.energy_delay_product.to_string(),
+                result.cost.to_string(),
+                result.pareto_optimal.to_string(),
+            ]);
+        }
+
+        report.notes.push(format!(
+            "{} on {}, Pareto front rows (fastest first): {}",
+            sweep.workload_name,
+            sweep.organization_name,
+            sweep
+                .pareto_front
+                .iter()
+                .map(|index| (index + 1).to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    report
+}
+
+fn means(means: &WeightedMeans, format: fn(f32) -> String) -> [String; 3] {
+    [
+        format(means.arithmetic),
+        format(means.harmonic),
+        format(means.geometric),
+    ]
+}
+
+pub fn monocycle_suite_report(results: &[MonocycleSuiteCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "suite",
+        "organization",
+        "mean",
+        "execution_time",
+        "average_cpi",
+    ]);
+
+    for suite in results {
+        let information = &suite.basic_information;
+        let aggregate = &suite.aggregate;
+
+        for (organization, execution_time, average_cpi) in [
+            (
+                &information.organization_a_name,
+                &aggregate.execution_time_organization_a,
+                &aggregate.average_cpi_organization_a,
+            ),
+            (
+                &information.organization_b_name,
+                &aggregate.execution_time_organization_b,
+                &aggregate.average_cpi_organization_b,
+            ),
+        ] {
+            let execution_times = means(execution_time, units::format_time);
+            let average_cpis = means(average_cpi, |cpi| cpi.to_string());
+
+            for (index, mean) in ["arithmetic", "harmonic", "geometric"].iter().enumerate() {
+                report.rows.push(vec![
+                    information.suite_name.clone(),
+                    organization.clone(),
+                    mean.to_string(),
+                    execution_times[index].clone(),
+                    average_cpis[index].clone(),
+                ]);
+            }
+        }
+
+        report.notes.push(format!(
+            "{} ({} programs): {}",
+            information.suite_name,
+            suite.programs.len(),
+            aggregate.best_performance
+        ));
+    }
+
+    report
+}
+
+pub fn pipeline_suite_report(results: &[PipelineSuiteCalculator]) -> Report {
+    let mut report = Report::new(vec![
+        "suite",
+        "technique",
+        "mean",
+        "execution_time",
+        "average_cpi",
+    ]);
+
+    for suite in results {
+        let information = &suite.basic_information;
+
+        for technique in suite.aggregate.iter() {
+            let execution_times = means(&technique.execution_time, units::format_time);
+            let average_cpis = means(&technique.average_cpi, |cpi| cpi.to_string());
+
+            for (index, mean) in ["arithmetic", "harmonic", "geometric"].iter().enumerate() {
+                report.rows.push(vec![
+                    information.suite_name.clone(),
+                    technique.technique_name.clone(),
+                    mean.to_string(),
+                    execution_times[index].clone(),
+                    average_cpis[index].clone(),
+                ]);
+            }
+        }
+
+        report.notes.push(format!(
+            "{} ({} programs) on {}, fastest first: {}",
+            information.suite_name,
+            suite.programs.len(),
+            information.organization_name,
+            information.best_performance.join(", ")
+        ));
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use riscv_engine::program::Program;
+
+    use super::*;
+    use crate::input;
+
+    fn pipeline_run() -> Report {
+        let organization = input::load_organization(
+            &Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../riscv_organizations/organization_b.json"),
+        )
+        .unwrap();
+        let program = Program::from_text(
+            "raw",
+            // lw x5, 0(x10); add x6, x5, x7
+            "00000000000001010010001010000011
+             00000000011100101000001100110011",
+        )
+        .unwrap();
+
+        pipeline_report(&[pipeline_calculator::PerformanceCalculator::pipeline_from(
+            &organization,
+            &program,
+        )])
+    }
+
+    #[test]
+    fn pipeline_csv_has_a_row_per_technique() {
+        let csv = pipeline_run().to_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "bin_file,technique,total_cycles,cycles_difference,average_cpi,execution_time,mips,\
+             performance,energy,average_power"
+        );
+        // B runs the load in 2 cycles and the add in 1, at 1.5 ns
+        assert_eq!(lines[1], "raw,original,3,0,1.5,4.5 ns,444.44446,1,0 J,0 W");
+        // Two NOPs between the load and the add without forwarding
+        assert_eq!(
+            lines[2],
+            "raw,only_nops,5,2,1.25,7.5 ns,533.3334,0.6,0 J,0 W"
+        );
+        assert_eq!(
+            lines.len(),
+            1 + pipeline_calculator::TECHNIQUE_NAMES.len() + 1
+        );
+    }
+
+    #[test]
+    fn tables_pad_the_columns_and_end_with_the_notes() {
+        let table = pipeline_run().to_table();
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert!(lines[0].starts_with("bin_file  technique                        total_cycles"));
+        assert!(lines[1].starts_with("--------  -------------------------------  ------------"));
+        assert!(lines[2].starts_with("raw       original                         3 "));
+        assert_eq!(
+            lines.last().unwrap(),
+            &"raw on B (1.5 ns (666.667 MHz)), fastest first: original, forwading_with_nops, \
+              forwading_and_reorder_with_nops, only_nops, reorder_with_only_nops, \
+              renaming_with_nops"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use riscv_engine::{
+    performance_calculator::data_hazard::{
+        read_registers, written_register, InstructionHazards, Scoreboard, HAZARD_WINDOW,
+    },
+    program::Program,
+    riscv_core::instruction::Instruction,
+};
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+const WINDOWS: [usize; 2] = [HAZARD_WINDOW, 16];
+
+// The bubble sort dump repeated until it has `size` instructions
+fn program(size: usize) -> Vec<Instruction> {
+    let dump = include_str!("../../riscv_bin_dump/vetor_decrescente_bubblesort.txt");
+    let program = Program::from_text("bubblesort", dump).expect("valid sample dump");
+
+    program
+        .instructions
+        .iter()
+        .copied()
+        .cycle()
+        .take(size)
+        .collect()
+}
+
+// Every instruction compared with each of the next `window` ones, as before the scoreboard
+fn pairwise(instructions: &[Instruction], window: usize) -> Vec<InstructionHazards> {
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, current)| {
+            let mut hazards = InstructionHazards::default();
+            let writes = written_register(current);
+            let reads = read_registers(current);
+
+            for (distance, next) in instructions[index + 1..].iter().take(window).enumerate() {
+                let nops = window - distance;
+                let next_writes = written_register(next);
+
+                if writes.is_some_and(|register| read_registers(next).contains(&register)) {
+                    hazards.raw = hazards.raw.max(nops);
+                }
+                if next_writes.is_some_and(|register| reads.contains(&register)) {
+                    hazards.war = hazards.war.max(nops);
+                }
+                if writes.is_some() && writes == next_writes {
+                    hazards.waw = hazards.waw.max(nops);
+                }
+            }
+
+            hazards
+        })
+        .collect()
+}
+
+fn hazard_detection(c: &mut Criterion) {
+    for window in WINDOWS {
+        let mut group = c.benchmark_group(format!("hazards/window {}", window));
+        group.sample_size(10);
+
+        for size in SIZES {
+            let instructions = program(size);
+            group.throughput(Throughput::Elements(size as u64));
+
+            group.bench_with_input(
+                BenchmarkId::new("scoreboard", size),
+                &instructions,
+                |b, instructions| b.iter(|| Scoreboard::scan(window, instructions)),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("pairwise", size),
+                &instructions,
+                |b, instructions| b.iter(|| pairwise(instructions, window)),
+            );
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, hazard_detection);
+criterion_main!(benches);
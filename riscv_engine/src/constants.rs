@@ -0,0 +1,2 @@
+// addi x0, x0, 0
+pub const NOP_WORD: u32 = 0b00000000000000000000000000010011;
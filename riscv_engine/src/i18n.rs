@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+// Languages of the result messages. Portuguese is the default, it was the only one before
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum Locale {
+    #[serde(rename = "en")]
+    En,
+    #[default]
+    #[serde(rename = "pt-BR")]
+    PtBr,
+}
+
+// Templates of one language, {winner}, {loser}, {speedup}, {a} and {b} are replaced by the values
+pub struct Catalog {
+    pub faster: &'static str,
+    pub equally_fast: &'static str,
+}
+
+const EN: Catalog = Catalog {
+    faster: "Organization {winner} is {speedup} times faster than organization {loser}",
+    equally_fast: "Organizations {a} and {b} are equally fast",
+};
+
+const PT_BR: Catalog = Catalog {
+    faster: "A organizacao {winner} eh {speedup} vezes mais rapida que a organizacao {loser}",
+    equally_fast: "As organizacoes {a} e {b} sao igualmente rapidas",
+};
+
+impl Locale {
+    // Language tags like "en", "en-US", "pt" or "pt-BR", case insensitive
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let tag = tag.trim().to_lowercase();
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+
+        match language {
+            "en" => Some(Locale::En),
+            "pt" => Some(Locale::PtBr),
+            _ => None,
+        }
+    }
+
+    // The supported language with the highest q value, e.g. "fr;q=0.9, en;q=0.8" -> En
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut languages: Vec<(&str, f32)> = header
+            .split(',')
+            .map(|language| {
+                let mut parts = language.split(';');
+                let tag = parts.next().unwrap_or_default().trim();
+                let quality = parts
+                    .find_map(|part| part.trim().strip_prefix("q="))
+                    .and_then(|quality| quality.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (tag, quality)
+            })
+            .collect();
+
+        // Stable, so languages with the same q value keep the client's order
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        languages
+            .into_iter()
+            .filter(|(_, quality)| *quality > 0.0)
+            .find_map(|(tag, _)| Locale::parse(tag))
+    }
+
+    pub fn catalog(self) -> &'static Catalog {
+        match self {
+            Locale::En => &EN,
+            Locale::PtBr => &PT_BR,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::PtBr => "pt-BR",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance_calculator::monocycle_calculator::{best_performance, SpeedupComparison};
+
+    #[test]
+    fn language_tags_and_accept_language() {
+        assert_eq!(Locale::parse("EN-us"), Some(Locale::En));
+        assert_eq!(Locale::parse("pt_BR"), Some(Locale::PtBr));
+        assert_eq!(Locale::parse("fr"), None);
+
+        assert_eq!(
+            Locale::from_accept_language("fr;q=0.9, en;q=0.8, pt-BR;q=0.85"),
+            Some(Locale::PtBr)
+        );
+        // Same q value: the client's order. q=0 means "not this one"
+        assert_eq!(Locale::from_accept_language("en, pt"), Some(Locale::En));
+        assert_eq!(
+            Locale::from_accept_language("en;q=0, pt"),
+            Some(Locale::PtBr)
+        );
+        assert_eq!(Locale::from_accept_language("fr, de"), None);
+
+        for locale in [Locale::En, Locale::PtBr] {
+            assert_eq!(Locale::parse(locale.tag()), Some(locale));
+        }
+    }
+
+    #[test]
+    fn best_performance_in_each_language() {
+        let faster = SpeedupComparison::from_execution_times("A", 1.0, "B", 2.0);
+        assert_eq!(
+            best_performance(&faster, Locale::En),
+            "Organization A is 2 times faster than organization B"
+        );
+        assert_eq!(
+            best_performance(&faster, Locale::PtBr),
+            "A organizacao A eh 2 vezes mais rapida que a organizacao B"
+        );
+
+        let tie = SpeedupComparison::from_execution_times("A", 1.0, "B", 1.0);
+        assert!(tie.equally_fast);
+        assert_eq!(
+            best_performance(&tie, Locale::En),
+            "Organizations A and B are equally fast"
+        );
+        assert_eq!(
+            best_performance(&tie, Locale::default()),
+            "As organizacoes A e B sao igualmente rapidas"
+        );
+    }
+}
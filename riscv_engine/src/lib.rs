@@ -0,0 +1,9 @@
+pub mod constants;
+pub mod i18n;
+pub mod organization;
+pub mod performance_calculator;
+pub mod pipeline_diagram;
+pub mod program;
+pub mod report;
+pub mod riscv_core;
+pub mod units;
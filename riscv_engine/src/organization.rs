@@ -0,0 +1,427 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::riscv_core::instruction::{Instruction, OpCodeType, MNEMONICS};
+
+// Class keys shared by several mnemonics, checked after the mnemonic itself
+pub const CPI_CLASS_KEYS: [&str; 4] = ["mul", "div", "branch_taken", "branch_not_taken"];
+// Energy keys of the instruction formats, checked after the mnemonic and the class
+pub const FORMAT_KEYS: [&str; 7] = ["r", "i", "l", "s", "b", "u", "j"];
+// Largest CPI accepted, keeps the cycle counts of the simulators far from overflowing
+pub const MAX_CPI: f32 = 1000.0;
+
+// Unit of OrganizationDefinition.clock: a period (ps, ns, us, ms, s) or a frequency (hz, khz, mhz,
+// ghz). Read through FromStr, so "MHz" and "mhz" are the same unit everywhere
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum ClockUnit {
+    Ps,
+    Ns,
+    Us,
+    Ms,
+    #[default]
+    S,
+    Hz,
+    KHz,
+    MHz,
+    GHz,
+}
+
+impl ClockUnit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClockUnit::Ps => "ps",
+            ClockUnit::Ns => "ns",
+            ClockUnit::Us => "us",
+            ClockUnit::Ms => "ms",
+            ClockUnit::S => "s",
+            ClockUnit::Hz => "hz",
+            ClockUnit::KHz => "khz",
+            ClockUnit::MHz => "mhz",
+            ClockUnit::GHz => "ghz",
+        }
+    }
+
+    // Converts a clock value in this unit to a period in seconds
+    pub fn to_period(self, value: f32) -> f32 {
+        let value = value as f64;
+        let period = match self {
+            ClockUnit::Ps => value * 1e-12,
+            ClockUnit::Ns => value * 1e-9,
+            ClockUnit::Us => value * 1e-6,
+            ClockUnit::Ms => value * 1e-3,
+            ClockUnit::S => value,
+            // TClock = 1 / FClock
+            ClockUnit::Hz => 1.0 / value,
+            ClockUnit::KHz => 1.0 / (value * 1e3),
+            ClockUnit::MHz => 1.0 / (value * 1e6),
+            ClockUnit::GHz => 1.0 / (value * 1e9),
+        };
+
+        period as f32
+    }
+}
+
+impl FromStr for ClockUnit {
+    type Err = String;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit.to_lowercase().as_str() {
+            "ps" => Ok(ClockUnit::Ps),
+            "ns" => Ok(ClockUnit::Ns),
+            "us" => Ok(ClockUnit::Us),
+            "ms" => Ok(ClockUnit::Ms),
+            "s" => Ok(ClockUnit::S),
+            "hz" => Ok(ClockUnit::Hz),
+            "khz" => Ok(ClockUnit::KHz),
+            "mhz" => Ok(ClockUnit::MHz),
+            "ghz" => Ok(ClockUnit::GHz),
+            _ => Err(format!("Unknown clock unit {}", unit)),
+        }
+    }
+}
+
+impl TryFrom<String> for ClockUnit {
+    type Error = String;
+
+    fn try_from(unit: String) -> Result<Self, Self::Error> {
+        unit.parse()
+    }
+}
+
+// Lets front-ends store the unit as text
+impl From<ClockUnit> for String {
+    fn from(unit: ClockUnit) -> String {
+        unit.as_str().to_string()
+    }
+}
+
+// An organization as people write it, in the organizations API or in a .toml or .json file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrganizationDefinition {
+    pub id: String,
+    pub clock: f32,
+    #[serde(default)]
+    pub clock_unit: ClockUnit,
+    pub cpi_instruction_r: f32,
+    pub cpi_instruction_i: f32,
+    pub cpi_instruction_l: f32,
+    pub cpi_instruction_s: f32,
+    pub cpi_instruction_b: f32,
+    pub cpi_instruction_u: f32,
+    pub cpi_instruction_j: f32,
+    #[serde(default)]
+    pub cpi_map: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub energy_map: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub static_power: f32,
+}
+
+impl OrganizationDefinition {
+    // Fields the calculators cannot use, as (field, message)
+    pub fn validate(&self) -> Vec<(String, String)> {
+        let mut errors = Vec::new();
+        let mut error = |field: &str, message: &str| {
+            errors.push((field.to_string(), message.to_string()));
+        };
+
+        if self.id.trim().is_empty() {
+            error("id", "must not be empty");
+        }
+
+        if !(self.clock.is_finite() && self.clock > 0.0) {
+            error("clock", "must be greater than zero");
+        }
+
+        let cpis = [
+            ("cpi_instruction_r", self.cpi_instruction_r),
+            ("cpi_instruction_i", self.cpi_instruction_i),
+            ("cpi_instruction_l", self.cpi_instruction_l),
+            ("cpi_instruction_s", self.cpi_instruction_s),
+            ("cpi_instruction_b", self.cpi_instruction_b),
+            ("cpi_instruction_u", self.cpi_instruction_u),
+            ("cpi_instruction_j", self.cpi_instruction_j),
+        ];
+        for (field, cpi) in cpis {
+            if let Err(message) = check_cpi(cpi) {
+                error(field, &message);
+            }
+        }
+
+        for (key, cpi) in &self.cpi_map {
+            let field = format!("cpi_map.{}", key);
+
+            if !MNEMONICS.contains(&key.as_str()) && !CPI_CLASS_KEYS.contains(&key.as_str()) {
+                error(&field, "must be a known mnemonic or instruction class");
+            }
+
+            if let Err(message) = check_cpi(*cpi) {
+                error(&field, &message);
+            }
+        }
+
+        for (key, energy) in &self.energy_map {
+            let field = format!("energy_map.{}", key);
+            let key = key.as_str();
+
+            if !MNEMONICS.contains(&key)
+                && !CPI_CLASS_KEYS.contains(&key)
+                && !FORMAT_KEYS.contains(&key)
+            {
+                error(
+                    &field,
+                    "must be a known mnemonic, instruction class or format",
+                );
+            }
+
+            if !(energy.is_finite() && *energy >= 0.0) {
+                error(&field, "must not be negative");
+            }
+        }
+
+        if !(self.static_power.is_finite() && self.static_power >= 0.0) {
+            error("static_power", "must not be negative");
+        }
+
+        errors
+    }
+}
+
+// Also checks the CPIs of a design space sweep
+pub fn check_cpi(cpi: f32) -> Result<(), String> {
+    if !(cpi.is_finite() && cpi > 0.0) {
+        return Err("must be greater than zero".to_string());
+    }
+    if cpi > MAX_CPI {
+        return Err(format!("must be at most {}", MAX_CPI));
+    }
+
+    Ok(())
+}
+
+// An organization as the calculators see it: the clock is already a period in seconds
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: String,
+    pub clock_period: f32,
+    pub cpi_instruction_r: f32,
+    pub cpi_instruction_i: f32,
+    pub cpi_instruction_l: f32,
+    pub cpi_instruction_s: f32,
+    pub cpi_instruction_b: f32,
+    pub cpi_instruction_u: f32,
+    pub cpi_instruction_j: f32,
+    // CPI overrides keyed by mnemonic (e.g. "mul", "lw", "jalr") or instruction class (CPI_CLASS_KEYS)
+    #[serde(default)]
+    pub cpi_map: BTreeMap<String, f32>,
+    // Dynamic energy in picojoules, keyed by mnemonic, class or format (FORMAT_KEYS). Instructions
+    // without a key cost nothing
+    #[serde(default)]
+    pub energy_map: BTreeMap<String, f32>,
+    // Watts drawn whatever runs
+    #[serde(default)]
+    pub static_power: f32,
+}
+
+fn format_key(opcode: OpCodeType) -> &'static str {
+    match opcode {
+        OpCodeType::R => "r",
+        OpCodeType::I => "i",
+        OpCodeType::L => "l",
+        OpCodeType::S => "s",
+        OpCodeType::B => "b",
+        OpCodeType::U => "u",
+        OpCodeType::J => "j",
+    }
+}
+
+pub fn instruction_class(inst: &Instruction) -> Option<&'static str> {
+    match inst.mnemonic() {
+        "mul" | "mulh" | "mulhsu" | "mulhu" => Some("mul"),
+        "div" | "divu" | "rem" | "remu" => Some("div"),
+        _ => match inst.opcode() {
+            // Static prediction: backward branches are taken, forward branches are not
+            OpCodeType::B if inst.is_backward_branch() => Some("branch_taken"),
+            OpCodeType::B => Some("branch_not_taken"),
+            _ => None,
+        },
+    }
+}
+
+impl From<OrganizationDefinition> for Organization {
+    fn from(definition: OrganizationDefinition) -> Organization {
+        Organization {
+            clock_period: definition.clock_unit.to_period(definition.clock),
+            id: definition.id,
+            cpi_instruction_r: definition.cpi_instruction_r,
+            cpi_instruction_i: definition.cpi_instruction_i,
+            cpi_instruction_l: definition.cpi_instruction_l,
+            cpi_instruction_s: definition.cpi_instruction_s,
+            cpi_instruction_b: definition.cpi_instruction_b,
+            cpi_instruction_u: definition.cpi_instruction_u,
+            cpi_instruction_j: definition.cpi_instruction_j,
+            cpi_map: definition.cpi_map,
+            energy_map: definition.energy_map,
+            static_power: definition.static_power,
+        }
+    }
+}
+
+impl Organization {
+    pub fn cpi_for(&self, inst: &Instruction) -> f32 {
+        let mnemonic_cpi = self.cpi_map.get(inst.mnemonic());
+        let class_cpi = instruction_class(inst).and_then(|class| self.cpi_map.get(class));
+
+        if let Some(cpi) = mnemonic_cpi.or(class_cpi) {
+            return *cpi;
+        }
+
+        // Fallback to the format-level defaults
+        match inst.opcode() {
+            OpCodeType::R => self.cpi_instruction_r,
+            OpCodeType::I => self.cpi_instruction_i,
+            OpCodeType::L => self.cpi_instruction_l,
+            OpCodeType::S => self.cpi_instruction_s,
+            OpCodeType::B => self.cpi_instruction_b,
+            OpCodeType::U => self.cpi_instruction_u,
+            OpCodeType::J => self.cpi_instruction_j,
+        }
+    }
+
+    // Joules spent by one execution of the instruction
+    pub fn energy_for(&self, inst: &Instruction) -> f32 {
+        let energy = self
+            .energy_map
+            .get(inst.mnemonic())
+            .or_else(|| instruction_class(inst).and_then(|class| self.energy_map.get(class)))
+            .or_else(|| self.energy_map.get(format_key(inst.opcode())))
+            .copied()
+            .unwrap_or(0.0);
+
+        energy * 1e-12
+    }
+
+    pub fn clock_frequency(&self) -> f32 {
+        1.0 / self.clock_period
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // cpis in format order (r, i, l, s, b, u, j), 1 ns clock and no maps
+    pub(crate) fn organization(id: &str, cpis: [f32; 7]) -> Organization {
+        let [r, i, l, s, b, u, j] = cpis;
+
+        Organization {
+            id: id.to_string(),
+            clock_period: 1e-9,
+            cpi_instruction_r: r,
+            cpi_instruction_i: i,
+            cpi_instruction_l: l,
+            cpi_instruction_s: s,
+            cpi_instruction_b: b,
+            cpi_instruction_u: u,
+            cpi_instruction_j: j,
+            cpi_map: BTreeMap::new(),
+            energy_map: BTreeMap::new(),
+            static_power: 0.0,
+        }
+    }
+
+    #[test]
+    fn cpi_follows_the_most_specific_key() {
+        let organization = Organization {
+            cpi_map: BTreeMap::from(
+                [("mul", 5.0), ("mulh", 7.0), ("branch_taken", 4.0)]
+                    .map(|(key, cpi)| (key.to_string(), cpi)),
+            ),
+            ..organization("cpi", [1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 1.0])
+        };
+        let cpi = |word: &str| organization.cpi_for(&Instruction::new(word).unwrap());
+
+        // mulh x6, x5, x5 has its own key, mulhu x6, x5, x5 takes the one of its class
+        assert_eq!(cpi("00000010010100101001001100110011"), 7.0);
+        assert_eq!(cpi("00000010010100101011001100110011"), 5.0);
+        // add x7, x6, x5 falls back to its format
+        assert_eq!(cpi("00000000010100110000001110110011"), 1.0);
+        // beq x0, x0, -8 is predicted taken, beq x0, x0, 8 is not and has no key
+        assert_eq!(cpi("11111110000000000000110011100011"), 4.0);
+        assert_eq!(cpi("00000000000000000000010001100011"), 2.0);
+    }
+
+    #[test]
+    fn clock_units_in_any_case() {
+        let spellings = [
+            ("ps", ClockUnit::Ps),
+            ("ns", ClockUnit::Ns),
+            ("us", ClockUnit::Us),
+            ("ms", ClockUnit::Ms),
+            ("s", ClockUnit::S),
+            ("Hz", ClockUnit::Hz),
+            ("kHz", ClockUnit::KHz),
+            ("MHz", ClockUnit::MHz),
+            ("GHz", ClockUnit::GHz),
+        ];
+
+        for (spelling, unit) in spellings {
+            for spelling in [spelling.to_string(), spelling.to_lowercase()] {
+                let json = format!("\"{}\"", spelling);
+                let toml = format!("clock_unit = \"{}\"", spelling);
+
+                assert_eq!(spelling.parse::<ClockUnit>(), Ok(unit));
+                assert_eq!(serde_json::from_str::<ClockUnit>(&json).unwrap(), unit);
+                assert_eq!(
+                    toml::from_str::<toml::Table>(&toml).unwrap()["clock_unit"]
+                        .clone()
+                        .try_into::<ClockUnit>()
+                        .unwrap(),
+                    unit
+                );
+            }
+            assert_eq!(
+                serde_json::to_string(&unit).unwrap(),
+                format!("\"{}\"", unit.as_str())
+            );
+        }
+        assert!(serde_json::from_str::<ClockUnit>("\"THz\"").is_err());
+    }
+
+    #[test]
+    fn cpis_must_be_positive_and_capped() {
+        let definition: OrganizationDefinition = serde_json::from_value(serde_json::json!({
+            "id": "capped",
+            "clock": 1.0,
+            "cpi_instruction_r": 1.0,
+            "cpi_instruction_i": 0.0,
+            "cpi_instruction_l": 1000.0,
+            "cpi_instruction_s": 1001.0,
+            "cpi_instruction_b": 1.0,
+            "cpi_instruction_u": 1.0,
+            "cpi_instruction_j": 1.0,
+            "cpi_map": { "mul": 1e30 }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            definition.validate(),
+            [
+                ("cpi_instruction_i", "must be greater than zero"),
+                ("cpi_instruction_s", "must be at most 1000"),
+                ("cpi_map.mul", "must be at most 1000"),
+            ]
+            .map(|(field, message)| (field.to_string(), message.to_string()))
+        );
+    }
+
+    #[test]
+    fn clocks_become_periods() {
+        assert!((ClockUnit::MHz.to_period(500.0) - 2e-9).abs() < 1e-15);
+        assert!((ClockUnit::GHz.to_period(2.5) - 0.4e-9).abs() < 1e-15);
+        assert!((ClockUnit::Ns.to_period(1.5) - 1.5e-9).abs() < 1e-15);
+    }
+}
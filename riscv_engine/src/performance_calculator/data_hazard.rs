@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    performance_calculator::{
+        liveness::{contains, Liveness},
+        memory_dependency::{self, MemoryDependency},
+    },
+    riscv_core::instruction::{Instruction, OpCodeType},
+};
+
+// NOPs each kind of conflict requires after an instruction, 0 when there is no conflict
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InstructionHazards {
+    pub raw: usize,
+    pub war: usize,
+    pub waw: usize,
+}
+
+impl InstructionHazards {
+    pub fn highest_nops(&self) -> usize {
+        self.raw.max(self.war).max(self.waw)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HazardKind {
+    Raw,
+    War,
+    Waw,
+}
+
+// A dependency between two instructions of the program. The producer is the earlier one
+// (the writer for RAW and WAW, the reader for WAR) and the consumer the later one
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Dependency {
+    pub kind: HazardKind,
+    pub producer: usize,
+    pub consumer: usize,
+    pub register: String,
+    pub distance: usize,
+    pub stalls_without_forwarding: usize,
+    pub stalls_with_forwarding: usize,
+}
+
+// Every dependency an instruction has on the instructions before it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InstructionHazardReport {
+    pub index: usize,
+    pub instruction: String,
+    pub mnemonic: String,
+    pub dependencies: Vec<Dependency>,
+    pub memory_dependencies: Vec<MemoryDependency>,
+    // Writes a register no later instruction reads
+    pub unused_write: bool,
+}
+
+// Start: Scoreboard
+// Instructions after a conflicting one that still see its result too early without forwarding,
+// and with forwarding (only the load-use case)
+pub const HAZARD_WINDOW: usize = 2;
+pub const FORWARDING_WINDOW: usize = 1;
+
+const REGISTER_COUNT: usize = 32;
+
+// Single pass hazard detection. For each register it keeps the instructions that wrote and read it
+// within the window, so every new instruction is only compared with those. A conflict costs the
+// earlier instruction `window` NOPs when the two are adjacent and one fewer per instruction between them
+pub struct Scoreboard {
+    window: usize,
+    writers: [Vec<usize>; REGISTER_COUNT],
+    readers: [Vec<usize>; REGISTER_COUNT],
+    hazards: Vec<InstructionHazards>,
+}
+
+impl Scoreboard {
+    pub fn new(window: usize) -> Scoreboard {
+        Scoreboard {
+            window,
+            writers: std::array::from_fn(|_| Vec::with_capacity(window)),
+            readers: std::array::from_fn(|_| Vec::with_capacity(window)),
+            hazards: Vec::new(),
+        }
+    }
+
+    pub fn scan(window: usize, instructions: &[Instruction]) -> Vec<InstructionHazards> {
+        let mut scoreboard = Scoreboard::new(window);
+        scoreboard.hazards.reserve(instructions.len());
+
+        for inst in instructions {
+            scoreboard.push(inst);
+        }
+
+        scoreboard.into_hazards()
+    }
+
+    pub fn push(&mut self, inst: &Instruction) {
+        let index = self.hazards.len();
+        let window = self.window;
+        let nops = |producer: usize| window + 1 - (index - producer);
+        let in_window = |producer: &&usize| index - **producer <= window;
+
+        self.hazards.push(InstructionHazards::default());
+
+        let operands = inst.operands();
+
+        // RAW: reads a register written by an instruction in the window
+        for register in operands.reads.into_iter().flatten() {
+            for &producer in self.writers[register as usize].iter().filter(in_window) {
+                let hazards = &mut self.hazards[producer];
+                hazards.raw = hazards.raw.max(nops(producer));
+            }
+        }
+
+        if let Some(register) = operands.write {
+            // WAR: writes a register read by an instruction in the window
+            for &producer in self.readers[register as usize].iter().filter(in_window) {
+                let hazards = &mut self.hazards[producer];
+                hazards.war = hazards.war.max(nops(producer));
+            }
+
+            // WAW: writes a register written by an instruction in the window
+            for &producer in self.writers[register as usize].iter().filter(in_window) {
+                let hazards = &mut self.hazards[producer];
+                hazards.waw = hazards.waw.max(nops(producer));
+            }
+        }
+
+        // Recorded after the checks, so an instruction never conflicts with itself
+        for register in operands.reads.into_iter().flatten() {
+            Self::record(&mut self.readers[register as usize], index, window);
+        }
+        if let Some(register) = operands.write {
+            Self::record(&mut self.writers[register as usize], index, window);
+        }
+    }
+
+    pub fn into_hazards(self) -> Vec<InstructionHazards> {
+        self.hazards
+    }
+
+    // Forgets the instructions no later one can conflict with anymore
+    fn record(instructions: &mut Vec<usize>, index: usize, window: usize) {
+        if instructions.last() == Some(&index) {
+            return;
+        }
+
+        instructions.retain(|&previous| index - previous < window);
+        instructions.push(index);
+    }
+}
+// End: Scoreboard
+
+// Start: Situacao 1 -> Only Nops
+pub fn check_for_hazards(instructions: &[Instruction]) -> Vec<InstructionHazards> {
+    Scoreboard::scan(HAZARD_WINDOW, instructions)
+}
+// End: Situacao 1 -> Only Nops
+
+// Start: Situacao 2 -> Forwarding
+pub fn check_for_hazards_with_forwarding(instructions: &[Instruction]) -> Vec<usize> {
+    load_use_nops(FORWARDING_WINDOW, instructions)
+}
+
+// Load-use NOPs within any window, for pipelines deeper than five stages
+pub fn load_use_nops(window: usize, instructions: &[Instruction]) -> Vec<usize> {
+    // Com forwarding, somente a instrucao L seguida de uma leitura do seu RD precisa de NOP
+    Scoreboard::scan(window, instructions)
+        .iter()
+        .zip(instructions)
+        .map(|(hazards, inst)| match inst.opcode() {
+            OpCodeType::L => hazards.raw,
+            _ => 0,
+        })
+        .collect()
+}
+// End: Situacao 2 -> Forwarding
+
+// Start: Situacao 3 e Situacao 4 -> Reorder and Only Nops && Reorder and Forwarding with Nops
+pub fn check_for_reorder(instructions: &[Instruction]) -> Vec<Option<[bool; 2]>> {
+    // Retorna um matriz contendo bool se pode ou nao reordenar de acordo com a instrucao atual e as proximas duas
+    let mut can_reorder: Vec<Option<[bool; 2]>> = Vec::with_capacity(instructions.len());
+    let hazards = check_for_hazards(instructions);
+    let starts = memory_dependency::tracking_starts(instructions);
+
+    for (index, current_inst) in instructions.iter().enumerate() {
+        // Se a inst atual for nops, ignora
+        if current_inst.is_nop() {
+            can_reorder.push(None);
+            continue;
+        }
+
+        // Se a inst atual for desvio, ignora
+        match current_inst.opcode() {
+            OpCodeType::J | OpCodeType::B => {
+                can_reorder.push(None);
+                continue;
+            }
+            _ => (),
+        }
+
+        let InstructionHazards {
+            raw: raw_nops,
+            war: war_nops,
+            waw: waw_nops,
+        } = hazards[index];
+
+        let mut can_reorder_first_inst = false;
+        let mut can_reorder_second_inst = false;
+
+        if war_nops == 2 || waw_nops == 2 || raw_nops == 2 {
+            // Se as proximas inst tiver 2 nops de conflito, nao as mova
+            can_reorder_first_inst = false;
+            can_reorder_second_inst = false;
+        } else if war_nops == 1 || waw_nops == 1 || raw_nops == 1 {
+            // Se as proximas inst tiver 1 nops de conflito, mova a segunda
+            can_reorder_first_inst = false; // Move 1 para cima
+            can_reorder_second_inst = true; // Move 2 para cima
+        }
+
+        // The techniques move it up to 3 positions, never above a store it may alias
+        let memory_safe = (index.saturating_sub(3)..index)
+            .all(|earlier| memory_dependency::can_swap(instructions, &starts, earlier, index));
+        if !memory_safe {
+            can_reorder_second_inst = false;
+        }
+
+        can_reorder.push(Some([can_reorder_first_inst, can_reorder_second_inst]));
+    }
+
+    can_reorder
+}
+// End: Situacao 3 e Situacao 4 -> Reorder and Only Nops && Reorder and Forwarding with Nops
+
+// Start: Hazard report
+// Register written by the instruction, x0 is never a dependency
+pub fn written_register(inst: &Instruction) -> Option<u8> {
+    inst.operands().write
+}
+
+// Registers read by the instruction, without x0 or repetitions
+pub fn read_registers(inst: &Instruction) -> Vec<u8> {
+    inst.operands().reads.into_iter().flatten().collect()
+}
+
+pub fn register_name(register: u8) -> String {
+    format!("x{}", register)
+}
+
+fn dependency(
+    kind: HazardKind,
+    producer: usize,
+    consumer: usize,
+    register: u8,
+    instructions: &[Instruction],
+) -> Dependency {
+    let distance = consumer - producer;
+
+    // Same model as the NOP techniques: without forwarding, a conflict with one of the next two
+    // instructions costs 2 cycles when they are adjacent and 1 otherwise. With forwarding, only a
+    // load followed right away by an instruction that uses its result stalls
+    let stalls_without_forwarding = 3usize.saturating_sub(distance);
+    let is_load_use = kind == HazardKind::Raw
+        && distance == 1
+        && matches!(instructions[producer].opcode(), OpCodeType::L);
+
+    Dependency {
+        kind,
+        producer,
+        consumer,
+        register: register_name(register),
+        distance,
+        stalls_without_forwarding,
+        stalls_with_forwarding: usize::from(is_load_use),
+    }
+}
+
+// Walks the program once, keeping the last writer of each register and who read it since
+pub fn hazard_report(instructions: &[Instruction]) -> Vec<InstructionHazardReport> {
+    let mut last_writer: HashMap<u8, usize> = HashMap::new();
+    let mut readers_since_write: HashMap<u8, Vec<usize>> = HashMap::new();
+    let mut report = Vec::new();
+    let mut memory_dependencies = memory_dependency::memory_dependencies(instructions).into_iter();
+    let liveness = Liveness::from_instructions(instructions);
+
+    for (index, inst) in instructions.iter().enumerate() {
+        let mut dependencies = Vec::new();
+        let reads = read_registers(inst);
+        let write = written_register(inst);
+
+        for register in reads.iter() {
+            if let Some(producer) = last_writer.get(register) {
+                dependencies.push(dependency(
+                    HazardKind::Raw,
+                    *producer,
+                    index,
+                    *register,
+                    instructions,
+                ));
+            }
+        }
+
+        if let Some(register) = write {
+            for reader in readers_since_write.get(&register).into_iter().flatten() {
+                dependencies.push(dependency(
+                    HazardKind::War,
+                    *reader,
+                    index,
+                    register,
+                    instructions,
+                ));
+            }
+
+            if let Some(producer) = last_writer.get(&register) {
+                dependencies.push(dependency(
+                    HazardKind::Waw,
+                    *producer,
+                    index,
+                    register,
+                    instructions,
+                ));
+            }
+
+            last_writer.insert(register, index);
+            readers_since_write.remove(&register);
+        }
+
+        // Reads happen before the write, so an instruction like "addi t0, t0, 1" still counts as a reader
+        for register in reads {
+            readers_since_write.entry(register).or_default().push(index);
+        }
+
+        report.push(InstructionHazardReport {
+            index,
+            instruction: inst.binary_text(),
+            mnemonic: inst.mnemonic().to_string(),
+            dependencies,
+            memory_dependencies: memory_dependencies.next().unwrap_or_default(),
+            unused_write: write
+                .is_some_and(|register| !contains(liveness.live_out[index], register)),
+        });
+    }
+
+    report
+}
+// End: Hazard report
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(rd: u8, rs1: u8, rs2: u8) -> Instruction {
+        let add = Instruction::from_word(0x0000_0033).unwrap();
+        add.with_rd(rd).with_rs1(rs1).with_rs2(rs2)
+    }
+
+    // lw rd, 0(rs1)
+    fn lw(rd: u8, rs1: u8) -> Instruction {
+        let lw = Instruction::from_word(0x0000_2003).unwrap();
+        lw.with_rd(rd).with_rs1(rs1)
+    }
+
+    #[test]
+    fn hazard_report_lists_every_dependency() {
+        let instructions = [lw(5, 10), add(6, 5, 5), add(10, 6, 0), add(5, 10, 10)];
+        let report = hazard_report(&instructions);
+
+        let dependencies: Vec<Vec<_>> = report
+            .iter()
+            .map(|instruction| {
+                instruction
+                    .dependencies
+                    .iter()
+                    .map(|dependency| {
+                        assert_eq!(dependency.consumer, instruction.index);
+                        (
+                            dependency.kind,
+                            dependency.producer,
+                            dependency.register.as_str(),
+                            dependency.distance,
+                            dependency.stalls_without_forwarding,
+                            dependency.stalls_with_forwarding,
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            dependencies,
+            vec![
+                vec![],
+                // Load-use: the only stall left with forwarding. x5 is read once
+                vec![(HazardKind::Raw, 0, "x5", 1, 2, 1)],
+                // x0 is never a dependency
+                vec![
+                    (HazardKind::Raw, 1, "x6", 1, 2, 0),
+                    (HazardKind::War, 0, "x10", 2, 1, 0),
+                ],
+                vec![
+                    (HazardKind::Raw, 2, "x10", 1, 2, 0),
+                    (HazardKind::War, 1, "x5", 2, 1, 0),
+                    (HazardKind::Waw, 0, "x5", 3, 0, 0),
+                ],
+            ]
+        );
+
+        assert_eq!(report[1].mnemonic, "add");
+        assert_eq!(report[0].instruction, lw(5, 10).binary_text());
+        let unused_writes: Vec<bool> = report
+            .iter()
+            .map(|instruction| instruction.unused_write)
+            .collect();
+        assert_eq!(unused_writes, vec![false, false, false, true]);
+    }
+
+    // The quadratic definition the scoreboard replaces: compare each instruction with the next
+    // `window` ones
+    fn pairwise(window: usize, instructions: &[Instruction]) -> Vec<InstructionHazards> {
+        let mut hazards = vec![InstructionHazards::default(); instructions.len()];
+
+        for (producer, earlier) in instructions.iter().enumerate() {
+            let earlier = earlier.operands();
+            let later_ones = instructions.iter().enumerate().skip(producer + 1);
+
+            for (consumer, later) in later_ones.take(window) {
+                let later = later.operands();
+                let nops = window + 1 - (consumer - producer);
+                let hazard = &mut hazards[producer];
+
+                if earlier.write.is_some() && later.reads.contains(&earlier.write) {
+                    hazard.raw = hazard.raw.max(nops);
+                }
+                if later.write.is_some() && earlier.reads.contains(&later.write) {
+                    hazard.war = hazard.war.max(nops);
+                }
+                if later.write.is_some() && later.write == earlier.write {
+                    hazard.waw = hazard.waw.max(nops);
+                }
+            }
+        }
+
+        hazards
+    }
+
+    #[test]
+    fn scoreboard_matches_the_pairwise_comparison() {
+        // Few registers, so most instructions conflict with a neighbour
+        let mut seed: u32 = 0x2545_f491;
+        let mut next = |bound: u32| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed % bound) as u8
+        };
+        let instructions: Vec<Instruction> = (0..2000)
+            .map(|_| {
+                let (rd, rs1, rs2) = (next(6), next(6), next(6));
+                match next(3) {
+                    0 => add(rd, rs1, rs2),
+                    1 => lw(rd, rs1),
+                    // sw rs2, 0(rs1) writes no register
+                    _ => Instruction::from_word(0x0000_2023)
+                        .unwrap()
+                        .with_rs1(rs1)
+                        .with_rs2(rs2),
+                }
+            })
+            .collect();
+
+        for window in [1, HAZARD_WINDOW, 3, 8] {
+            assert_eq!(
+                Scoreboard::scan(window, &instructions),
+                pairwise(window, &instructions),
+                "window {}",
+                window
+            );
+        }
+    }
+}
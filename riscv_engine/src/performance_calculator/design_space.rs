@@ -0,0 +1,642 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    organization::{self, Organization, CPI_CLASS_KEYS, FORMAT_KEYS},
+    performance_calculator::{
+        data_hazard::{load_use_nops, Scoreboard, FORWARDING_WINDOW, HAZARD_WINDOW},
+        energy::EnergyEstimate,
+        memory_hierarchy::{self, CacheConfig},
+        suite_calculator::WeightedMeans,
+    },
+    program::Program,
+    riscv_core::{
+        executor::{self, Execution},
+        instruction::{Instruction, MNEMONICS},
+    },
+    units,
+};
+
+// Points of the Cartesian product, so a request cannot take the server for minutes
+pub const MAX_SWEEP_POINTS: usize = 4096;
+pub const MAX_PIPELINE_DEPTH: usize = 32;
+// The five stages of the pipeline the techniques are written for
+pub const DEFAULT_PIPELINE_DEPTH: usize = 5;
+
+// Parameter name to its value
+pub type DesignPoint = BTreeMap<String, f32>;
+
+// Every value of a parameter, or start, start + step, ... up to end
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum SweepValues {
+    List(Vec<f32>),
+    Range { start: f32, end: f32, step: f32 },
+}
+
+impl SweepValues {
+    fn values(&self) -> Result<Vec<f32>, String> {
+        match self {
+            SweepValues::List(values) if values.is_empty() => {
+                Err("must have at least one value".to_string())
+            }
+            SweepValues::List(values) => Ok(values.clone()),
+            SweepValues::Range { start, end, step } => {
+                if !(start.is_finite() && end.is_finite() && step.is_finite()) {
+                    return Err("start, end and step must be numbers".to_string());
+                }
+                if *step <= 0.0 || end < start {
+                    return Err("step must be positive and end not before start".to_string());
+                }
+
+                // The tolerance keeps end when the steps add up to slightly more than it
+                let count = ((end - start) / step * (1.0 + 1e-6)).floor() as usize + 1;
+                if count > MAX_SWEEP_POINTS {
+                    return Err(format!("must have at most {} values", MAX_SWEEP_POINTS));
+                }
+
+                Ok((0..count)
+                    .map(|step_index| start + step * step_index as f32)
+                    .collect())
+            }
+        }
+    }
+}
+
+// Parameters a sweep can vary, by their name in the request
+enum Parameter {
+    // Seconds
+    ClockPeriod,
+    // Multiplies the clock period, e.g. 1.1 for a clock 10% slower
+    ClockScale,
+    // cpi.<format, mnemonic or class>
+    Cpi(String),
+    // energy.<format, mnemonic or class>, in picojoules
+    Energy(String),
+    StaticPower,
+    PipelineDepth,
+    // 0 or 1
+    Forwarding,
+    // Bytes of both L1 caches, with the other cache settings at their defaults
+    CacheSize,
+}
+
+fn is_instruction_key(key: &str) -> bool {
+    MNEMONICS.contains(&key) || CPI_CLASS_KEYS.contains(&key) || FORMAT_KEYS.contains(&key)
+}
+
+impl Parameter {
+    fn parse(name: &str) -> Option<Parameter> {
+        match name.split_once('.') {
+            Some(("cpi", key)) if is_instruction_key(key) => Some(Parameter::Cpi(key.to_string())),
+            Some(("energy", key)) if is_instruction_key(key) => {
+                Some(Parameter::Energy(key.to_string()))
+            }
+            Some(_) => None,
+            None => match name {
+                "clock_period" => Some(Parameter::ClockPeriod),
+                "clock_scale" => Some(Parameter::ClockScale),
+                "static_power" => Some(Parameter::StaticPower),
+                "pipeline_depth" => Some(Parameter::PipelineDepth),
+                "forwarding" => Some(Parameter::Forwarding),
+                "cache_size" => Some(Parameter::CacheSize),
+                _ => None,
+            },
+        }
+    }
+
+    fn check(&self, value: f32) -> Result<(), String> {
+        if let Parameter::Cpi(_) = self {
+            return organization::check_cpi(value);
+        }
+
+        let valid = match self {
+            Parameter::ClockPeriod | Parameter::ClockScale | Parameter::Cpi(_) => value > 0.0,
+            Parameter::Energy(_) | Parameter::StaticPower => value >= 0.0,
+            Parameter::PipelineDepth => {
+                value.fract() == 0.0 && (1.0..=MAX_PIPELINE_DEPTH as f32).contains(&value)
+            }
+            Parameter::Forwarding => value == 0.0 || value == 1.0,
+            Parameter::CacheSize => {
+                value.fract() == 0.0
+                    && value > 0.0
+                    && cache_config(value as usize).validate().is_empty()
+            }
+        };
+
+        match (valid && value.is_finite(), self) {
+            (true, _) => Ok(()),
+            (false, Parameter::ClockPeriod | Parameter::ClockScale | Parameter::Cpi(_)) => {
+                Err("must be greater than zero".to_string())
+            }
+            (false, Parameter::Energy(_) | Parameter::StaticPower) => {
+                Err("must not be negative".to_string())
+            }
+            (false, Parameter::PipelineDepth) => Err(format!(
+                "must be whole numbers between 1 and {}",
+                MAX_PIPELINE_DEPTH
+            )),
+            (false, Parameter::Forwarding) => Err("must be 0 or 1".to_string()),
+            (false, Parameter::CacheSize) => Err(format!(
+                "must be powers of two between {} and {}",
+                memory_hierarchy::DEFAULT_BLOCK_SIZE,
+                memory_hierarchy::MAX_CACHE_SIZE
+            )),
+        }
+    }
+}
+
+fn cache_config(size: usize) -> CacheConfig {
+    CacheConfig {
+        size,
+        ..CacheConfig::default()
+    }
+}
+
+// The Cartesian product of the parameter values, one map of name to value per point. Errors come
+// as (parameter name, message)
+pub fn design_points(
+    parameters: &BTreeMap<String, SweepValues>,
+) -> Result<Vec<DesignPoint>, Vec<(String, String)>> {
+    let mut errors = Vec::new();
+    let mut axes = Vec::new();
+
+    for (name, values) in parameters {
+        let parameter = match Parameter::parse(name) {
+            Some(parameter) => parameter,
+            None => {
+                errors.push((name.clone(), "unknown parameter".to_string()));
+                continue;
+            }
+        };
+
+        match values.values().and_then(|values| {
+            values
+                .iter()
+                .try_for_each(|value| parameter.check(*value))
+                .map(|_| values)
+        }) {
+            Ok(values) => axes.push((name.clone(), values)),
+            Err(message) => errors.push((name.clone(), message)),
+        }
+    }
+
+    let points = axes
+        .iter()
+        .try_fold(1usize, |points, (_, values)| {
+            points.checked_mul(values.len())
+        })
+        .filter(|points| *points <= MAX_SWEEP_POINTS);
+    if errors.is_empty() && points.is_none() {
+        errors.push((
+            String::new(),
+            format!("the sweep must have at most {} points", MAX_SWEEP_POINTS),
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut points = vec![BTreeMap::new()];
+    for (name, values) in axes.iter() {
+        points = points
+            .into_iter()
+            .flat_map(|point| {
+                values.iter().map(move |value| {
+                    let mut point = point.clone();
+                    point.insert(name.clone(), *value);
+                    point
+                })
+            })
+            .collect();
+    }
+
+    Ok(points)
+}
+
+// The organization and pipeline of one point
+struct Design {
+    organization: Organization,
+    pipeline_depth: usize,
+    forwarding: bool,
+    cache: Option<CacheConfig>,
+}
+
+fn cpi_field<'a>(organization: &'a mut Organization, key: &str) -> &'a mut f32 {
+    match key {
+        "r" => &mut organization.cpi_instruction_r,
+        "i" => &mut organization.cpi_instruction_i,
+        "l" => &mut organization.cpi_instruction_l,
+        "s" => &mut organization.cpi_instruction_s,
+        "b" => &mut organization.cpi_instruction_b,
+        "u" => &mut organization.cpi_instruction_u,
+        "j" => &mut organization.cpi_instruction_j,
+        _ => organization.cpi_map.entry(key.to_string()).or_default(),
+    }
+}
+
+impl Design {
+    // Parameters left out of the point keep the value of the organization, forwarding is on and
+    // there are no caches
+    fn from_point(base: &Organization, point: &DesignPoint) -> Design {
+        let mut design = Design {
+            organization: base.clone(),
+            pipeline_depth: DEFAULT_PIPELINE_DEPTH,
+            forwarding: true,
+            cache: None,
+        };
+        let mut clock_scale = 1.0;
+
+        for (name, value) in point {
+            match Parameter::parse(name) {
+                Some(Parameter::ClockPeriod) => design.organization.clock_period = *value,
+                Some(Parameter::ClockScale) => clock_scale = *value,
+                Some(Parameter::Cpi(key)) => *cpi_field(&mut design.organization, &key) = *value,
+                Some(Parameter::Energy(key)) => {
+                    design.organization.energy_map.insert(key, *value);
+                }
+                Some(Parameter::StaticPower) => design.organization.static_power = *value,
+                Some(Parameter::PipelineDepth) => design.pipeline_depth = *value as usize,
+                Some(Parameter::Forwarding) => design.forwarding = *value == 1.0,
+                Some(Parameter::CacheSize) => design.cache = Some(cache_config(*value as usize)),
+                None => {}
+            }
+        }
+        design.organization.clock_period *= clock_scale;
+
+        design
+    }
+
+    // Whether the point forwards, and how many later instructions a result conflicts with
+    fn hazard_window(&self) -> (bool, usize) {
+        let window = match self.forwarding {
+            true => FORWARDING_WINDOW,
+            false => HAZARD_WINDOW,
+        };
+
+        (self.forwarding, scaled_window(window, self.pipeline_depth))
+    }
+}
+
+// A window of the five stage pipeline at another depth. The extra (or missing) stages are spread
+// over the pipeline, so the stages between reading a register and writing it grow with the depth
+fn scaled_window(window: usize, depth: usize) -> usize {
+    (window * depth + DEFAULT_PIPELINE_DEPTH / 2) / DEFAULT_PIPELINE_DEPTH
+}
+
+// What every point needs from a program, worked out once
+struct Workload<'a> {
+    program: &'a Program,
+    weight: f32,
+    execution: Execution,
+    // NOPs the techniques put after each instruction, for every hazard window of the points
+    nops: BTreeMap<(bool, usize), Vec<usize>>,
+}
+
+impl Workload<'_> {
+    fn new<'a>(
+        program: &'a Program,
+        weight: f32,
+        windows: &BTreeSet<(bool, usize)>,
+    ) -> Workload<'a> {
+        let instructions = &program.instructions;
+        let nops = windows
+            .iter()
+            .map(|(forwarding, window)| {
+                let nops = match forwarding {
+                    true => load_use_nops(*window, instructions),
+                    false => Scoreboard::scan(*window, instructions)
+                        .iter()
+                        .map(|hazards| hazards.highest_nops())
+                        .collect(),
+                };
+                ((*forwarding, *window), nops)
+            })
+            .collect();
+
+        Workload {
+            program,
+            weight,
+            execution: executor::execute(instructions, executor::MAX_STEPS),
+            nops,
+        }
+    }
+
+    // Cycles and energy of the executed instructions with the NOPs of only_nops (or
+    // forwading_with_nops) in windows scaled to the depth, the pipeline fill and the cache miss
+    // stalls
+    fn evaluate(&self, design: &Design) -> (f32, f32, EnergyEstimate) {
+        let organization = &design.organization;
+        let instructions = &self.program.instructions;
+        let nops = &self.nops[&design.hazard_window()];
+
+        let executed_nops: usize = self
+            .execution
+            .executed
+            .iter()
+            .map(|index| nops[*index])
+            .sum();
+        let mut cycles: f32 = self
+            .execution
+            .executed
+            .iter()
+            .map(|index| organization.cpi_for(&instructions[*index]))
+            .sum();
+        cycles += executed_nops as f32 * organization.cpi_for(&Instruction::NOP);
+        cycles += design.pipeline_depth.saturating_sub(1) as f32;
+        if let Some(cache) = design.cache {
+            let (instruction_cache, data_cache) =
+                memory_hierarchy::simulate(&self.execution, cache, cache);
+            cycles += (instruction_cache.stall_cycles + data_cache.stall_cycles) as f32;
+        }
+
+        let execution_time = cycles * organization.clock_period;
+        let energy = EnergyEstimate::from_run(
+            organization,
+            self.execution
+                .executed
+                .iter()
+                .map(|index| &instructions[*index])
+                .chain(std::iter::repeat_n(&Instruction::NOP, executed_nops)),
+            execution_time,
+        );
+
+        (cycles, execution_time, energy)
+    }
+}
+
+// What the Pareto front trades execution time against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CostMetric {
+    #[default]
+    Energy,
+    EnergyDelayProduct,
+    AveragePower,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SweepResult {
+    pub parameters: DesignPoint,
+    pub total_cycles: f32,
+    pub execution_time: f32,
+    pub formatted_execution_time: String,
+    pub total_energy: f32,
+    pub formatted_total_energy: String,
+    pub average_power: f32,
+    pub energy_delay_product: f32,
+    // The value of the requested cost metric
+    pub cost: f32,
+    pub pareto_optimal: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SweepCalculator {
+    pub organization_name: String,
+    // The bin file or benchmark suite
+    pub workload_name: String,
+    pub cost_metric: CostMetric,
+    pub results: Vec<SweepResult>,
+    // Indices of the results no other result beats on both execution time and cost, fastest first
+    pub pareto_front: Vec<usize>,
+}
+
+// Indices of the non-dominated (time, cost) pairs, fastest first. Equal pairs are all kept
+pub fn pareto_front(points: &[(f32, f32)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|a, b| {
+        points[*a]
+            .0
+            .total_cmp(&points[*b].0)
+            .then(points[*a].1.total_cmp(&points[*b].1))
+    });
+
+    let mut front: Vec<usize> = Vec::new();
+    for index in order {
+        let (time, cost) = points[index];
+        let kept = match front.last().map(|last| points[*last]) {
+            None => true,
+            Some((last_time, last_cost)) => {
+                cost < last_cost || (time == last_time && cost == last_cost)
+            }
+        };
+        if kept {
+            front.push(index);
+        }
+    }
+
+    front
+}
+
+impl SweepCalculator {
+    // Evaluates every point on every program in parallel. A suite reports the weighted arithmetic
+    // mean of each metric over its programs, so the front reads like one for a single program
+    pub fn sweep_from(
+        organization: &Organization,
+        workload_name: &str,
+        programs: &[(Program, f32)],
+        points: &[DesignPoint],
+        cost_metric: CostMetric,
+    ) -> SweepCalculator {
+        let designs: Vec<Design> = points
+            .iter()
+            .map(|point| Design::from_point(organization, point))
+            .collect();
+        let windows: BTreeSet<(bool, usize)> = designs.iter().map(Design::hazard_window).collect();
+        let workloads: Vec<Workload> = programs
+            .par_iter()
+            .map(|(program, weight)| Workload::new(program, *weight, &windows))
+            .collect();
+
+        let mut results: Vec<SweepResult> = points
+            .par_iter()
+            .zip(&designs)
+            .map(|(point, design)| {
+                let evaluations: Vec<(f32, (f32, f32, EnergyEstimate))> = workloads
+                    .iter()
+                    .map(|workload| (workload.weight, workload.evaluate(design)))
+                    .collect();
+                let mean = |metric: &dyn Fn(&(f32, f32, EnergyEstimate)) -> f32| {
+                    let samples: Vec<(f32, f32)> = evaluations
+                        .iter()
+                        .map(|(weight, evaluation)| (metric(evaluation), *weight))
+                        .collect();
+                    WeightedMeans::from_samples(&samples).arithmetic
+                };
+
+                let execution_time = mean(&|(_, execution_time, _)| *execution_time);
+                let total_energy = mean(&|(_, _, energy)| energy.total_energy);
+                let average_power = mean(&|(_, _, energy)| energy.average_power);
+                let energy_delay_product = mean(&|(_, _, energy)| energy.energy_delay_product);
+
+                SweepResult {
+                    parameters: point.clone(),
+                    total_cycles: mean(&|(cycles, _, _)| *cycles),
+                    execution_time,
+                    formatted_execution_time: units::format_time(execution_time),
+                    total_energy,
+                    formatted_total_energy: units::format_energy(total_energy),
+                    average_power,
+                    energy_delay_product,
+                    cost: match cost_metric {
+                        CostMetric::Energy => total_energy,
+                        CostMetric::EnergyDelayProduct => energy_delay_product,
+                        CostMetric::AveragePower => average_power,
+                    },
+                    pareto_optimal: false,
+                }
+            })
+            .collect();
+
+        let front = pareto_front(
+            &results
+                .iter()
+                .map(|result| (result.execution_time, result.cost))
+                .collect::<Vec<(f32, f32)>>(),
+        );
+        for index in front.iter() {
+            results[*index].pareto_optimal = true;
+        }
+
+        SweepCalculator {
+            organization_name: organization.id.clone(),
+            workload_name: workload_name.to_string(),
+            cost_metric,
+            results,
+            pareto_front: front,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::organization::tests::organization;
+
+    #[test]
+    fn sweep_points_and_pareto_front() {
+        let parameters = BTreeMap::from([
+            ("cpi.b".to_string(), SweepValues::List(vec![2.0, 3.0])),
+            (
+                "clock_scale".to_string(),
+                SweepValues::Range {
+                    start: 1.0,
+                    end: 1.2,
+                    step: 0.1,
+                },
+            ),
+        ]);
+        let points = design_points(&parameters).unwrap();
+        assert_eq!(points.len(), 6);
+
+        let invalid = BTreeMap::from([
+            ("cpi.bogus".to_string(), SweepValues::List(vec![1.0])),
+            ("forwarding".to_string(), SweepValues::List(vec![2.0])),
+        ]);
+        let fields: Vec<String> = design_points(&invalid)
+            .unwrap_err()
+            .into_iter()
+            .map(|(field, _)| field)
+            .collect();
+        assert_eq!(fields, vec!["cpi.bogus", "forwarding"]);
+
+        // Ties on both metrics stay on the front
+        assert_eq!(
+            pareto_front(&[(1.0, 5.0), (2.0, 3.0), (2.0, 4.0), (3.0, 3.0), (1.0, 5.0)]),
+            vec![0, 4, 1]
+        );
+
+        let organization = Organization {
+            energy_map: BTreeMap::from([("i".to_string(), 10.0)]),
+            static_power: 0.05,
+            ..organization("sweep", [1.0, 1.0, 3.0, 2.0, 3.0, 1.0, 2.0])
+        };
+        let fatorial = Program::from_text(
+            "fatorial",
+            include_str!("../../../riscv_bin_dump/fatorial.txt"),
+        )
+        .unwrap();
+        let sweep = SweepCalculator::sweep_from(
+            &organization,
+            "fatorial",
+            &[(fatorial, 1.0)],
+            &points,
+            CostMetric::Energy,
+        );
+        let fastest = &sweep.results[sweep.pareto_front[0]];
+        assert_eq!(
+            fastest.parameters,
+            BTreeMap::from([("clock_scale".to_string(), 1.0), ("cpi.b".to_string(), 2.0)])
+        );
+        // Faster is also cheaper when the energy only grows with time
+        assert_eq!(sweep.pareto_front.len(), 1);
+        assert!(sweep
+            .results
+            .iter()
+            .all(|result| result.pareto_optimal == (result.parameters == fastest.parameters)));
+    }
+
+    #[test]
+    fn hazard_windows_grow_with_the_depth() {
+        assert_eq!(
+            scaled_window(HAZARD_WINDOW, DEFAULT_PIPELINE_DEPTH),
+            HAZARD_WINDOW
+        );
+        assert_eq!(
+            scaled_window(FORWARDING_WINDOW, DEFAULT_PIPELINE_DEPTH),
+            FORWARDING_WINDOW
+        );
+        assert_eq!(scaled_window(HAZARD_WINDOW, 10), 4);
+        assert_eq!(scaled_window(HAZARD_WINDOW, 1), 0);
+
+        let program = Program::from_text(
+            "depth",
+            // add x5, x6, x7; add x6, x5, x5
+            "00000000011100110000001010110011
+             00000000010100101000001100110011",
+        )
+        .unwrap();
+        let parameters = BTreeMap::from([
+            (
+                "pipeline_depth".to_string(),
+                SweepValues::List(vec![5.0, 10.0]),
+            ),
+            ("forwarding".to_string(), SweepValues::List(vec![0.0, 1.0])),
+        ]);
+        let sweep = SweepCalculator::sweep_from(
+            &organization("depth", [1.0; 7]),
+            "depth",
+            &[(program, 1.0)],
+            &design_points(&parameters).unwrap(),
+            CostMetric::Energy,
+        );
+
+        // Two adds, the NOPs of the RAW and the fill of the pipeline
+        let cycles: Vec<(f32, f32, f32)> = sweep
+            .results
+            .iter()
+            .map(|result| {
+                (
+                    result.parameters["forwarding"],
+                    result.parameters["pipeline_depth"],
+                    result.total_cycles,
+                )
+            })
+            .collect();
+        assert_eq!(
+            cycles,
+            vec![
+                (0.0, 5.0, 2.0 + 2.0 + 4.0),
+                (0.0, 10.0, 2.0 + 4.0 + 9.0),
+                (1.0, 5.0, 2.0 + 4.0),
+                (1.0, 10.0, 2.0 + 9.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{organization::Organization, riscv_core::instruction::Instruction, units};
+
+// Energies in joules, power in watts
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EnergyEstimate {
+    // Sum of the energy_map cost of every instruction run
+    pub dynamic_energy: f32,
+    // static_power * Texec
+    pub static_energy: f32,
+    pub total_energy: f32,
+    pub formatted_total_energy: String,
+    // total_energy / Texec
+    pub average_power: f32,
+    pub formatted_average_power: String,
+    // total_energy * Texec, in joule-seconds
+    pub energy_delay_product: f32,
+    pub energy_per_instruction: f32,
+}
+
+impl EnergyEstimate {
+    // instructions are the ones that ran, once per execution, in the execution_time of the result
+    pub fn from_run<'a>(
+        organization: &Organization,
+        instructions: impl IntoIterator<Item = &'a Instruction>,
+        execution_time: f32,
+    ) -> EnergyEstimate {
+        let mut total_instructions = 0;
+        let mut dynamic_energy = 0.0;
+        for inst in instructions {
+            total_instructions += 1;
+            dynamic_energy += organization.energy_for(inst);
+        }
+
+        let static_energy = organization.static_power * execution_time;
+        let total_energy = dynamic_energy + static_energy;
+        let average_power = match execution_time > 0.0 {
+            true => total_energy / execution_time,
+            false => 0.0,
+        };
+
+        EnergyEstimate {
+            dynamic_energy,
+            static_energy,
+            total_energy,
+            formatted_total_energy: units::format_energy(total_energy),
+            average_power,
+            formatted_average_power: units::format_power(average_power),
+            energy_delay_product: total_energy * execution_time,
+            energy_per_instruction: match total_instructions {
+                0 => 0.0,
+                _ => total_energy / total_instructions as f32,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{
+        organization::tests::organization, performance_calculator::pipeline_calculator,
+        program::Program,
+    };
+
+    #[test]
+    fn energy_follows_the_most_specific_key() {
+        let organization = Organization {
+            energy_map: BTreeMap::from(
+                [
+                    ("i", 10.0),
+                    ("r", 20.0),
+                    ("mul", 40.0),
+                    ("s", 25.0),
+                    ("sw", 30.0),
+                ]
+                .map(|(key, energy)| (key.to_string(), energy)),
+            ),
+            static_power: 0.01,
+            ..organization("energy", [1.0; 7])
+        };
+        let program = Program::from_text(
+            "energy",
+            // addi x5, x0, 1; mul x6, x5, x5; sw x6, 0(x2)
+            "00000000000100000000001010010011
+             00000010010100101000001100110011
+             00000000011000010010000000100011",
+        )
+        .unwrap();
+
+        let results =
+            pipeline_calculator::PerformanceCalculator::pipeline_from(&organization, &program)
+                .results;
+        let original = &results[0].energy;
+        // 10 + 40 + 30 pJ, and 10 mW for 3 ns
+        assert!((original.dynamic_energy - 80e-12).abs() < 1e-15);
+        assert!((original.static_energy - 30e-12).abs() < 1e-15);
+        assert!((original.average_power - 110e-12 / 3e-9).abs() < 1e-6);
+        assert!((original.energy_per_instruction - 110e-12 / 3.0).abs() < 1e-15);
+        assert_eq!(original.formatted_total_energy, "110 pJ");
+
+        // The NOPs cost an addi each and stretch the static energy
+        let only_nops = &results[1];
+        let nops = (only_nops.total_cicles - 3.0) * 1e-12;
+        assert!((only_nops.energy.dynamic_energy - (80e-12 + nops * 10.0)).abs() < 1e-15);
+        assert!(only_nops.energy.energy_delay_product > original.energy_delay_product);
+    }
+}
@@ -0,0 +1,463 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    performance_calculator::data_hazard::register_name,
+    riscv_core::instruction::{Instruction, OpCodeType, INSTRUCTION_SIZE},
+};
+
+// Set of registers, bit n for xn
+pub type RegisterSet = u32;
+
+// Every register but x0, for jalr: where it returns to is not known
+pub const ALL_REGISTERS: RegisterSet = !1;
+
+const INSTRUCTION_BYTES: i64 = (INSTRUCTION_SIZE / 8) as i64;
+
+pub fn contains(set: RegisterSet, register: u8) -> bool {
+    set & (1 << register) != 0
+}
+
+pub fn registers(set: RegisterSet) -> impl Iterator<Item = u8> {
+    (0..32).filter(move |register| contains(set, *register))
+}
+
+fn register_set(registers: impl IntoIterator<Item = u8>) -> RegisterSet {
+    registers
+        .into_iter()
+        .fold(0, |set, register| set | (1 << register))
+}
+
+fn register_names(set: RegisterSet) -> Vec<String> {
+    registers(set).map(register_name).collect()
+}
+
+// Index a branch or jal jumps to, None for other instructions. The offset is only right on the
+// original program: the techniques insert NOPs without fixing it
+pub fn jump_target(index: usize, inst: &Instruction) -> Option<i64> {
+    match inst.opcode() {
+        OpCodeType::B | OpCodeType::J => {
+            Some(index as i64 + inst.immediate() as i64 / INSTRUCTION_BYTES)
+        }
+        _ => None,
+    }
+}
+
+pub fn is_jump(inst: &Instruction) -> bool {
+    inst.opcode() == OpCodeType::J || inst.mnemonic() == "jalr"
+}
+
+// Instructions some branch or jal jumps to
+pub fn jump_targets(instructions: &[Instruction]) -> Vec<bool> {
+    let mut targets = vec![false; instructions.len()];
+
+    for (index, inst) in instructions.iter().enumerate() {
+        if let Some(target) = jump_target(index, inst)
+            .and_then(|target| usize::try_from(target).ok())
+            .and_then(|target| targets.get_mut(target))
+        {
+            *target = true;
+        }
+    }
+
+    targets
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Successor {
+    Instruction(usize),
+    // After the last instruction, nothing is read anymore
+    Exit,
+    // jalr, or a jump out of the program: anything may be read there
+    Unknown,
+}
+
+// Where the program may go after the instruction. A call (jal to a register other than x0) also
+// goes on to the next instruction, where the callee returns
+pub fn successors(instructions: &[Instruction], index: usize) -> Vec<Successor> {
+    let inst = &instructions[index];
+    let next = match index + 1 < instructions.len() {
+        true => Successor::Instruction(index + 1),
+        false => Successor::Exit,
+    };
+    let target = || match jump_target(index, inst).map(usize::try_from) {
+        Some(Ok(target)) if target < instructions.len() => Successor::Instruction(target),
+        // Out of the program
+        _ => Successor::Unknown,
+    };
+
+    match inst.opcode() {
+        OpCodeType::B => vec![next, target()],
+        OpCodeType::J if inst.rd() != 0 => vec![target(), next],
+        OpCodeType::J => vec![target()],
+        _ if inst.mnemonic() == "jalr" => vec![Successor::Unknown],
+        _ => vec![next],
+    }
+}
+
+// Registers whose value may still be read, before and after each instruction
+pub struct Liveness {
+    pub live_in: Vec<RegisterSet>,
+    pub live_out: Vec<RegisterSet>,
+}
+
+impl Liveness {
+    // Backward data flow over the control flow graph until nothing changes
+    pub fn from_instructions(instructions: &[Instruction]) -> Liveness {
+        let len = instructions.len();
+        let mut live_in = vec![0; len];
+        let mut live_out = vec![0; len];
+
+        let reads: Vec<RegisterSet> = instructions
+            .iter()
+            .map(|inst| register_set(inst.operands().reads.into_iter().flatten()))
+            .collect();
+        let writes: Vec<RegisterSet> = instructions
+            .iter()
+            .map(|inst| register_set(inst.operands().write))
+            .collect();
+        let successors: Vec<Vec<Successor>> = (0..len)
+            .map(|index| successors(instructions, index))
+            .collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for index in (0..len).rev() {
+                let out = successors[index]
+                    .iter()
+                    .fold(0, |out, successor| match successor {
+                        Successor::Instruction(next) => out | live_in[*next],
+                        Successor::Exit => out,
+                        Successor::Unknown => ALL_REGISTERS,
+                    });
+                let new_in = reads[index] | (out & !writes[index]);
+
+                if out != live_out[index] || new_in != live_in[index] {
+                    live_out[index] = out;
+                    live_in[index] = new_in;
+                    changed = true;
+                }
+            }
+        }
+
+        Liveness { live_in, live_out }
+    }
+}
+
+// Straight-line run of instructions, entered only at start and left only after end
+pub struct BasicBlock {
+    pub start: usize,
+    pub end: usize,
+    pub successors: Vec<usize>,
+    // Whether it can leave the program or go to an address that is not known (jalr)
+    pub exits: bool,
+}
+
+// A write to a register and the instructions that may read that value
+pub struct Definition {
+    pub index: usize,
+    pub register: u8,
+    pub uses: Vec<usize>,
+}
+
+pub struct DataFlow {
+    pub blocks: Vec<BasicBlock>,
+    pub liveness: Liveness,
+    // In program order
+    pub definitions: Vec<Definition>,
+}
+
+fn basic_blocks(instructions: &[Instruction]) -> Vec<BasicBlock> {
+    let mut leaders = jump_targets(instructions);
+    if let Some(first) = leaders.first_mut() {
+        *first = true;
+    }
+    for (index, inst) in instructions.iter().enumerate() {
+        let ends_block = inst.opcode() == OpCodeType::B || is_jump(inst);
+        if let Some(next) = leaders.get_mut(index + 1).filter(|_| ends_block) {
+            *next = true;
+        }
+    }
+
+    let starts: Vec<usize> = (0..instructions.len())
+        .filter(|index| leaders[*index])
+        .collect();
+    let block_of = |index: usize| starts.partition_point(|start| *start <= index) - 1;
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(block, start)| {
+            let end = starts
+                .get(block + 1)
+                .map_or(instructions.len() - 1, |next| next - 1);
+            let mut successors = Vec::new();
+            let mut exits = false;
+
+            for successor in self::successors(instructions, end) {
+                match successor {
+                    Successor::Instruction(next) => successors.push(block_of(next)),
+                    Successor::Exit | Successor::Unknown => exits = true,
+                }
+            }
+
+            BasicBlock {
+                start: *start,
+                end,
+                successors,
+                exits,
+            }
+        })
+        .collect()
+}
+
+// Bit set over the definitions, one bit each
+#[derive(Clone, PartialEq)]
+struct DefinitionSet(Vec<u64>);
+
+impl DefinitionSet {
+    fn new(len: usize) -> DefinitionSet {
+        DefinitionSet(vec![0; len.div_ceil(64)])
+    }
+
+    fn contains(&self, definition: usize) -> bool {
+        self.0[definition / 64] & (1 << (definition % 64)) != 0
+    }
+
+    fn insert(&mut self, definition: usize) {
+        self.0[definition / 64] |= 1 << (definition % 64);
+    }
+
+    fn remove(&mut self, definition: usize) {
+        self.0[definition / 64] &= !(1 << (definition % 64));
+    }
+
+    fn union(&mut self, other: &DefinitionSet) {
+        for (word, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *word |= other;
+        }
+    }
+}
+
+impl DataFlow {
+    // Liveness, basic blocks and def-use chains (from reaching definitions) of the program
+    pub fn from_instructions(instructions: &[Instruction]) -> DataFlow {
+        let blocks = basic_blocks(instructions);
+        let liveness = Liveness::from_instructions(instructions);
+
+        let mut definitions: Vec<Definition> = Vec::new();
+        let mut definitions_of: Vec<Vec<usize>> = vec![Vec::new(); 32];
+        for (index, inst) in instructions.iter().enumerate() {
+            if let Some(register) = inst.operands().write {
+                definitions_of[register as usize].push(definitions.len());
+                definitions.push(Definition {
+                    index,
+                    register,
+                    uses: Vec::new(),
+                });
+            }
+        }
+
+        // Runs the instruction on the set of definitions that reach it
+        let apply = |reaching: &mut DefinitionSet, definition: usize| {
+            for other in definitions_of[definitions[definition].register as usize].iter() {
+                reaching.remove(*other);
+            }
+            reaching.insert(definition);
+        };
+        let definition_at = |index: usize| {
+            definitions
+                .binary_search_by_key(&index, |definition| definition.index)
+                .ok()
+        };
+
+        // Forward data flow of the definitions that reach the end of each block
+        let mut reach_out = vec![DefinitionSet::new(definitions.len()); blocks.len()];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); blocks.len()];
+        for (block, basic_block) in blocks.iter().enumerate() {
+            for successor in basic_block.successors.iter() {
+                predecessors[*successor].push(block);
+            }
+        }
+
+        let reach_in = |reach_out: &[DefinitionSet], block: usize| {
+            let mut reaching = DefinitionSet::new(definitions.len());
+            for predecessor in predecessors[block].iter() {
+                reaching.union(&reach_out[*predecessor]);
+            }
+            reaching
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for (block, basic_block) in blocks.iter().enumerate() {
+                let mut reaching = reach_in(&reach_out, block);
+                for index in basic_block.start..=basic_block.end {
+                    if let Some(definition) = definition_at(index) {
+                        apply(&mut reaching, definition);
+                    }
+                }
+
+                if reaching != reach_out[block] {
+                    reach_out[block] = reaching;
+                    changed = true;
+                }
+            }
+        }
+
+        let mut uses: Vec<Vec<usize>> = vec![Vec::new(); definitions.len()];
+        for (block, basic_block) in blocks.iter().enumerate() {
+            let mut reaching = reach_in(&reach_out, block);
+
+            let block_instructions = &instructions[basic_block.start..=basic_block.end];
+            for (index, inst) in (basic_block.start..).zip(block_instructions) {
+                for register in inst.operands().reads.into_iter().flatten() {
+                    for definition in definitions_of[register as usize].iter() {
+                        if reaching.contains(*definition) {
+                            uses[*definition].push(index);
+                        }
+                    }
+                }
+
+                if let Some(definition) = definition_at(index) {
+                    apply(&mut reaching, definition);
+                }
+            }
+        }
+
+        for (definition, mut uses) in definitions.iter_mut().zip(uses) {
+            uses.sort_unstable();
+            definition.uses = uses;
+        }
+
+        DataFlow {
+            blocks,
+            liveness,
+            definitions,
+        }
+    }
+
+    // Basic block the instruction belongs to
+    pub fn block(&self, index: usize) -> usize {
+        self.blocks.partition_point(|block| block.start <= index) - 1
+    }
+
+    pub fn definition(&self, index: usize) -> Option<&Definition> {
+        self.definitions
+            .binary_search_by_key(&index, |definition| definition.index)
+            .ok()
+            .map(|definition| &self.definitions[definition])
+    }
+
+    // Writes no instruction may read, x0 aside
+    pub fn unused_writes(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter().filter(|definition| {
+            !contains(
+                self.liveness.live_out[definition.index],
+                definition.register,
+            )
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InstructionLiveness {
+    pub index: usize,
+    pub instruction: String,
+    pub mnemonic: String,
+    pub block: usize,
+    pub live_in: Vec<String>,
+    pub live_out: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BasicBlockLiveness {
+    pub index: usize,
+    pub start: usize,
+    pub end: usize,
+    pub successors: Vec<usize>,
+    pub exits: bool,
+    pub live_in: Vec<String>,
+    pub live_out: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DefUseChain {
+    pub definition: usize,
+    pub register: String,
+    pub uses: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnusedWrite {
+    pub index: usize,
+    pub register: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LivenessReport {
+    pub instructions: Vec<InstructionLiveness>,
+    pub blocks: Vec<BasicBlockLiveness>,
+    pub def_use_chains: Vec<DefUseChain>,
+    pub unused_writes: Vec<UnusedWrite>,
+}
+
+pub fn liveness_report(instructions: &[Instruction]) -> LivenessReport {
+    let data_flow = DataFlow::from_instructions(instructions);
+    let live_in = &data_flow.liveness.live_in;
+    let live_out = &data_flow.liveness.live_out;
+
+    let mut instruction_reports = Vec::with_capacity(instructions.len());
+    let mut blocks = Vec::with_capacity(data_flow.blocks.len());
+    for (block, basic_block) in data_flow.blocks.iter().enumerate() {
+        for index in basic_block.start..=basic_block.end {
+            instruction_reports.push(InstructionLiveness {
+                index,
+                instruction: instructions[index].binary_text(),
+                mnemonic: instructions[index].mnemonic().to_string(),
+                block,
+                live_in: register_names(live_in[index]),
+                live_out: register_names(live_out[index]),
+            });
+        }
+
+        blocks.push(BasicBlockLiveness {
+            index: block,
+            start: basic_block.start,
+            end: basic_block.end,
+            successors: basic_block.successors.clone(),
+            exits: basic_block.exits,
+            live_in: register_names(live_in[basic_block.start]),
+            live_out: register_names(live_out[basic_block.end]),
+        });
+    }
+
+    LivenessReport {
+        instructions: instruction_reports,
+        blocks,
+        def_use_chains: data_flow
+            .definitions
+            .iter()
+            .map(|definition| DefUseChain {
+                definition: definition.index,
+                register: register_name(definition.register),
+                uses: definition.uses.clone(),
+            })
+            .collect(),
+        unused_writes: data_flow
+            .unused_writes()
+            .map(|definition| UnusedWrite {
+                index: definition.index,
+                register: register_name(definition.register),
+            })
+            .collect(),
+    }
+}
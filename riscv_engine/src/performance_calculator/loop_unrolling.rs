@@ -0,0 +1,406 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    organization::Organization,
+    performance_calculator::{
+        data_hazard::register_name,
+        liveness::{is_jump, jump_target},
+        pipeline_calculator,
+    },
+    pipeline_diagram::ORIGINAL_TECHNIQUE,
+    program::Program,
+    riscv_core::instruction::{Instruction, OpCodeType, INSTRUCTION_SIZE, ZERO_REGISTER},
+};
+
+pub const DEFAULT_UNROLL_FACTOR: usize = 4;
+// Past this the copies only make the listings longer
+pub const MAX_UNROLL_FACTOR: usize = 16;
+
+const INSTRUCTION_BYTES: i64 = (INSTRUCTION_SIZE / 8) as i64;
+
+// The factors the front ends accept, one copy would leave the loop as it is
+pub fn check_factor(factor: usize) -> Result<(), String> {
+    match (2..=MAX_UNROLL_FACTOR).contains(&factor) {
+        true => Ok(()),
+        false => Err(format!("must be between 2 and {}", MAX_UNROLL_FACTOR)),
+    }
+}
+
+// A loop tested at the top: head branches out to the instruction after latch and latch jumps back
+// to head. The induction register moves by step on every iteration and the other register of the
+// test is never written in the loop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountedLoop {
+    pub head: usize,
+    pub latch: usize,
+    pub induction: u8,
+    pub step: i32,
+}
+
+pub struct UnrolledLoop {
+    pub original: CountedLoop,
+    // Where the loop is in the unrolled program
+    pub unrolled: CountedLoop,
+}
+
+pub struct Unrolling {
+    pub factor: usize,
+    pub instructions: Vec<Instruction>,
+    pub loops: Vec<UnrolledLoop>,
+}
+
+fn target_index(index: usize, inst: &Instruction) -> Option<usize> {
+    jump_target(index, inst).and_then(|target| usize::try_from(target).ok())
+}
+
+// Branches and jal of the program that jump to each instruction
+fn jump_sources(instructions: &[Instruction]) -> Vec<Vec<usize>> {
+    let mut sources = vec![Vec::new(); instructions.len()];
+
+    for (index, inst) in instructions.iter().enumerate() {
+        if let Some(sources) = target_index(index, inst).and_then(|target| sources.get_mut(target))
+        {
+            sources.push(index);
+        }
+    }
+
+    sources
+}
+
+// The loop closed by the jal at latch, when it is a counted loop. Its body may only branch forward
+// inside the loop, and nothing outside may jump into it past the head
+fn counted_loop(
+    instructions: &[Instruction],
+    sources: &[Vec<usize>],
+    latch: usize,
+) -> Option<CountedLoop> {
+    let back = &instructions[latch];
+    if back.opcode() != OpCodeType::J || back.rd() != ZERO_REGISTER {
+        return None;
+    }
+
+    let head = target_index(latch, back).filter(|head| *head < latch)?;
+    let test = &instructions[head];
+    if test.opcode() != OpCodeType::B || target_index(head, test) != Some(latch + 1) {
+        return None;
+    }
+
+    for index in head + 1..=latch {
+        let inst = &instructions[index];
+        let enters_from_outside = sources[index]
+            .iter()
+            .any(|source| *source <= head || *source > latch);
+        let leaves = match inst.opcode() {
+            _ if index == latch => false,
+            OpCodeType::B => {
+                !matches!(target_index(index, inst), Some(target) if target > index && target <= latch)
+            }
+            // ecall may end the program
+            _ => is_jump(inst) || inst.mnemonic() == "ecall",
+        };
+
+        if enters_from_outside || leaves {
+            return None;
+        }
+    }
+
+    let writes = |register: u8| {
+        (head + 1..latch)
+            .filter(|index| instructions[*index].operands().write == Some(register))
+            .collect::<Vec<usize>>()
+    };
+    // A branch of the body skipping over the instruction
+    let skipped = |index: usize| {
+        (head + 1..index).any(|branch| {
+            target_index(branch, &instructions[branch]).is_some_and(|target| target > index)
+        })
+    };
+
+    [(test.rs1(), test.rs2()), (test.rs2(), test.rs1())]
+        .into_iter()
+        .find_map(|(induction, bound)| {
+            if induction == ZERO_REGISTER || induction == bound || !writes(bound).is_empty() {
+                return None;
+            }
+
+            let step = match writes(induction)[..] {
+                [index] if !skipped(index) => {
+                    let inst = &instructions[index];
+                    let counts = inst.mnemonic() == "addi" && inst.rs1() == induction;
+                    Some(inst.immediate()).filter(|step| counts && *step != 0)
+                }
+                _ => None,
+            }?;
+
+            Some(CountedLoop {
+                head,
+                latch,
+                induction,
+                step,
+            })
+        })
+}
+
+pub fn counted_loops(instructions: &[Instruction]) -> Vec<CountedLoop> {
+    let sources = jump_sources(instructions);
+
+    (0..instructions.len())
+        .filter_map(|latch| counted_loop(instructions, &sources, latch))
+        .collect()
+}
+
+// Where a branch or jal of the unrolled program has to jump
+#[derive(Clone, Copy)]
+enum Target {
+    // An instruction of the original program, wherever it ended up
+    Original(usize),
+    Unrolled(usize),
+}
+
+// Copies the test and body of every counted loop factor times before its latch. Each copy keeps
+// the exit test, so the loop still runs the right number of iterations whatever the count is, and
+// only the jump back is saved. The offsets of every branch and jal are fixed for the new layout.
+// The program is left as it is when an offset no longer fits
+pub fn unroll_loops(instructions: &[Instruction], factor: usize) -> Unrolling {
+    let loops = match factor {
+        0 | 1 => Vec::new(),
+        _ => counted_loops(instructions),
+    };
+
+    let mut unrolled: Vec<(Instruction, Option<Target>)> = Vec::new();
+    // Position of each original instruction in the unrolled program, the first copy for loops
+    let mut positions = vec![0; instructions.len() + 1];
+    let mut unrolled_loops = Vec::new();
+
+    let mut index = 0;
+    while index < instructions.len() {
+        let counted = match loops.iter().find(|counted| counted.head == index) {
+            Some(counted) => counted,
+            None => {
+                let inst = instructions[index];
+                positions[index] = unrolled.len();
+                unrolled.push((inst, target_index(index, &inst).map(Target::Original)));
+                index += 1;
+                continue;
+            }
+        };
+
+        let size = counted.latch - counted.head;
+        let start = unrolled.len();
+        let copy_start = |copy: usize| start + copy * size;
+
+        for copy in 0..factor {
+            for original in counted.head..counted.latch {
+                if copy == 0 {
+                    positions[original] = unrolled.len();
+                }
+
+                let inst = instructions[original];
+                let target = target_index(original, &inst).map(|target| match target {
+                    // The exit test
+                    _ if original == counted.head => Target::Original(target),
+                    // Skipping the rest of the body goes to the test of the next copy
+                    _ if target == counted.latch => Target::Unrolled(copy_start(copy + 1)),
+                    _ => Target::Unrolled(copy_start(copy) + target - counted.head),
+                });
+                unrolled.push((inst, target));
+            }
+        }
+
+        positions[counted.latch] = unrolled.len();
+        unrolled.push((
+            instructions[counted.latch],
+            Some(Target::Unrolled(copy_start(0))),
+        ));
+
+        unrolled_loops.push(UnrolledLoop {
+            original: *counted,
+            unrolled: CountedLoop {
+                head: start,
+                latch: copy_start(factor),
+                ..*counted
+            },
+        });
+        index = counted.latch + 1;
+    }
+    positions[instructions.len()] = unrolled.len();
+
+    let fixed: Option<Vec<Instruction>> = unrolled
+        .iter()
+        .enumerate()
+        .map(|(position, (inst, target))| {
+            let target = match target {
+                Some(Target::Original(target)) => positions.get(*target),
+                Some(Target::Unrolled(target)) => Some(target),
+                None => None,
+            };
+
+            match target {
+                Some(target) => {
+                    let offset = (*target as i64 - position as i64) * INSTRUCTION_BYTES;
+                    i32::try_from(offset)
+                        .ok()
+                        .and_then(|offset| inst.with_offset(offset))
+                }
+                // Not a branch, or a jump out of the program that cannot be followed
+                None => Some(*inst),
+            }
+        })
+        .collect();
+
+    match fixed {
+        Some(fixed) => Unrolling {
+            factor,
+            instructions: fixed,
+            loops: unrolled_loops,
+        },
+        None => Unrolling {
+            factor,
+            instructions: instructions.to_vec(),
+            loops: Vec::new(),
+        },
+    }
+}
+
+fn is_control(inst: &Instruction) -> bool {
+    matches!(inst.opcode(), OpCodeType::B | OpCodeType::J)
+}
+
+// Cycles from the head to the latch of the loop once a technique ran on the program. The
+// techniques never move branches and jal, so the n-th one of the program is still the n-th one
+fn loop_cycles(
+    organization: &Organization,
+    instructions: &[Instruction],
+    counted: &CountedLoop,
+    technique: &[Instruction],
+) -> Option<f32> {
+    let ordinal = |index: usize| {
+        instructions[..index]
+            .iter()
+            .filter(|inst| is_control(inst))
+            .count()
+    };
+    let mut controls = technique
+        .iter()
+        .enumerate()
+        .filter(|(_, inst)| is_control(inst))
+        .map(|(position, _)| position);
+
+    let head = controls.nth(ordinal(counted.head))?;
+    let latch = controls.nth(ordinal(counted.latch) - ordinal(counted.head) - 1)?;
+
+    Some(
+        technique[head..=latch]
+            .iter()
+            .map(|inst| organization.cpi_for(inst))
+            .sum(),
+    )
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnrollingResult {
+    pub technique_name: String,
+    pub cycles_per_iteration: f32,
+    pub unrolled_cycles_per_iteration: f32,
+    pub speedup: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnrolledLoopResult {
+    pub head: usize,
+    pub latch: usize,
+    pub induction: String,
+    pub step: i32,
+    pub unrolled_head: usize,
+    pub unrolled_latch: usize,
+    pub results: Vec<UnrollingResult>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct LoopUnrollingCalculator {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    pub factor: usize,
+    // Instructions of the unrolled program, before any technique
+    pub unrolled_instructions: usize,
+    pub loops: Vec<UnrolledLoopResult>,
+}
+
+impl LoopUnrollingCalculator {
+    // Runs the conflict techniques on the program and on its unrolled version, and compares the
+    // cycles each one spends per iteration of every counted loop
+    pub fn unrolling_from(
+        organization: &Organization,
+        program: &Program,
+        factor: usize,
+    ) -> LoopUnrollingCalculator {
+        let unrolling = unroll_loops(&program.instructions, factor);
+        let techniques = pipeline_calculator::techniques(&program.instructions);
+        let unrolled_techniques = pipeline_calculator::techniques(&unrolling.instructions);
+
+        let mut programs: Vec<(String, &[Instruction], &[Instruction])> = vec![(
+            ORIGINAL_TECHNIQUE.to_string(),
+            &program.instructions,
+            &unrolling.instructions,
+        )];
+        for (technique, unrolled) in techniques.iter().zip(unrolled_techniques.iter()) {
+            programs.push((
+                technique.technique_name.clone(),
+                &technique.instructions,
+                &unrolled.instructions,
+            ));
+        }
+
+        let loops = unrolling
+            .loops
+            .iter()
+            .map(|unrolled_loop| {
+                let results = programs
+                    .iter()
+                    .filter_map(|(technique_name, instructions, unrolled)| {
+                        let cycles = loop_cycles(
+                            organization,
+                            &program.instructions,
+                            &unrolled_loop.original,
+                            instructions,
+                        )?;
+                        let unrolled_cycles = loop_cycles(
+                            organization,
+                            &unrolling.instructions,
+                            &unrolled_loop.unrolled,
+                            unrolled,
+                        )? / unrolling.factor as f32;
+
+                        Some(UnrollingResult {
+                            technique_name: technique_name.clone(),
+                            cycles_per_iteration: cycles,
+                            unrolled_cycles_per_iteration: unrolled_cycles,
+                            speedup: cycles / unrolled_cycles,
+                        })
+                    })
+                    .collect();
+
+                UnrolledLoopResult {
+                    head: unrolled_loop.original.head,
+                    latch: unrolled_loop.original.latch,
+                    induction: register_name(unrolled_loop.original.induction),
+                    step: unrolled_loop.original.step,
+                    unrolled_head: unrolled_loop.unrolled.head,
+                    unrolled_latch: unrolled_loop.unrolled.latch,
+                    results,
+                }
+            })
+            .collect();
+
+        LoopUnrollingCalculator {
+            organization_name: organization.id.clone(),
+            bin_file_name: program.id.clone(),
+            factor: unrolling.factor,
+            unrolled_instructions: unrolling.instructions.len(),
+            loops,
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    performance_calculator::{
+        data_hazard::HazardKind,
+        liveness::{is_jump, jump_targets},
+    },
+    riscv_core::instruction::{Instruction, OpCodeType},
+};
+
+// How far back the hazard report looks for memory dependencies. The reorder techniques move an
+// instruction at most 3 positions up
+pub const MEMORY_WINDOW: usize = 8;
+
+const REGISTER_COUNT: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum AliasKind {
+    // Never the same bytes
+    NoAlias,
+    // The addresses could not be compared
+    MayAlias,
+    // Always some of the same bytes
+    MustAlias,
+}
+
+// A load or store that reads or writes memory after (or before) an earlier one
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MemoryDependency {
+    pub kind: HazardKind,
+    pub producer: usize,
+    pub consumer: usize,
+    pub distance: usize,
+    pub alias: AliasKind,
+}
+
+// Unknown value a register holds: what it had when the tracking started, or the result of an
+// instruction the tracking does not model
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Symbol {
+    Entry(u8),
+    Definition(usize),
+}
+
+// A symbol plus a constant, or just a constant when there is no symbol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Value {
+    symbol: Option<Symbol>,
+    offset: i64,
+}
+
+impl Value {
+    fn constant(offset: i64) -> Value {
+        Value {
+            symbol: None,
+            offset,
+        }
+    }
+
+    fn add(self, offset: i64) -> Value {
+        Value {
+            symbol: self.symbol,
+            offset: self.offset + offset,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct MemoryAccess {
+    is_store: bool,
+    address: Value,
+    bytes: i64,
+}
+
+impl MemoryAccess {
+    fn alias(&self, other: &MemoryAccess) -> AliasKind {
+        if self.address.symbol != other.address.symbol {
+            return AliasKind::MayAlias;
+        }
+
+        let start = self.address.offset.max(other.address.offset);
+        let end = (self.address.offset + self.bytes).min(other.address.offset + other.bytes);
+        if start < end {
+            AliasKind::MustAlias
+        } else {
+            AliasKind::NoAlias
+        }
+    }
+
+    // Two loads can always be swapped
+    fn dependency_kind(&self, later: &MemoryAccess) -> Option<HazardKind> {
+        match (self.is_store, later.is_store) {
+            (true, false) => Some(HazardKind::Raw),
+            (false, true) => Some(HazardKind::War),
+            (true, true) => Some(HazardKind::Waw),
+            (false, false) => None,
+        }
+    }
+}
+
+// Follows the register values along straight-line code, so the addresses of two accesses can be
+// compared. Only valid while no instruction in between is the target of a jump
+struct ValueTracker {
+    registers: [Value; REGISTER_COUNT],
+}
+
+impl ValueTracker {
+    fn new() -> ValueTracker {
+        ValueTracker {
+            registers: std::array::from_fn(|register| Value {
+                symbol: Some(Symbol::Entry(register as u8)),
+                offset: 0,
+            }),
+        }
+    }
+
+    fn value(&self, register: u8) -> Value {
+        match register {
+            0 => Value::constant(0),
+            _ => self.registers[register as usize],
+        }
+    }
+
+    fn access(&self, inst: &Instruction) -> Option<MemoryAccess> {
+        let is_store = match inst.opcode() {
+            OpCodeType::L => false,
+            OpCodeType::S => true,
+            _ => return None,
+        };
+
+        Some(MemoryAccess {
+            is_store,
+            address: self.value(inst.rs1()).add(inst.immediate() as i64),
+            // lb/sb, lh/sh, lw/sw and the unsigned loads
+            bytes: 1 << (inst.funct3() & 0b11),
+        })
+    }
+
+    fn step(&mut self, index: usize, inst: &Instruction) {
+        let register = match inst.operands().write {
+            Some(register) => register,
+            None => return,
+        };
+
+        let immediate = inst.immediate() as i64;
+        let rs1 = self.value(inst.rs1());
+        let rs2 = self.value(inst.rs2());
+        let value = match inst.mnemonic() {
+            "lui" => Some(Value::constant(immediate)),
+            "addi" => Some(rs1.add(immediate)),
+            "add" if rs2.symbol.is_none() => Some(rs1.add(rs2.offset)),
+            "add" if rs1.symbol.is_none() => Some(rs2.add(rs1.offset)),
+            "sub" if rs2.symbol.is_none() => Some(rs1.add(-rs2.offset)),
+            "slli" if rs1.symbol.is_none() => {
+                Some(Value::constant(rs1.offset << (immediate & 0x1f)))
+            }
+            _ => None,
+        };
+
+        self.registers[register as usize] = value.unwrap_or(Value {
+            symbol: Some(Symbol::Definition(index)),
+            offset: 0,
+        });
+    }
+}
+
+// Instructions where the register values are no longer known: the first one, the targets of
+// branches and jal, and the ones after jal and jalr (reached when a call returns). A branch that
+// is not taken falls through with its values
+pub fn tracking_starts(instructions: &[Instruction]) -> Vec<bool> {
+    let mut starts = jump_targets(instructions);
+    if let Some(first) = starts.first_mut() {
+        *first = true;
+    }
+
+    for (index, inst) in instructions.iter().enumerate() {
+        if is_jump(inst) {
+            if let Some(next) = starts.get_mut(index + 1) {
+                *next = true;
+            }
+        }
+    }
+
+    starts
+}
+
+// How the accesses of two instructions relate when the later one runs right after the earlier one
+// and whatever is between them, as when the later one is moved above the earlier one. starts comes
+// from tracking_starts: a jump target after the earlier one may bring other register values, so
+// the addresses cannot be compared. None when one of them does not access memory
+pub fn alias(
+    instructions: &[Instruction],
+    starts: &[bool],
+    earlier: usize,
+    later: usize,
+) -> Option<AliasKind> {
+    let mut tracker = ValueTracker::new();
+    let first = tracker.access(&instructions[earlier])?;
+
+    for (index, inst) in instructions.iter().enumerate().take(later).skip(earlier) {
+        tracker.step(index, inst);
+    }
+
+    let second = tracker.access(&instructions[later])?;
+    if starts[earlier + 1..=later].contains(&true) {
+        return Some(AliasKind::MayAlias);
+    }
+
+    Some(first.alias(&second))
+}
+
+// Whether moving the later instruction above the earlier one keeps the memory ordering. Only loads
+// can pass each other, and anything else only when they never touch the same bytes
+pub fn can_swap(
+    instructions: &[Instruction],
+    starts: &[bool],
+    earlier: usize,
+    later: usize,
+) -> bool {
+    let (first, second) = (&instructions[earlier], &instructions[later]);
+    if first.opcode() != OpCodeType::S && second.opcode() != OpCodeType::S {
+        return true;
+    }
+
+    matches!(
+        alias(instructions, starts, earlier, later),
+        None | Some(AliasKind::NoAlias)
+    )
+}
+
+// Memory dependencies of each instruction on the loads and stores in the MEMORY_WINDOW
+// instructions before it, back to the last jump target. Pairs that never touch the same bytes are left out
+pub fn memory_dependencies(instructions: &[Instruction]) -> Vec<Vec<MemoryDependency>> {
+    let starts = tracking_starts(instructions);
+    let mut dependencies = Vec::with_capacity(instructions.len());
+    let mut tracker = ValueTracker::new();
+    let mut recent: Vec<(usize, MemoryAccess)> = Vec::new();
+
+    for (index, inst) in instructions.iter().enumerate() {
+        if starts[index] {
+            tracker = ValueTracker::new();
+            recent.clear();
+        }
+
+        let mut instruction_dependencies = Vec::new();
+        if let Some(access) = tracker.access(inst) {
+            recent.retain(|(producer, _)| index - producer <= MEMORY_WINDOW);
+
+            for (producer, earlier) in recent.iter() {
+                let kind = match earlier.dependency_kind(&access) {
+                    Some(kind) => kind,
+                    None => continue,
+                };
+
+                let alias = earlier.alias(&access);
+                if alias != AliasKind::NoAlias {
+                    instruction_dependencies.push(MemoryDependency {
+                        kind,
+                        producer: *producer,
+                        consumer: index,
+                        distance: index - producer,
+                        alias,
+                    });
+                }
+            }
+
+            recent.push((index, access));
+        }
+
+        tracker.step(index, inst);
+        dependencies.push(instruction_dependencies);
+    }
+
+    dependencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(lines: &[&str]) -> Vec<Instruction> {
+        lines
+            .iter()
+            .map(|line| Instruction::new(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn loop_targets_stop_the_address_comparison() {
+        // sw x6, 0(x10); addi x10, x10, 4; lw x7, 0(x10)
+        let straight = program(&[
+            "00000000011001010010000000100011",
+            "00000000010001010000010100010011",
+            "00000000000001010010001110000011",
+        ]);
+        let starts = tracking_starts(&straight);
+        assert_eq!(alias(&straight, &starts, 0, 2), Some(AliasKind::NoAlias));
+        assert!(can_swap(&straight, &starts, 0, 2));
+
+        // bne x7, x0, -8 loops back to the addi, so x10 at the lw may be what the sw wrote to
+        let mut looped = straight.clone();
+        looped.extend(program(&["11111110000000111001110011100011"]));
+        let starts = tracking_starts(&looped);
+        assert!(starts[1]);
+        assert_eq!(alias(&looped, &starts, 0, 2), Some(AliasKind::MayAlias));
+        assert!(!can_swap(&looped, &starts, 0, 2));
+    }
+}
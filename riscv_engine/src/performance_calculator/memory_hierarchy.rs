@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    organization::Organization,
+    performance_calculator::energy::EnergyEstimate,
+    program::Program,
+    riscv_core::executor::{self, AccessKind, Execution, StopReason},
+    units,
+};
+
+pub const DEFAULT_CACHE_SIZE: usize = 1024;
+pub const DEFAULT_BLOCK_SIZE: usize = 16;
+pub const DEFAULT_ASSOCIATIVITY: usize = 1;
+pub const DEFAULT_MISS_PENALTY: usize = 20;
+pub const MAX_CACHE_SIZE: usize = 1 << 20;
+pub const MAX_MISS_PENALTY: usize = 1000;
+// Smallest block that holds a word
+const MIN_BLOCK_SIZE: usize = 4;
+// Seed of the random replacement, so the same request gives the same result
+const RANDOM_SEED: u32 = 0x2545_f491;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Replacement {
+    Lru,
+    Fifo,
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WritePolicy {
+    // Stores only mark the block dirty, and a store miss brings the block in first
+    WriteBack,
+    // Every store goes to memory too, and a store miss leaves the cache as it was
+    WriteThrough,
+}
+
+// Sizes are in bytes. A hit is part of the instruction CPI, a miss stalls for miss_penalty
+// cycles. Writes to memory go through a write buffer and never stall
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CacheConfig {
+    pub size: usize,
+    pub block_size: usize,
+    pub associativity: usize,
+    pub replacement: Replacement,
+    pub write_policy: WritePolicy,
+    pub miss_penalty: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            size: DEFAULT_CACHE_SIZE,
+            block_size: DEFAULT_BLOCK_SIZE,
+            associativity: DEFAULT_ASSOCIATIVITY,
+            replacement: Replacement::Lru,
+            write_policy: WritePolicy::WriteBack,
+            miss_penalty: DEFAULT_MISS_PENALTY,
+        }
+    }
+}
+
+impl CacheConfig {
+    // Fields that cannot build a cache, with the reason
+    pub fn validate(&self) -> Vec<(&'static str, String)> {
+        let mut errors = Vec::new();
+
+        if !self.size.is_power_of_two() || self.size > MAX_CACHE_SIZE {
+            errors.push((
+                "size",
+                format!("must be a power of two up to {}", MAX_CACHE_SIZE),
+            ));
+        }
+        if !self.block_size.is_power_of_two()
+            || self.block_size < MIN_BLOCK_SIZE
+            || self.block_size > self.size
+        {
+            errors.push((
+                "block_size",
+                format!(
+                    "must be a power of two between {} and the cache size",
+                    MIN_BLOCK_SIZE
+                ),
+            ));
+        } else if !self.associativity.is_power_of_two()
+            || self.associativity > self.size / self.block_size
+        {
+            errors.push((
+                "associativity",
+                "must be a power of two up to the number of blocks".to_string(),
+            ));
+        }
+        if self.miss_penalty > MAX_MISS_PENALTY {
+            errors.push((
+                "miss_penalty",
+                format!("must be at most {}", MAX_MISS_PENALTY),
+            ));
+        }
+
+        errors
+    }
+
+    pub fn sets(&self) -> usize {
+        self.size / self.block_size / self.associativity
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Line {
+    tag: u32,
+    dirty: bool,
+    // Access count when the line was filled (FIFO) or last used (LRU)
+    stamp: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CacheStats {
+    pub accesses: usize,
+    pub hits: usize,
+    pub misses: usize,
+    pub hit_rate: f32,
+    // Dirty blocks written back on eviction
+    pub write_backs: usize,
+    // Stores sent straight to memory by write-through
+    pub memory_writes: usize,
+    // Average memory access time in cycles, taking a hit as one cycle
+    pub amat: f32,
+    pub stall_cycles: usize,
+}
+
+// One cache level in front of memory, fed one access at a time
+pub struct Cache {
+    config: CacheConfig,
+    sets: Vec<Vec<Line>>,
+    stats: CacheStats,
+    random: u32,
+}
+
+impl Cache {
+    pub fn new(config: CacheConfig) -> Cache {
+        Cache {
+            config,
+            sets: vec![Vec::with_capacity(config.associativity); config.sets()],
+            stats: CacheStats::default(),
+            random: RANDOM_SEED,
+        }
+    }
+
+    // xorshift32
+    fn next_random(&mut self) -> usize {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 17;
+        self.random ^= self.random << 5;
+        self.random as usize
+    }
+
+    // Accesses spanning two blocks count once, on the block of their first byte
+    pub fn access(&mut self, address: u32, write: bool) -> bool {
+        let stamp = self.stats.accesses;
+        self.stats.accesses += 1;
+
+        let block = address / self.config.block_size as u32;
+        let set_count = self.sets.len() as u32;
+        let set_index = (block % set_count) as usize;
+        let tag = block / set_count;
+        let write_back = self.config.write_policy == WritePolicy::WriteBack;
+
+        if write && !write_back {
+            self.stats.memory_writes += 1;
+        }
+
+        if let Some(line) = self.sets[set_index].iter_mut().find(|line| line.tag == tag) {
+            self.stats.hits += 1;
+            if self.config.replacement == Replacement::Lru {
+                line.stamp = stamp;
+            }
+            line.dirty |= write && write_back;
+            return true;
+        }
+
+        self.stats.misses += 1;
+        self.stats.stall_cycles += self.config.miss_penalty;
+        if write && !write_back {
+            return false;
+        }
+
+        let line = Line {
+            tag,
+            dirty: write,
+            stamp,
+        };
+        if self.sets[set_index].len() < self.config.associativity {
+            self.sets[set_index].push(line);
+            return false;
+        }
+
+        let victim = match self.config.replacement {
+            Replacement::Random => self.next_random() % self.config.associativity,
+            Replacement::Lru | Replacement::Fifo => self.sets[set_index]
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, line)| line.stamp)
+                .map(|(way, _)| way)
+                .unwrap_or(0),
+        };
+        if self.sets[set_index][victim].dirty {
+            self.stats.write_backs += 1;
+        }
+        self.sets[set_index][victim] = line;
+
+        false
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let accesses = self.stats.accesses;
+        let miss_rate = match accesses {
+            0 => 0.0,
+            _ => self.stats.misses as f32 / accesses as f32,
+        };
+
+        CacheStats {
+            hit_rate: match accesses {
+                0 => 0.0,
+                _ => 1.0 - miss_rate,
+            },
+            amat: 1.0 + miss_rate * self.config.miss_penalty as f32,
+            ..self.stats.clone()
+        }
+    }
+}
+
+// Feeds the fetches to the instruction cache and the loads and stores to the data cache
+pub fn simulate(
+    execution: &Execution,
+    instruction_cache: CacheConfig,
+    data_cache: CacheConfig,
+) -> (CacheStats, CacheStats) {
+    let mut instruction_cache = Cache::new(instruction_cache);
+    let mut data_cache = Cache::new(data_cache);
+
+    for access in &execution.trace {
+        match access.kind {
+            AccessKind::Fetch => instruction_cache.access(access.address, false),
+            AccessKind::Load => data_cache.access(access.address, false),
+            AccessKind::Store => data_cache.access(access.address, true),
+        };
+    }
+
+    (instruction_cache.stats(), data_cache.stats())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStop {
+    Exit,
+    EndOfProgram,
+    InvalidJump,
+    Breakpoint,
+    StepLimit,
+}
+
+impl From<StopReason> for ExecutionStop {
+    fn from(stop: StopReason) -> Self {
+        match stop {
+            StopReason::Exit => ExecutionStop::Exit,
+            StopReason::EndOfProgram => ExecutionStop::EndOfProgram,
+            StopReason::InvalidJump => ExecutionStop::InvalidJump,
+            StopReason::Breakpoint => ExecutionStop::Breakpoint,
+            StopReason::StepLimit => ExecutionStop::StepLimit,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CacheCalculator {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    pub instruction_cache_config: CacheConfig,
+    pub data_cache_config: CacheConfig,
+    pub stop: ExecutionStop,
+    // Instructions run, not the size of the program
+    pub executed_instructions: usize,
+    pub instruction_cache: CacheStats,
+    pub data_cache: CacheStats,
+    // Cycles with every access hitting, from the organization CPIs
+    pub base_cycles: f32,
+    pub stall_cycles: usize,
+    pub total_cycles: f32,
+    pub base_cpi: f32,
+    pub cpi: f32,
+    pub base_execution_time: f32,
+    pub execution_time: f32,
+    pub formatted_execution_time: String,
+    pub mips: f32,
+    // Static energy also runs during the stalls
+    pub energy: EnergyEstimate,
+}
+
+impl CacheCalculator {
+    // Runs the program and adds the miss stalls of both caches to the cycles of the organization
+    pub fn cache_from(
+        organization: &Organization,
+        program: &Program,
+        instruction_cache_config: CacheConfig,
+        data_cache_config: CacheConfig,
+    ) -> CacheCalculator {
+        let execution = executor::execute(&program.instructions, executor::MAX_STEPS);
+        let (instruction_cache, data_cache) =
+            simulate(&execution, instruction_cache_config, data_cache_config);
+
+        let executed_instructions = execution.executed.len();
+        let base_cycles: f32 = execution
+            .executed
+            .iter()
+            .map(|index| organization.cpi_for(&program.instructions[*index]))
+            .sum();
+        let stall_cycles = instruction_cache.stall_cycles + data_cache.stall_cycles;
+        let total_cycles = base_cycles + stall_cycles as f32;
+        let per_instruction = |cycles: f32| match executed_instructions {
+            0 => 0.0,
+            _ => cycles / executed_instructions as f32,
+        };
+        let execution_time = total_cycles * organization.clock_period;
+
+        CacheCalculator {
+            organization_name: organization.id.clone(),
+            bin_file_name: program.id.clone(),
+            instruction_cache_config,
+            data_cache_config,
+            stop: ExecutionStop::from(execution.stop),
+            executed_instructions,
+            instruction_cache,
+            data_cache,
+            base_cycles,
+            stall_cycles,
+            total_cycles,
+            base_cpi: per_instruction(base_cycles),
+            cpi: per_instruction(total_cycles),
+            base_execution_time: base_cycles * organization.clock_period,
+            execution_time,
+            formatted_execution_time: units::format_time(execution_time),
+            mips: units::mips(executed_instructions, execution_time),
+            energy: EnergyEstimate::from_run(
+                organization,
+                execution
+                    .executed
+                    .iter()
+                    .map(|index| &program.instructions[*index]),
+                execution_time,
+            ),
+        }
+    }
+}
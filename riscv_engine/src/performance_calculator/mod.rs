@@ -0,0 +1,13 @@
+pub mod data_hazard;
+pub mod design_space;
+pub mod energy;
+pub mod liveness;
+pub mod loop_unrolling;
+pub mod memory_dependency;
+pub mod memory_hierarchy;
+pub mod monocycle_calculator;
+pub mod pipeline_calculator;
+pub mod register_renaming;
+pub mod suite_calculator;
+pub mod superscalar;
+pub mod tomasulo;
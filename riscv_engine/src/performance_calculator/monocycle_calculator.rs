@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::Locale, organization::Organization, performance_calculator::energy::EnergyEstimate,
+    program::Program, units,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "openapi", schema(as = MonocyclePerformance))]
+pub struct PerformanceCalculator {
+    pub basic_information: BasicInformation,
+    pub result: Result,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "openapi", schema(as = MonocycleBasicInformation))]
+pub struct BasicInformation {
+    pub organization_a_name: String,
+    pub organization_a_clock_time: f32,
+    pub organization_a_clock_frequency: f32,
+    pub organization_a_clock: String,
+    pub organization_b_name: String,
+    pub organization_b_clock_time: f32,
+    pub organization_b_clock_frequency: f32,
+    pub organization_b_clock: String,
+    pub bin_file_name: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "openapi", schema(as = MonocycleResult))]
+pub struct Result {
+    pub total_cicles_organization_a: f32,
+    pub total_cicles_organization_b: f32,
+    pub average_cpi_organization_a: f32,
+    pub average_cpi_organization_b: f32,
+    pub execution_time_organization_a: f32,
+    pub execution_time_organization_b: f32,
+    pub formatted_execution_time_organization_a: String,
+    pub formatted_execution_time_organization_b: String,
+    pub mips_organization_a: f32,
+    pub mips_organization_b: f32,
+    pub instructions_per_second_organization_a: f32,
+    pub instructions_per_second_organization_b: f32,
+    pub energy_organization_a: EnergyEstimate,
+    pub energy_organization_b: EnergyEstimate,
+    // comparison written in the requested language (pt-BR unless localized)
+    pub best_performance: String,
+    pub comparison: SpeedupComparison,
+}
+
+// Which organization is faster and by how much, so clients can word the result themselves
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SpeedupComparison {
+    pub winner: String,
+    pub loser: String,
+    // Texec(loser) / Texec(winner), 1 when equally_fast
+    pub speedup: f32,
+    // Then winner is organization A and loser organization B
+    pub equally_fast: bool,
+}
+
+impl SpeedupComparison {
+    // Performance = Texec(GreaterValue) / Texec(LesserValue)
+    pub fn from_execution_times(
+        organization_a_name: &str,
+        execution_time_a: f32,
+        organization_b_name: &str,
+        execution_time_b: f32,
+    ) -> SpeedupComparison {
+        let (winner, loser, speedup) = if execution_time_a < execution_time_b {
+            (
+                organization_a_name,
+                organization_b_name,
+                execution_time_b / execution_time_a,
+            )
+        } else if execution_time_a > execution_time_b {
+            (
+                organization_b_name,
+                organization_a_name,
+                execution_time_a / execution_time_b,
+            )
+        } else {
+            (organization_a_name, organization_b_name, 1.0)
+        };
+
+        SpeedupComparison {
+            winner: winner.to_string(),
+            loser: loser.to_string(),
+            speedup,
+            equally_fast: execution_time_a == execution_time_b,
+        }
+    }
+}
+
+impl PerformanceCalculator {
+    pub fn monocycle_from(
+        organization_a: &Organization,
+        organization_b: &Organization,
+        program: &Program,
+    ) -> PerformanceCalculator {
+        // Start: calculating instruction info
+        let total_instructions = program.instructions.len();
+        let mut total_cicles_a: f32 = 0.0;
+        let mut total_cicles_b: f32 = 0.0;
+
+        for inst in program.instructions.iter() {
+            total_cicles_a += organization_a.cpi_for(inst);
+            total_cicles_b += organization_b.cpi_for(inst);
+        }
+
+        // CPI = total_cycles (with acordingly Instruction cycle) / total_instructions
+        let average_cpi_a = total_cicles_a / total_instructions as f32;
+        let average_cpi_b = total_cicles_b / total_instructions as f32;
+
+        // Texec = Total Instructions * CPI * TClock (clock is normalized to a period, even if given as a frequency)
+        let execution_time_a =
+            total_instructions as f32 * average_cpi_a * organization_a.clock_period; // In seconds
+        let execution_time_b =
+            total_instructions as f32 * average_cpi_b * organization_b.clock_period; // In seconds
+
+        let comparison = SpeedupComparison::from_execution_times(
+            &organization_a.id,
+            execution_time_a,
+            &organization_b.id,
+            execution_time_b,
+        );
+        // End: calculating instruction info
+
+        // Start: function return
+        PerformanceCalculator {
+            basic_information: BasicInformation {
+                organization_a_clock_time: organization_a.clock_period,
+                organization_a_clock_frequency: organization_a.clock_frequency(),
+                organization_a_clock: format_clock(organization_a),
+                organization_a_name: organization_a.id.clone(),
+                organization_b_clock_time: organization_b.clock_period,
+                organization_b_clock_frequency: organization_b.clock_frequency(),
+                organization_b_clock: format_clock(organization_b),
+                organization_b_name: organization_b.id.clone(),
+                bin_file_name: program.id.clone(),
+            },
+            result: Result {
+                total_cicles_organization_a: total_cicles_a,
+                total_cicles_organization_b: total_cicles_b,
+                average_cpi_organization_a: average_cpi_a,
+                average_cpi_organization_b: average_cpi_b,
+                execution_time_organization_a: execution_time_a,
+                execution_time_organization_b: execution_time_b,
+                formatted_execution_time_organization_a: units::format_time(execution_time_a),
+                formatted_execution_time_organization_b: units::format_time(execution_time_b),
+                mips_organization_a: units::mips(total_instructions, execution_time_a),
+                mips_organization_b: units::mips(total_instructions, execution_time_b),
+                instructions_per_second_organization_a: units::instructions_per_second(
+                    total_instructions,
+                    execution_time_a,
+                ),
+                instructions_per_second_organization_b: units::instructions_per_second(
+                    total_instructions,
+                    execution_time_b,
+                ),
+                energy_organization_a: EnergyEstimate::from_run(
+                    organization_a,
+                    program.instructions.iter(),
+                    execution_time_a,
+                ),
+                energy_organization_b: EnergyEstimate::from_run(
+                    organization_b,
+                    program.instructions.iter(),
+                    execution_time_b,
+                ),
+                best_performance: best_performance(&comparison, Locale::default()),
+                comparison,
+            },
+        }
+        // End: function return
+    }
+
+    // Rewrites best_performance in another language
+    pub fn localize(&mut self, locale: Locale) {
+        self.result.best_performance = best_performance(&self.result.comparison, locale);
+    }
+}
+
+// The comparison as a sentence from the locale's catalog
+pub fn best_performance(comparison: &SpeedupComparison, locale: Locale) -> String {
+    let catalog = locale.catalog();
+
+    if comparison.equally_fast {
+        catalog
+            .equally_fast
+            .replace("{a}", &comparison.winner)
+            .replace("{b}", &comparison.loser)
+    } else {
+        catalog
+            .faster
+            .replace("{winner}", &comparison.winner)
+            .replace("{loser}", &comparison.loser)
+            .replace("{speedup}", &comparison.speedup.to_string())
+    }
+}
+
+// e.g. "1 ns (1 GHz)"
+pub fn format_clock(organization: &Organization) -> String {
+    format!(
+        "{} ({})",
+        units::format_time(organization.clock_period),
+        units::format_frequency(organization.clock_frequency())
+    )
+}
@@ -0,0 +1,442 @@
+use std::{fs, io, path::Path};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    organization::Organization,
+    performance_calculator::{
+        data_hazard::{check_for_hazards, check_for_hazards_with_forwarding, check_for_reorder},
+        energy::EnergyEstimate,
+        register_renaming::rename_registers,
+    },
+    program::Program,
+    riscv_core::instruction::{Instruction, OpCodeType},
+    units,
+};
+
+use super::monocycle_calculator::format_clock;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "openapi", schema(as = PipelinePerformance))]
+pub struct PerformanceCalculator {
+    pub basic_information: BasicInformation,
+    pub results: Vec<TechniqueResult>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "openapi", schema(as = PipelineBasicInformation))]
+pub struct BasicInformation {
+    pub organization_name: String,
+    pub organization_clock_time: f32,
+    pub organization_clock_frequency: f32,
+    pub organization_clock: String,
+    pub bin_file_name: String,
+    pub best_performance: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TechniqueResult {
+    pub technique_name: String,
+    pub total_cicles: f32,
+    pub cicles_diference: f32,
+    pub average_cpi: f32,
+    pub execution_time: f32,
+    pub formatted_execution_time: String,
+    pub mips: f32,
+    pub instructions_per_second: f32,
+    pub performance: f32,
+    // NOPs cost the energy of addi
+    pub energy: EnergyEstimate,
+    // WAR and WAW dependencies the technique removed by renaming registers
+    pub false_dependencies_removed: usize,
+}
+
+// The program produced by a conflict technique
+#[derive(Clone)]
+pub struct TechniqueProgram {
+    pub technique_name: String,
+    pub file_name: &'static str,
+    // Whether the technique assumes the pipeline forwards results between stages
+    pub forwarding: bool,
+    pub false_dependencies_removed: usize,
+    pub instructions: Vec<Instruction>,
+}
+
+// Names of the conflict techniques, in the order they are run and reported
+pub const TECHNIQUE_NAMES: [&str; 5] = [
+    "only_nops",
+    "forwading_with_nops",
+    "reorder_with_only_nops",
+    "forwading_and_reorder_with_nops",
+    "renaming_with_nops",
+];
+
+// Runs one conflict technique on the original program, None for an unknown name
+pub fn technique(technique_name: &str, instructions: &[Instruction]) -> Option<TechniqueProgram> {
+    let technique = |file_name, forwarding, instructions| TechniqueProgram {
+        technique_name: technique_name.to_string(),
+        file_name,
+        forwarding,
+        false_dependencies_removed: 0,
+        instructions,
+    };
+
+    match technique_name {
+        "only_nops" => Some(technique("only_nops.txt", false, only_nops(instructions))),
+        "forwading_with_nops" => Some(technique(
+            "forwarding_with_nops.txt",
+            true,
+            forwading_with_nops(instructions),
+        )),
+        "reorder_with_only_nops" => Some(technique(
+            "reorder_with_only_nops.txt",
+            false,
+            reorder_with_only_nops(instructions),
+        )),
+        "forwading_and_reorder_with_nops" => Some(technique(
+            "forwading_and_reorder_with_nops.txt",
+            true,
+            forwarding_and_reorder_with_nops(instructions),
+        )),
+        "renaming_with_nops" => {
+            let renaming = rename_registers(instructions);
+            Some(TechniqueProgram {
+                false_dependencies_removed: renaming.false_dependencies_removed,
+                ..technique(
+                    "renaming_with_nops.txt",
+                    false,
+                    only_nops(&renaming.instructions),
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+// Runs every conflict technique on the original program
+pub fn techniques(instructions: &[Instruction]) -> Vec<TechniqueProgram> {
+    TECHNIQUE_NAMES
+        .iter()
+        .filter_map(|technique_name| technique(technique_name, instructions))
+        .collect()
+}
+
+// Writes each technique program to its own file in dir, one instruction per line
+pub fn write_technique_files(dir: &Path, techniques: &[TechniqueProgram]) -> io::Result<()> {
+    for technique in techniques {
+        let mut content = String::new();
+        for inst in technique.instructions.iter() {
+            content.push_str(&format!("{}\n", inst.binary_text()));
+        }
+
+        fs::write(dir.join(technique.file_name), content)?;
+    }
+
+    Ok(())
+}
+
+impl PerformanceCalculator {
+    pub fn pipeline_from(organization: &Organization, program: &Program) -> PerformanceCalculator {
+        Self::from_techniques(organization, program, &techniques(&program.instructions))
+    }
+
+    // Same as pipeline_from, for callers that also need the technique programs
+    pub fn from_techniques(
+        organization: &Organization,
+        program: &Program,
+        techniques: &[TechniqueProgram],
+    ) -> PerformanceCalculator {
+        // Start: Calc Performance
+        let results = Self::calc_techniques(organization, &program.instructions, techniques);
+        // End: Calc Performance
+
+        // Start: define best technique performance
+        let mut sorted_results = results.clone();
+        sorted_results.sort_by(|a, b| b.performance.total_cmp(&a.performance));
+
+        let best_performance = sorted_results
+            .iter()
+            .map(|technique| technique.technique_name.clone())
+            .collect();
+        // End: define best technique performance
+
+        let basic_information = BasicInformation {
+            organization_name: organization.id.clone(),
+            organization_clock_time: organization.clock_period,
+            organization_clock_frequency: organization.clock_frequency(),
+            organization_clock: format_clock(organization),
+            bin_file_name: program.id.clone(),
+            best_performance,
+        };
+
+        PerformanceCalculator {
+            basic_information,
+            results,
+        }
+    }
+
+    fn calc_techniques(
+        organization: &Organization,
+        instructions: &[Instruction],
+        techniques: &[TechniqueProgram],
+    ) -> Vec<TechniqueResult> {
+        // Elaborar uma analise de desempenho que avalie o sobrecusto em instrucoes da solucao, o tempo de execucao e o numero de ciclos de programa da solucao em Pipeline selecionada considerando um tempo de clock fornecido pelo usuario.
+        let mut techniques_result: Vec<TechniqueResult> = Vec::new();
+
+        // Calc from original
+        // Start: calculating instruction info
+        let total_instructions = instructions.len();
+        let mut total_cicles: f32 = 0.0;
+
+        for inst in instructions {
+            total_cicles += organization.cpi_for(inst);
+        }
+
+        // CPI = total_cycles (with acordingly Instruction cycle) / total_instructions
+        let average_cpi = total_cicles / total_instructions as f32;
+
+        // Texec = Total Instructions * CPI * TClock
+        let execution_time = total_instructions as f32 * average_cpi * organization.clock_period; // In seconds
+
+        let performance = 1.0; // A performance do original sempre sera 1.0
+
+        let original = TechniqueResult {
+            technique_name: "original".to_string(),
+            total_cicles,
+            cicles_diference: 0.0,
+            average_cpi,
+            execution_time,
+            formatted_execution_time: units::format_time(execution_time),
+            mips: units::mips(total_instructions, execution_time),
+            instructions_per_second: units::instructions_per_second(
+                total_instructions,
+                execution_time,
+            ),
+            performance,
+            energy: EnergyEstimate::from_run(organization, instructions, execution_time),
+            false_dependencies_removed: 0,
+        };
+        // End: calculating instruction info
+
+        techniques_result.push(original);
+
+        // Calc from every technique, in the order they were run
+        for technique in techniques {
+            let mut technique_result = Self::calc_performance(
+                organization,
+                &technique.instructions,
+                total_cicles,
+                execution_time,
+                technique.technique_name.clone(),
+            );
+            technique_result.false_dependencies_removed = technique.false_dependencies_removed;
+
+            techniques_result.push(technique_result);
+        }
+
+        techniques_result
+    }
+
+    fn calc_performance(
+        organization: &Organization,
+        instructions: &[Instruction],
+        original_cicles: f32,
+        original_exec_time: f32,
+        technique_name: String,
+    ) -> TechniqueResult {
+        // Start: calculating instruction info
+        let total_instructions = instructions.len();
+        let mut total_cicles: f32 = 0.0;
+
+        for inst in instructions {
+            total_cicles += organization.cpi_for(inst);
+        }
+
+        // CPI = total_cycles (with acordingly Instruction cycle) / total_instructions
+        let average_cpi = total_cicles / total_instructions as f32;
+
+        // Texec = Total Instructions * CPI * TClock
+        let execution_time = total_instructions as f32 * average_cpi * organization.clock_period; // In seconds
+
+        let cicles_diference = total_cicles - original_cicles;
+
+        let mut performance = 1.0;
+        if original_exec_time > 0.0 {
+            performance = original_exec_time / execution_time;
+        }
+        // End: calculating instruction info
+
+        // Start: function return
+        TechniqueResult {
+            technique_name,
+            cicles_diference,
+            average_cpi,
+            execution_time,
+            formatted_execution_time: units::format_time(execution_time),
+            mips: units::mips(total_instructions, execution_time),
+            instructions_per_second: units::instructions_per_second(
+                total_instructions,
+                execution_time,
+            ),
+            performance,
+            total_cicles,
+            energy: EnergyEstimate::from_run(organization, instructions, execution_time),
+            false_dependencies_removed: 0,
+        }
+        // End: function return
+    }
+}
+
+pub fn only_nops(instructions: &[Instruction]) -> Vec<Instruction> {
+    // Considerar que não há nenhuma solução em hardware para conflitos e incluir NOPs, quando necessário, para evitar o conflito de dados.
+    let mut nop_counter = 0;
+    let mut instructions_with_nops = Vec::with_capacity(instructions.len());
+
+    let hazards = check_for_hazards(instructions);
+
+    for (index, inst) in instructions.iter().enumerate() {
+        instructions_with_nops.push(*inst);
+
+        for _ in 0..hazards[index].highest_nops() {
+            instructions_with_nops.push(Instruction::NOP);
+            nop_counter += 1;
+        }
+    }
+
+    debug!("NOP: {}", nop_counter);
+
+    instructions_with_nops
+}
+
+pub fn forwading_with_nops(instructions: &[Instruction]) -> Vec<Instruction> {
+    // Considerar que foi implementada a técnica de forwarding e inserir NOPs, quando necessário, para evitar conflito de dados.
+    // Somente inserir nops para instrucoes de formato L
+
+    let mut nop_counter = 0;
+    let mut forwading_with_nops = Vec::with_capacity(instructions.len());
+
+    let hazards = check_for_hazards_with_forwarding(instructions);
+    for (index, inst) in instructions.iter().enumerate() {
+        forwading_with_nops.push(*inst);
+
+        let nops = hazards[index];
+
+        for _ in 0..nops {
+            forwading_with_nops.push(Instruction::NOP);
+            nop_counter += 1;
+        }
+    }
+
+    debug!("Forwading with NOPs: {}", nop_counter);
+
+    forwading_with_nops
+}
+
+pub fn reorder_with_only_nops(instructions: &[Instruction]) -> Vec<Instruction> {
+    // Considerar que não há nenhuma solução em hardware para conflitos e quando possível reordenar as instruções e quando não for possível inserir NOPs, para evitar conflito de dados.
+    //     a. Por exemplo, é possível que o programa não tenha nenhuma instrução, a diante no código, para ser reordenada.
+
+    let mut nop_counter = 0;
+    let only_nops = only_nops(instructions);
+    let mut reorder_with_only_nops: Vec<Instruction> = Vec::with_capacity(only_nops.len());
+
+    let can_reorder = check_for_reorder(&only_nops);
+
+    // Nao reordena a primeira instrucao e inst do formato J e B
+    for (index, current_inst) in only_nops.iter().enumerate() {
+        if current_inst.is_nop() {
+            reorder_with_only_nops.push(*current_inst);
+            nop_counter += 1;
+        } else {
+            reorder_with_only_nops.push(*current_inst);
+
+            if let Some(bool_insts) = can_reorder[index] {
+                if bool_insts[1] && index >= 3 {
+                    let prev_index = index - 2;
+
+                    reorder_with_only_nops.remove(prev_index);
+                    reorder_with_only_nops.insert(prev_index, *current_inst);
+                    reorder_with_only_nops.remove(index);
+
+                    nop_counter -= 1;
+                }
+            }
+        }
+    }
+
+    debug!("Reorder With NOPs: {}", nop_counter);
+
+    reorder_with_only_nops
+}
+
+pub fn forwarding_and_reorder_with_nops(instructions: &[Instruction]) -> Vec<Instruction> {
+    // Considerar que foi implementada a técnica de forwarding e quando possível reordenar as instruções e quando não for possível inserir NOPs, para evitar conflito de dados.
+
+    let mut nop_counter = 0;
+    let forwading_with_nops = forwading_with_nops(instructions);
+    let mut forwarding_and_reorder_with_nops: Vec<Instruction> =
+        Vec::with_capacity(forwading_with_nops.len());
+    let can_reorder = check_for_reorder(&forwading_with_nops);
+
+    for (index, current_inst) in forwading_with_nops.iter().enumerate() {
+        // Nao reordena inst do formato J e B
+        match current_inst.opcode() {
+            OpCodeType::B | OpCodeType::J => {
+                forwarding_and_reorder_with_nops.push(*current_inst);
+                continue;
+            }
+            _ => (),
+        }
+
+        if current_inst.is_nop() {
+            forwarding_and_reorder_with_nops.push(*current_inst);
+            nop_counter += 1;
+        } else {
+            forwarding_and_reorder_with_nops.push(*current_inst);
+
+            if let Some(bool_insts) = can_reorder[index] {
+                if bool_insts[1] && index >= 3 {
+                    let prev_index = index - 3;
+
+                    let nops = forwarding_and_reorder_with_nops[prev_index];
+                    if nops.is_nop() {
+                        forwarding_and_reorder_with_nops.remove(prev_index);
+                        forwarding_and_reorder_with_nops.insert(prev_index, *current_inst);
+                        forwarding_and_reorder_with_nops.remove(index);
+
+                        nop_counter -= 1;
+                    }
+                }
+            }
+        }
+    }
+
+    debug!("Forwarding and Reorder With NOPs: {}", nop_counter);
+
+    forwarding_and_reorder_with_nops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn techniques_run_in_the_order_of_their_names() {
+        let names: Vec<String> = techniques(&[])
+            .into_iter()
+            .map(|technique| technique.technique_name)
+            .collect();
+        assert_eq!(names, TECHNIQUE_NAMES);
+
+        assert!(technique("original", &[]).is_none());
+        assert!(technique("forwarding_with_nops", &[]).is_none());
+        assert_eq!(
+            technique("forwading_with_nops", &[]).unwrap().file_name,
+            "forwarding_with_nops.txt"
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    performance_calculator::{
+        data_hazard::{hazard_report, HazardKind, HAZARD_WINDOW},
+        liveness::{contains, is_jump, DataFlow},
+    },
+    riscv_core::instruction::{Instruction, OpCodeType},
+};
+
+// Registers a value can be moved to. x1 to x4 (ra, sp, gp and tp) keep their ABI role
+const FIRST_FREE_REGISTER: u8 = 5;
+const REGISTER_COUNT: u8 = 32;
+
+pub struct Renaming {
+    pub instructions: Vec<Instruction>,
+    // Destinations moved to another register
+    pub renamed: usize,
+    pub false_dependencies_removed: usize,
+}
+
+// WAR and WAW dependencies close enough to cost NOPs without forwarding
+pub fn false_dependencies(instructions: &[Instruction]) -> usize {
+    hazard_report(instructions)
+        .iter()
+        .flat_map(|report| report.dependencies.iter())
+        .filter(|dependency| {
+            dependency.kind != HazardKind::Raw && dependency.distance <= HAZARD_WINDOW
+        })
+        .count()
+}
+
+fn references(inst: &Instruction, register: u8) -> bool {
+    let operands = inst.operands();
+    operands.write == Some(register) || operands.reads.contains(&Some(register))
+}
+
+// Whether the destination conflicts with the reads or writes of the instructions just before it
+fn has_false_dependency(instructions: &[Instruction], index: usize, register: u8) -> bool {
+    instructions[index.saturating_sub(HAZARD_WINDOW)..index]
+        .iter()
+        .any(|inst| references(inst, register))
+}
+
+// Instructions that read the value written at index, and the last instruction where it matters.
+// None when the value may be read out of its basic block, where other paths would see the
+// change, or by an implicit operand
+fn value_uses(
+    instructions: &[Instruction],
+    data_flow: &DataFlow,
+    index: usize,
+    register: u8,
+) -> Option<(Vec<usize>, usize)> {
+    let live_out = &data_flow.liveness.live_out;
+    let end = data_flow.blocks[data_flow.block(index)].end;
+    let uses = data_flow.definition(index)?.uses.clone();
+
+    // ecall reads a0 and a7 without naming them
+    let outside = |position: &usize| {
+        *position <= index || *position > end || instructions[*position].mnemonic() == "ecall"
+    };
+    if uses.iter().any(outside) {
+        return None;
+    }
+
+    if !contains(live_out[index], register) {
+        return Some((uses, index));
+    }
+
+    (index + 1..=end)
+        .find(|position| {
+            instructions[*position].operands().write == Some(register)
+                || !contains(live_out[*position], register)
+        })
+        .map(|last| (uses, last))
+}
+
+// A register dead after index and not touched from HAZARD_WINDOW instructions before it to
+// HAZARD_WINDOW instructions after the last use, so the renaming does not add conflicts
+fn free_register(
+    instructions: &[Instruction],
+    data_flow: &DataFlow,
+    index: usize,
+    last: usize,
+    register: u8,
+) -> Option<u8> {
+    let start = index.saturating_sub(HAZARD_WINDOW);
+    let end = (last + HAZARD_WINDOW).min(instructions.len() - 1);
+
+    (FIRST_FREE_REGISTER..REGISTER_COUNT).find(|&candidate| {
+        candidate != register
+            && !contains(data_flow.liveness.live_out[index], candidate)
+            && !instructions[start..=end]
+                .iter()
+                .any(|inst| references(inst, candidate))
+    })
+}
+
+fn rename_reads(inst: &Instruction, from: u8, to: u8) -> Instruction {
+    let mut renamed = *inst;
+    if inst.rs1() == from {
+        renamed = renamed.with_rs1(to);
+    }
+
+    // Only R, S and B read rs2, the other formats keep immediate bits there
+    let reads_rs2 = matches!(inst.opcode(), OpCodeType::R | OpCodeType::S | OpCodeType::B);
+    if reads_rs2 && inst.rs2() == from {
+        renamed = renamed.with_rs2(to);
+    }
+
+    renamed
+}
+
+// Local register renaming: a destination that conflicts (WAR or WAW) with one of the two
+// instructions before it moves to a free register, and so do the reads of that value. Only
+// values read in the same straight-line code are moved, so no other path sees the change
+pub fn rename_registers(instructions: &[Instruction]) -> Renaming {
+    let mut renamed_instructions = instructions.to_vec();
+    let mut data_flow = DataFlow::from_instructions(&renamed_instructions);
+    let mut renamed = 0;
+
+    for index in 0..renamed_instructions.len() {
+        let inst = renamed_instructions[index];
+        let register = match inst.operands().write {
+            // ecall writes a0 without naming it, and the value jal and jalr write is read
+            // wherever they return to
+            Some(register)
+                if inst.rd() == register && !is_jump(&inst) && inst.mnemonic() != "ecall" =>
+            {
+                register
+            }
+            _ => continue,
+        };
+
+        if !has_false_dependency(&renamed_instructions, index, register) {
+            continue;
+        }
+
+        let (uses, last) = match value_uses(&renamed_instructions, &data_flow, index, register) {
+            Some(uses) => uses,
+            None => continue,
+        };
+        let free = match free_register(&renamed_instructions, &data_flow, index, last, register) {
+            Some(free) => free,
+            None => continue,
+        };
+
+        renamed_instructions[index] = inst.with_rd(free);
+        for position in uses {
+            renamed_instructions[position] =
+                rename_reads(&renamed_instructions[position], register, free);
+        }
+
+        data_flow = DataFlow::from_instructions(&renamed_instructions);
+        renamed += 1;
+    }
+
+    let false_dependencies_removed =
+        false_dependencies(instructions).saturating_sub(false_dependencies(&renamed_instructions));
+
+    Renaming {
+        instructions: renamed_instructions,
+        renamed,
+        false_dependencies_removed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance_calculator::pipeline_calculator;
+
+    fn add(rd: u8, rs1: u8, rs2: u8) -> Instruction {
+        let add = Instruction::from_word(0x0000_0033).unwrap();
+        add.with_rd(rd).with_rs1(rs1).with_rs2(rs2)
+    }
+
+    fn nops(technique_name: &str, instructions: &[Instruction]) -> usize {
+        pipeline_calculator::technique(technique_name, instructions)
+            .unwrap()
+            .instructions
+            .iter()
+            .filter(|inst| inst.is_nop())
+            .count()
+    }
+
+    #[test]
+    fn renaming_saves_the_nops_of_false_dependencies() {
+        let instructions = [
+            add(5, 6, 7),
+            // WAR on x6 with the instruction before, and WAW on x5 two instructions later
+            add(6, 8, 9),
+            add(5, 8, 9),
+            // Reads both renamed values
+            add(12, 6, 5),
+        ];
+
+        let renaming = rename_registers(&instructions);
+        assert_eq!(
+            renaming.instructions,
+            vec![add(5, 6, 7), add(10, 8, 9), add(11, 8, 9), add(12, 10, 11)]
+        );
+        assert_eq!(renaming.renamed, 2);
+        assert_eq!(renaming.false_dependencies_removed, 2);
+
+        // Only the NOPs of the two reads (RAW) are left
+        assert_eq!(nops("only_nops", &instructions), 5);
+        assert_eq!(nops("renaming_with_nops", &instructions), 3);
+    }
+}
@@ -0,0 +1,364 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    i18n::Locale,
+    organization::Organization,
+    performance_calculator::{monocycle_calculator, pipeline_calculator},
+    program::Program,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WeightedMeans {
+    pub arithmetic: f32,
+    pub harmonic: f32,
+    pub geometric: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonocycleSuiteCalculator {
+    pub basic_information: MonocycleSuiteBasicInformation,
+    pub programs: Vec<MonocycleProgramResult>,
+    pub aggregate: MonocycleSuiteAggregate,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonocycleSuiteBasicInformation {
+    pub organization_a_name: String,
+    pub organization_b_name: String,
+    pub suite_name: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonocycleProgramResult {
+    pub weight: f32,
+    pub performance: monocycle_calculator::PerformanceCalculator,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MonocycleSuiteAggregate {
+    pub execution_time_organization_a: WeightedMeans,
+    pub execution_time_organization_b: WeightedMeans,
+    pub average_cpi_organization_a: WeightedMeans,
+    pub average_cpi_organization_b: WeightedMeans,
+    pub best_performance: String,
+    pub comparison: monocycle_calculator::SpeedupComparison,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineSuiteCalculator {
+    pub basic_information: PipelineSuiteBasicInformation,
+    pub programs: Vec<PipelineProgramResult>,
+    pub aggregate: Vec<TechniqueAggregate>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineSuiteBasicInformation {
+    pub organization_name: String,
+    pub suite_name: String,
+    pub best_performance: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PipelineProgramResult {
+    pub weight: f32,
+    pub performance: pipeline_calculator::PerformanceCalculator,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TechniqueAggregate {
+    pub technique_name: String,
+    pub execution_time: WeightedMeans,
+    pub average_cpi: WeightedMeans,
+}
+
+impl WeightedMeans {
+    // Each sample is a (value, weight) pair
+    pub fn from_samples(samples: &[(f32, f32)]) -> WeightedMeans {
+        let total_weight: f32 = samples.iter().map(|(_, weight)| weight).sum();
+
+        if samples.is_empty() || total_weight <= 0.0 {
+            return WeightedMeans {
+                arithmetic: 0.0,
+                harmonic: 0.0,
+                geometric: 0.0,
+            };
+        }
+
+        // A = sum(w * x) / sum(w)
+        let arithmetic = samples
+            .iter()
+            .map(|(value, weight)| weight * value)
+            .sum::<f32>()
+            / total_weight;
+
+        // Harmonic and geometric means are only defined for positive values
+        let all_positive = samples.iter().all(|(value, _)| *value > 0.0);
+
+        // H = sum(w) / sum(w / x)
+        let harmonic = if all_positive {
+            total_weight
+                / samples
+                    .iter()
+                    .map(|(value, weight)| weight / value)
+                    .sum::<f32>()
+        } else {
+            0.0
+        };
+
+        // G = exp(sum(w * ln(x)) / sum(w))
+        let geometric = if all_positive {
+            (samples
+                .iter()
+                .map(|(value, weight)| weight * value.ln())
+                .sum::<f32>()
+                / total_weight)
+                .exp()
+        } else {
+            0.0
+        };
+
+        WeightedMeans {
+            arithmetic,
+            harmonic,
+            geometric,
+        }
+    }
+}
+
+impl MonocycleSuiteCalculator {
+    // Each program comes with its weight in the suite, None when the suite has no programs
+    pub fn monocycle_suite_from(
+        organization_a: &Organization,
+        organization_b: &Organization,
+        suite_name: &str,
+        suite_programs: &[(Program, f32)],
+    ) -> Option<MonocycleSuiteCalculator> {
+        if suite_programs.is_empty() {
+            return None;
+        }
+
+        let programs: Vec<MonocycleProgramResult> = suite_programs
+            .iter()
+            .map(|(program, weight)| MonocycleProgramResult {
+                weight: *weight,
+                performance: monocycle_calculator::PerformanceCalculator::monocycle_from(
+                    organization_a,
+                    organization_b,
+                    program,
+                ),
+            })
+            .collect();
+
+        let means = |value: fn(&monocycle_calculator::Result) -> f32| {
+            let samples: Vec<(f32, f32)> = programs
+                .iter()
+                .map(|program| (value(&program.performance.result), program.weight))
+                .collect();
+            WeightedMeans::from_samples(&samples)
+        };
+
+        let execution_time_organization_a = means(|result| result.execution_time_organization_a);
+        let execution_time_organization_b = means(|result| result.execution_time_organization_b);
+
+        // The geometric mean is the one that keeps the comparison independent of the reference program
+        let comparison = monocycle_calculator::SpeedupComparison::from_execution_times(
+            &organization_a.id,
+            execution_time_organization_a.geometric,
+            &organization_b.id,
+            execution_time_organization_b.geometric,
+        );
+
+        let aggregate = MonocycleSuiteAggregate {
+            execution_time_organization_a,
+            execution_time_organization_b,
+            average_cpi_organization_a: means(|result| result.average_cpi_organization_a),
+            average_cpi_organization_b: means(|result| result.average_cpi_organization_b),
+            best_performance: monocycle_calculator::best_performance(
+                &comparison,
+                Locale::default(),
+            ),
+            comparison,
+        };
+
+        Some(MonocycleSuiteCalculator {
+            basic_information: MonocycleSuiteBasicInformation {
+                organization_a_name: organization_a.id.clone(),
+                organization_b_name: organization_b.id.clone(),
+                suite_name: suite_name.to_string(),
+            },
+            programs,
+            aggregate,
+        })
+    }
+
+    // Rewrites every best_performance in another language
+    pub fn localize(&mut self, locale: Locale) {
+        for program in self.programs.iter_mut() {
+            program.performance.localize(locale);
+        }
+
+        self.aggregate.best_performance =
+            monocycle_calculator::best_performance(&self.aggregate.comparison, locale);
+    }
+}
+
+impl PipelineSuiteCalculator {
+    // Each program comes with its weight in the suite, None when the suite has no programs
+    pub fn pipeline_suite_from(
+        organization: &Organization,
+        suite_name: &str,
+        suite_programs: &[(Program, f32)],
+    ) -> Option<PipelineSuiteCalculator> {
+        let programs: Vec<PipelineProgramResult> = suite_programs
+            .iter()
+            .map(|(program, weight)| PipelineProgramResult {
+                weight: *weight,
+                performance: pipeline_calculator::PerformanceCalculator::pipeline_from(
+                    organization,
+                    program,
+                ),
+            })
+            .collect();
+
+        // Every program reports the same techniques, in the same order
+        let technique_names: Vec<String> = programs
+            .first()?
+            .performance
+            .results
+            .iter()
+            .map(|technique| technique.technique_name.clone())
+            .collect();
+
+        let aggregate: Vec<TechniqueAggregate> = technique_names
+            .into_iter()
+            .enumerate()
+            .map(|(index, technique_name)| {
+                let mut execution_times = Vec::new();
+                let mut average_cpis = Vec::new();
+
+                for program in programs.iter() {
+                    let technique = &program.performance.results[index];
+                    execution_times.push((technique.execution_time, program.weight));
+                    average_cpis.push((technique.average_cpi, program.weight));
+                }
+
+                TechniqueAggregate {
+                    technique_name,
+                    execution_time: WeightedMeans::from_samples(&execution_times),
+                    average_cpi: WeightedMeans::from_samples(&average_cpis),
+                }
+            })
+            .collect();
+
+        // Start: define best technique performance
+        let mut sorted_aggregate = aggregate.clone();
+        sorted_aggregate.sort_by(|a, b| {
+            a.execution_time
+                .geometric
+                .total_cmp(&b.execution_time.geometric)
+        });
+
+        let best_performance = sorted_aggregate
+            .iter()
+            .map(|technique| technique.technique_name.clone())
+            .collect();
+        // End: define best technique performance
+
+        Some(PipelineSuiteCalculator {
+            basic_information: PipelineSuiteBasicInformation {
+                organization_name: organization.id.clone(),
+                suite_name: suite_name.to_string(),
+                best_performance,
+            },
+            programs,
+            aggregate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::organization::tests::organization;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn weighted_means_by_hand() {
+        // 2 with weight 1 and 8 with weight 3
+        let means = WeightedMeans::from_samples(&[(2.0, 1.0), (8.0, 3.0)]);
+        // (2 + 3 * 8) / 4
+        assert_close(means.arithmetic, 6.5);
+        // 4 / (1 / 2 + 3 / 8)
+        assert_close(means.harmonic, 4.0 / 0.875);
+        // (2 * 8^3)^(1 / 4) = 2^(10 / 4)
+        assert_close(means.geometric, 2f32.powf(2.5));
+
+        // Only the arithmetic mean is defined with a zero
+        let means = WeightedMeans::from_samples(&[(0.0, 1.0), (4.0, 1.0)]);
+        assert_close(means.arithmetic, 2.0);
+        assert_eq!((means.harmonic, means.geometric), (0.0, 0.0));
+    }
+
+    #[test]
+    fn empty_suites_have_no_result() {
+        let organization = organization("suite", [1.0; 7]);
+
+        assert!(MonocycleSuiteCalculator::monocycle_suite_from(
+            &organization,
+            &organization,
+            "",
+            &[]
+        )
+        .is_none());
+        assert!(PipelineSuiteCalculator::pipeline_suite_from(&organization, "", &[]).is_none());
+    }
+
+    #[test]
+    fn a_single_program_is_its_own_mean() {
+        let organization = organization("suite", [1.0, 2.0, 3.0, 1.0, 2.0, 1.0, 1.0]);
+        // addi x5, x0, 1; lw x6, 0(x5); add x7, x6, x5
+        let program = Program::from_text(
+            "suite",
+            "00000000000100000000001010010011
+             00000000000000101010001100000011
+             00000000010100110000001110110011",
+        )
+        .unwrap();
+
+        let suite = PipelineSuiteCalculator::pipeline_suite_from(
+            &organization,
+            "suite",
+            &[(program.clone(), 2.0)],
+        )
+        .unwrap();
+        let performance =
+            pipeline_calculator::PerformanceCalculator::pipeline_from(&organization, &program);
+
+        for (aggregate, result) in suite.aggregate.iter().zip(performance.results.iter()) {
+            assert_eq!(aggregate.technique_name, result.technique_name);
+            assert_close(aggregate.execution_time.geometric, result.execution_time);
+            assert_close(aggregate.average_cpi.harmonic, result.average_cpi);
+        }
+        assert_eq!(
+            suite.basic_information.best_performance[0],
+            performance.basic_information.best_performance[0]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    organization::Organization,
+    performance_calculator::{
+        data_hazard::{read_registers, written_register},
+        energy::EnergyEstimate,
+    },
+    pipeline_diagram::instruction_label,
+    program::Program,
+    riscv_core::instruction::{Instruction, OpCodeType},
+    units,
+};
+
+pub const DEFAULT_ISSUE_WIDTH: usize = 2;
+pub const MAX_ISSUE_WIDTH: usize = 8;
+
+// IF and ID before the issue (EX) cycle, MEM and WB after the last one
+const PIPELINE_FILL: usize = 4;
+
+// How many instructions start EX together, and how many of them may use each unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IssueRules {
+    pub width: usize,
+    pub memory_ports: usize,
+    pub branch_units: usize,
+    pub forwarding: bool,
+}
+
+impl IssueRules {
+    // Rules the front ends reject, as (field, message)
+    pub fn check(&self) -> Vec<(String, String)> {
+        let mut errors = Vec::new();
+        if !(1..=MAX_ISSUE_WIDTH).contains(&self.width) {
+            errors.push((
+                "width".to_string(),
+                format!("must be between 1 and {}", MAX_ISSUE_WIDTH),
+            ));
+        }
+        if self.memory_ports == 0 {
+            errors.push(("memory_ports".to_string(), "must be at least 1".to_string()));
+        }
+        if self.branch_units == 0 {
+            errors.push(("branch_units".to_string(), "must be at least 1".to_string()));
+        }
+
+        errors
+    }
+}
+
+impl Default for IssueRules {
+    fn default() -> Self {
+        IssueRules {
+            width: DEFAULT_ISSUE_WIDTH,
+            memory_ports: 1,
+            branch_units: 1,
+            forwarding: true,
+        }
+    }
+}
+
+// Why the slots left in a cycle went unused. Issue is in order, so the first instruction that
+// cannot go holds back the ones after it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum UnusedSlotReason {
+    // An operand comes from an earlier cycle and is not ready yet (load-use, or no forwarding)
+    OperandNotReady,
+    // Reads or writes a register written in the same cycle
+    GroupDependency,
+    // Every memory port is taken
+    MemoryPort,
+    // Every branch unit is taken
+    BranchUnit,
+    // Nothing is issued after a branch or jump in the same cycle
+    ControlTransfer,
+    // No instructions left
+    ProgramEnd,
+}
+
+const REASONS: [UnusedSlotReason; 6] = [
+    UnusedSlotReason::OperandNotReady,
+    UnusedSlotReason::GroupDependency,
+    UnusedSlotReason::MemoryPort,
+    UnusedSlotReason::BranchUnit,
+    UnusedSlotReason::ControlTransfer,
+    UnusedSlotReason::ProgramEnd,
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IssuedInstruction {
+    pub index: usize,
+    pub instruction: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IssueCycle {
+    pub cycle: usize,
+    pub issued: Vec<IssuedInstruction>,
+    pub unused_slots: usize,
+    // None when every slot was used
+    pub reason: Option<UnusedSlotReason>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnusedSlots {
+    pub reason: UnusedSlotReason,
+    pub slots: usize,
+}
+
+fn uses_memory(inst: &Instruction) -> bool {
+    matches!(inst.opcode(), OpCodeType::L | OpCodeType::S)
+}
+
+// Branches, jumps and the system instructions, which may change what runs next
+fn is_control(inst: &Instruction) -> bool {
+    matches!(inst.opcode(), OpCodeType::B | OpCodeType::J)
+        || matches!(inst.mnemonic(), "jalr" | "ecall" | "ebreak")
+}
+
+// Issue cycle of every instruction and the issue log, walking the program in order like the
+// pipeline diagram: control hazards are not modeled
+pub fn schedule(instructions: &[Instruction], rules: &IssueRules) -> (Vec<usize>, Vec<IssueCycle>) {
+    // At least one of each, or some instructions could never issue
+    let width = rules.width.max(1);
+    let memory_ports = rules.memory_ports.max(1);
+    let branch_units = rules.branch_units.max(1);
+    let mut issue_cycles: Vec<usize> = Vec::with_capacity(instructions.len());
+    // Instruction that last wrote each register
+    let mut last_writer: [Option<usize>; 32] = [None; 32];
+    let mut log = Vec::new();
+
+    let mut next = 0;
+    let mut cycle = 0;
+    while next < instructions.len() {
+        let mut issued = Vec::new();
+        let mut memory = 0;
+        let mut branches = 0;
+        let mut reason = None;
+
+        while issued.len() < width {
+            let inst = match instructions.get(next) {
+                Some(inst) => inst,
+                None => {
+                    reason = Some(UnusedSlotReason::ProgramEnd);
+                    break;
+                }
+            };
+
+            let mut ready = 0;
+            let mut in_group = false;
+            for register in read_registers(inst) {
+                if let Some(producer) = last_writer[register as usize] {
+                    let is_load = instructions[producer].opcode() == OpCodeType::L;
+                    // Forwarded from the end of EX (MEM for loads), or read from the register
+                    // file after WB
+                    let latency = match rules.forwarding {
+                        true => 1 + usize::from(is_load),
+                        false => 3,
+                    };
+                    ready = ready.max(issue_cycles[producer] + latency);
+                    in_group |= issue_cycles[producer] == cycle;
+                }
+            }
+            if let Some(producer) =
+                written_register(inst).and_then(|register| last_writer[register as usize])
+            {
+                in_group |= issue_cycles[producer] == cycle;
+            }
+
+            let blocked = if in_group {
+                Some(UnusedSlotReason::GroupDependency)
+            } else if ready > cycle {
+                Some(UnusedSlotReason::OperandNotReady)
+            } else if uses_memory(inst) && memory == memory_ports {
+                Some(UnusedSlotReason::MemoryPort)
+            } else if is_control(inst) && branches == branch_units {
+                Some(UnusedSlotReason::BranchUnit)
+            } else {
+                None
+            };
+            if blocked.is_some() {
+                reason = blocked;
+                break;
+            }
+
+            memory += usize::from(uses_memory(inst));
+            branches += usize::from(is_control(inst));
+            if let Some(register) = written_register(inst) {
+                last_writer[register as usize] = Some(next);
+            }
+            issue_cycles.push(cycle);
+            issued.push(IssuedInstruction {
+                index: next,
+                instruction: instruction_label(inst),
+            });
+            next += 1;
+
+            if is_control(inst) && issued.len() < width {
+                reason = Some(UnusedSlotReason::ControlTransfer);
+                break;
+            }
+        }
+
+        log.push(IssueCycle {
+            cycle,
+            unused_slots: width - issued.len(),
+            issued,
+            reason,
+        });
+        cycle += 1;
+    }
+
+    (issue_cycles, log)
+}
+
+// Cycles until the last instruction leaves WB
+fn total_cycles(issue_cycles: &[usize]) -> usize {
+    issue_cycles
+        .last()
+        .map(|cycle| cycle + 1 + PIPELINE_FILL)
+        .unwrap_or(0)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SuperscalarCalculator {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    pub rules: IssueRules,
+    pub total_instructions: usize,
+    pub total_cycles: usize,
+    pub ipc: f32,
+    pub execution_time: f32,
+    pub formatted_execution_time: String,
+    pub mips: f32,
+    pub energy: EnergyEstimate,
+    // Same rules one instruction wide
+    pub scalar_cycles: usize,
+    pub speedup: f32,
+    pub unused_slots: Vec<UnusedSlots>,
+    pub issue_log: Vec<IssueCycle>,
+}
+
+impl SuperscalarCalculator {
+    // The organization only gives the clock: every instruction takes one cycle per stage
+    pub fn superscalar_from(
+        organization: &Organization,
+        program: &Program,
+        rules: IssueRules,
+    ) -> SuperscalarCalculator {
+        let (issue_cycles, issue_log) = schedule(&program.instructions, &rules);
+        let scalar_rules = IssueRules { width: 1, ..rules };
+        let (scalar_issue_cycles, _) = schedule(&program.instructions, &scalar_rules);
+
+        let total_instructions = program.instructions.len();
+        let total_cycles = total_cycles(&issue_cycles);
+        let scalar_cycles = self::total_cycles(&scalar_issue_cycles);
+        let execution_time = total_cycles as f32 * organization.clock_period;
+
+        let unused_slots = REASONS
+            .iter()
+            .map(|reason| UnusedSlots {
+                reason: *reason,
+                slots: issue_log
+                    .iter()
+                    .filter(|cycle| cycle.reason == Some(*reason))
+                    .map(|cycle| cycle.unused_slots)
+                    .sum(),
+            })
+            .filter(|unused| unused.slots > 0)
+            .collect();
+
+        SuperscalarCalculator {
+            organization_name: organization.id.clone(),
+            bin_file_name: program.id.clone(),
+            rules,
+            total_instructions,
+            total_cycles,
+            ipc: match total_cycles {
+                0 => 0.0,
+                _ => total_instructions as f32 / total_cycles as f32,
+            },
+            execution_time,
+            formatted_execution_time: units::format_time(execution_time),
+            mips: units::mips(total_instructions, execution_time),
+            energy: EnergyEstimate::from_run(
+                organization,
+                program.instructions.iter(),
+                execution_time,
+            ),
+            scalar_cycles,
+            speedup: match total_cycles {
+                0 => 1.0,
+                _ => scalar_cycles as f32 / total_cycles as f32,
+            },
+            unused_slots,
+            issue_log,
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    organization::{instruction_class, Organization, MAX_CPI},
+    performance_calculator::{
+        data_hazard::{read_registers, written_register, HazardKind},
+        energy::EnergyEstimate,
+        memory_dependency::memory_dependencies,
+        pipeline_calculator::{self, PerformanceCalculator},
+    },
+    pipeline_diagram::instruction_label,
+    program::Program,
+    riscv_core::instruction::{Instruction, OpCodeType},
+    units,
+};
+
+pub const DEFAULT_RESERVATION_STATIONS: usize = 3;
+pub const DEFAULT_FUNCTIONAL_UNITS: usize = 1;
+pub const DEFAULT_ROB_SIZE: usize = 16;
+pub const DEFAULT_CDB_WIDTH: usize = 1;
+// Per unit kind, and for the ROB and the CDB
+pub const MAX_TOMASULO_RESOURCES: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum UnitKind {
+    Integer,
+    // mul and div (RV32M)
+    Multiplier,
+    // Loads and stores, which compute the address
+    Memory,
+    // Branches, jumps and the system instructions
+    Branch,
+}
+
+const UNIT_KINDS: [UnitKind; 4] = [
+    UnitKind::Integer,
+    UnitKind::Multiplier,
+    UnitKind::Memory,
+    UnitKind::Branch,
+];
+
+impl UnitKind {
+    pub fn of(inst: &Instruction) -> UnitKind {
+        match (inst.opcode(), instruction_class(inst)) {
+            (_, Some("mul" | "div")) => UnitKind::Multiplier,
+            (OpCodeType::L | OpCodeType::S, _) => UnitKind::Memory,
+            (OpCodeType::B | OpCodeType::J, _) => UnitKind::Branch,
+            _ if matches!(inst.mnemonic(), "jalr" | "ecall" | "ebreak") => UnitKind::Branch,
+            _ => UnitKind::Integer,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UnitConfig {
+    pub kind: UnitKind,
+    pub reservation_stations: usize,
+    // Each one runs one instruction at a time, for as many cycles as its CPI
+    pub functional_units: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TomasuloConfig {
+    pub units: Vec<UnitConfig>,
+    pub rob_size: usize,
+    // Results broadcast per cycle
+    pub cdb_width: usize,
+}
+
+impl TomasuloConfig {
+    // The same reservation stations and functional units for every kind
+    pub fn uniform(
+        reservation_stations: usize,
+        functional_units: usize,
+        rob_size: usize,
+        cdb_width: usize,
+    ) -> TomasuloConfig {
+        TomasuloConfig {
+            units: UNIT_KINDS
+                .iter()
+                .map(|kind| UnitConfig {
+                    kind: *kind,
+                    reservation_stations,
+                    functional_units,
+                })
+                .collect(),
+            rob_size,
+            cdb_width,
+        }
+    }
+
+    // Resources outside 1..=MAX_TOMASULO_RESOURCES, as (field, message). A field is reported once
+    // even when several unit kinds are out of range
+    pub fn check(&self) -> Vec<(String, String)> {
+        let in_range = |value: usize| (1..=MAX_TOMASULO_RESOURCES).contains(&value);
+        let fields = [
+            (
+                "reservation_stations",
+                self.units
+                    .iter()
+                    .all(|unit| in_range(unit.reservation_stations)),
+            ),
+            (
+                "functional_units",
+                self.units
+                    .iter()
+                    .all(|unit| in_range(unit.functional_units)),
+            ),
+            ("rob_size", in_range(self.rob_size)),
+            ("cdb_width", in_range(self.cdb_width)),
+        ];
+
+        fields
+            .iter()
+            .filter(|(_, valid)| !valid)
+            .map(|(field, _)| {
+                (
+                    field.to_string(),
+                    format!("must be between 1 and {}", MAX_TOMASULO_RESOURCES),
+                )
+            })
+            .collect()
+    }
+
+    // At least one of everything, or some instructions could never issue. check rejects the
+    // configs this changes, it only keeps the simulation going for callers that skip it
+    fn unit(&self, kind: UnitKind) -> (usize, usize) {
+        self.units
+            .iter()
+            .find(|unit| unit.kind == kind)
+            .map_or((1, 1), |unit| {
+                (
+                    unit.reservation_stations.max(1),
+                    unit.functional_units.max(1),
+                )
+            })
+    }
+}
+
+impl Default for TomasuloConfig {
+    fn default() -> Self {
+        TomasuloConfig::uniform(
+            DEFAULT_RESERVATION_STATIONS,
+            DEFAULT_FUNCTIONAL_UNITS,
+            DEFAULT_ROB_SIZE,
+            DEFAULT_CDB_WIDTH,
+        )
+    }
+}
+
+// One row of the textbook table. Cycles start at 1
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TomasuloInstruction {
+    pub index: usize,
+    pub instruction: String,
+    pub unit: UnitKind,
+    pub issue: usize,
+    pub execute_start: usize,
+    pub execute_end: usize,
+    // None for stores and branches, which write no register
+    pub write_result: Option<usize>,
+    pub commit: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Timing {
+    issue: Option<usize>,
+    execute_start: Option<usize>,
+    execute_end: Option<usize>,
+    write_result: Option<usize>,
+    commit: Option<usize>,
+}
+
+impl Timing {
+    // Last cycle the reservation station is held: until the result is on the CDB, or until the
+    // end of execution when there is no result
+    fn release(&self, writes: bool) -> Option<usize> {
+        match writes {
+            true => self.write_result,
+            false => self.execute_end,
+        }
+    }
+}
+
+// Issues one instruction per cycle in program order, runs each as soon as its operands are on the
+// CDB and a unit is free, and commits in order from the ROB, one per cycle. Loads wait for the
+// earlier stores they may alias to commit. Control hazards are not modeled, like in the techniques
+pub fn simulate(
+    organization: &Organization,
+    instructions: &[Instruction],
+    config: &TomasuloConfig,
+) -> Vec<TomasuloInstruction> {
+    let len = instructions.len();
+    let kinds: Vec<UnitKind> = instructions.iter().map(UnitKind::of).collect();
+    let writes: Vec<bool> = instructions
+        .iter()
+        .map(|inst| written_register(inst).is_some())
+        .collect();
+    let latencies: Vec<usize> = instructions
+        .iter()
+        .map(|inst| (organization.cpi_for(inst).min(MAX_CPI).round() as usize).max(1))
+        .collect();
+
+    // Where each operand comes from: the last earlier writer of the register, if any
+    let mut last_writer: [Option<usize>; 32] = [None; 32];
+    let producers: Vec<Vec<usize>> = instructions
+        .iter()
+        .enumerate()
+        .map(|(index, inst)| {
+            let producers = read_registers(inst)
+                .into_iter()
+                .filter_map(|register| last_writer[register as usize])
+                .collect();
+            if let Some(register) = written_register(inst) {
+                last_writer[register as usize] = Some(index);
+            }
+            producers
+        })
+        .collect();
+    let stores: Vec<Vec<usize>> = memory_dependencies(instructions)
+        .into_iter()
+        .map(|dependencies| {
+            dependencies
+                .into_iter()
+                .filter(|dependency| dependency.kind == HazardKind::Raw)
+                .map(|dependency| dependency.producer)
+                .collect()
+        })
+        .collect();
+
+    let mut timings = vec![Timing::default(); len];
+    // Cycle until which each functional unit is busy
+    let mut busy_until: Vec<Vec<usize>> = UNIT_KINDS
+        .iter()
+        .map(|kind| vec![0; config.unit(*kind).1])
+        .collect();
+    let kind_index = |kind: UnitKind| UNIT_KINDS.iter().position(|other| *other == kind).unwrap();
+
+    let mut next_issue = 0;
+    let mut next_commit = 0;
+    let mut cycle = 0;
+    while next_commit < len {
+        cycle += 1;
+
+        // Commit the head of the ROB once its result is written. Its entry is free next cycle
+        let mut committed = false;
+        if next_commit < next_issue {
+            let done = timings[next_commit].release(writes[next_commit]);
+            if done.is_some_and(|done| done < cycle) {
+                timings[next_commit].commit = Some(cycle);
+                next_commit += 1;
+                committed = true;
+            }
+        }
+
+        // Results on the CDB, oldest first
+        let mut broadcasts = 0;
+        for index in next_commit..next_issue {
+            if broadcasts == config.cdb_width.max(1) {
+                break;
+            }
+            let timing = timings[index];
+            let finished = timing.execute_end.is_some_and(|end| end < cycle);
+            if writes[index] && finished && timing.write_result.is_none() {
+                timings[index].write_result = Some(cycle);
+                broadcasts += 1;
+            }
+        }
+
+        // Start the oldest instructions whose operands are ready on a free unit
+        let mut started = false;
+        for index in next_commit..next_issue {
+            let timing = timings[index];
+            if timing.execute_start.is_some() || timing.issue.is_some_and(|issue| issue >= cycle) {
+                continue;
+            }
+
+            let operands_ready = producers[index].iter().all(|producer| {
+                timings[*producer]
+                    .write_result
+                    .is_some_and(|write| write < cycle)
+            });
+            let stores_committed = stores[index]
+                .iter()
+                .all(|store| timings[*store].commit.is_some_and(|commit| commit < cycle));
+            if !operands_ready || !stores_committed {
+                continue;
+            }
+
+            let units = &mut busy_until[kind_index(kinds[index])];
+            if let Some(unit) = units.iter_mut().find(|busy| **busy < cycle) {
+                *unit = cycle + latencies[index] - 1;
+                timings[index].execute_start = Some(cycle);
+                timings[index].execute_end = Some(*unit);
+                started = true;
+            }
+        }
+
+        // Issue the next instruction when the ROB and its reservation stations have room
+        let mut issued = false;
+        if next_issue < len {
+            let rob_used = next_issue - next_commit + usize::from(committed);
+            let kind = kinds[next_issue];
+            // Committed instructions released their stations before
+            let stations_used = (next_commit..next_issue)
+                .filter(|index| kinds[*index] == kind)
+                .filter(|index| {
+                    timings[*index]
+                        .release(writes[*index])
+                        .is_none_or(|release| release >= cycle)
+                })
+                .count();
+
+            if rob_used < config.rob_size.max(1) && stations_used < config.unit(kind).0 {
+                timings[next_issue].issue = Some(cycle);
+                next_issue += 1;
+                issued = true;
+            }
+        }
+
+        // Nothing moved, so nothing will until a unit finishes: jump to the cycle it does
+        if !(committed || broadcasts > 0 || started || issued) {
+            if let Some(end) = busy_until
+                .iter()
+                .flatten()
+                .filter(|end| **end >= cycle)
+                .min()
+            {
+                cycle = *end;
+            }
+        }
+    }
+
+    timings
+        .iter()
+        .enumerate()
+        .map(|(index, timing)| TomasuloInstruction {
+            index,
+            instruction: instruction_label(&instructions[index]),
+            unit: kinds[index],
+            issue: timing.issue.unwrap_or_default(),
+            execute_start: timing.execute_start.unwrap_or_default(),
+            execute_end: timing.execute_end.unwrap_or_default(),
+            write_result: timing.write_result,
+            commit: timing.commit.unwrap_or_default(),
+        })
+        .collect()
+}
+
+// How a technique of pipeline_calculator runs the same program in order
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct InOrderComparison {
+    pub technique_name: String,
+    // NOPs the technique inserted, which the reservation stations make unnecessary
+    pub nops: usize,
+    pub total_cycles: f32,
+    // Tomasulo speedup over the technique
+    pub speedup: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TomasuloCalculator {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    pub config: TomasuloConfig,
+    pub total_instructions: usize,
+    // Cycle of the last commit
+    pub total_cycles: usize,
+    pub ipc: f32,
+    pub execution_time: f32,
+    pub formatted_execution_time: String,
+    pub mips: f32,
+    pub energy: EnergyEstimate,
+    pub instructions: Vec<TomasuloInstruction>,
+    pub in_order: Vec<InOrderComparison>,
+}
+
+impl TomasuloCalculator {
+    // Each instruction executes for as many cycles as its CPI in the organization
+    pub fn tomasulo_from(
+        organization: &Organization,
+        program: &Program,
+        config: TomasuloConfig,
+    ) -> TomasuloCalculator {
+        let instructions = simulate(organization, &program.instructions, &config);
+        let total_instructions = instructions.len();
+        let total_cycles = instructions.last().map_or(0, |last| last.commit);
+        let execution_time = total_cycles as f32 * organization.clock_period;
+
+        let techniques = pipeline_calculator::techniques(&program.instructions);
+        let performance =
+            PerformanceCalculator::from_techniques(organization, program, &techniques);
+        // The results start with the original program, which has no NOPs
+        let nops = std::iter::once(0).chain(techniques.iter().map(|technique| {
+            technique
+                .instructions
+                .iter()
+                .filter(|inst| inst.is_nop())
+                .count()
+        }));
+        let in_order = performance
+            .results
+            .iter()
+            .zip(nops)
+            .map(|(result, nops)| InOrderComparison {
+                technique_name: result.technique_name.clone(),
+                nops,
+                total_cycles: result.total_cicles,
+                speedup: match total_cycles {
+                    0 => 1.0,
+                    _ => result.total_cicles / total_cycles as f32,
+                },
+            })
+            .collect();
+
+        TomasuloCalculator {
+            organization_name: organization.id.clone(),
+            bin_file_name: program.id.clone(),
+            config,
+            total_instructions,
+            total_cycles,
+            ipc: match total_cycles {
+                0 => 0.0,
+                _ => total_instructions as f32 / total_cycles as f32,
+            },
+            execution_time,
+            formatted_execution_time: units::format_time(execution_time),
+            mips: units::mips(total_instructions, execution_time),
+            energy: EnergyEstimate::from_run(
+                organization,
+                program.instructions.iter(),
+                execution_time,
+            ),
+            instructions,
+            in_order,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::organization::tests::organization;
+
+    // (issue, execute_start, execute_end, write_result, commit) of every instruction
+    fn table(
+        organization: &Organization,
+        text: &str,
+    ) -> Vec<(usize, usize, usize, Option<usize>, usize)> {
+        let program = Program::from_text("tomasulo", text).unwrap();
+
+        simulate(
+            organization,
+            &program.instructions,
+            &TomasuloConfig::default(),
+        )
+        .iter()
+        .map(|row| {
+            (
+                row.issue,
+                row.execute_start,
+                row.execute_end,
+                row.write_result,
+                row.commit,
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn textbook_table() {
+        // Loads take 2 cycles, everything else 1
+        let organization = organization("tomasulo", [1.0, 1.0, 2.0, 1.0, 1.0, 1.0, 1.0]);
+
+        assert_eq!(
+            table(
+                &organization,
+                // lw x5, 0(x10); add x6, x5, x7; addi x8, x0, 1; sw x6, 0(x10); lw x9, 0(x10)
+                "00000000000001010010001010000011
+                 00000000011100101000001100110011
+                 00000000000100000000010000010011
+                 00000000011001010010000000100011
+                 00000000000001010010010010000011",
+            ),
+            vec![
+                (1, 2, 3, Some(4), 5),
+                // Waits for x5 on the CDB
+                (2, 5, 5, Some(6), 7),
+                // Runs before add, commits after it
+                (3, 4, 4, Some(5), 8),
+                (4, 7, 7, None, 9),
+                // Reads what the store writes, so it waits for its commit
+                (5, 10, 11, Some(12), 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn configs_out_of_range_are_reported_once() {
+        assert!(TomasuloConfig::default().check().is_empty());
+
+        let fields: Vec<String> = TomasuloConfig::uniform(0, 1, MAX_TOMASULO_RESOURCES + 1, 1)
+            .check()
+            .into_iter()
+            .map(|(field, _)| field)
+            .collect();
+        assert_eq!(fields, vec!["reservation_stations", "rob_size"]);
+    }
+
+    #[test]
+    fn latencies_are_capped_at_the_max_cpi() {
+        // Not a valid organization, the simulation still takes MAX_CPI cycles per load
+        let organization = organization("slow", [1.0, 1.0, 1e30, 1.0, 1.0, 1.0, 1.0]);
+
+        assert_eq!(
+            table(
+                &organization,
+                // lw x5, 0(x10); add x6, x5, x7
+                "00000000000001010010001010000011
+                 00000000011100101000001100110011",
+            ),
+            vec![
+                (1, 2, 1001, Some(1002), 1003),
+                (2, 1003, 1003, Some(1004), 1005)
+            ]
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use super::{svg, PipelineDiagram};
+
+// Standalone page with the SVG diagram and a summary of the run
+pub fn render(diagram: &PipelineDiagram) -> String {
+    let forwarded: String = diagram
+        .arrows
+        .iter()
+        .map(|arrow| {
+            format!(
+                "<li>{}: instruction {} &rarr; instruction {} (cycle {})</li>\n",
+                arrow.register,
+                arrow.producer + 1,
+                arrow.consumer + 1,
+                arrow.to_cycle + 1
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Pipeline diagram - {technique}</title>
+<style>
+body {{ font-family: sans-serif; margin: 24px; }}
+.diagram {{ overflow-x: auto; }}
+</style>
+</head>
+<body>
+<h1>Pipeline diagram - {technique}</h1>
+<p>Forwarding: {forwarding} &middot; Cycles: {cycles} &middot; Stalls: {stalls} &middot; Bubbles: {bubbles}</p>
+<div class="diagram">
+{svg}</div>
+<ul>
+{forwarded}</ul>
+</body>
+</html>
+"#,
+        technique = diagram.technique_name,
+        forwarding = if diagram.forwarding { "yes" } else { "no" },
+        cycles = diagram.total_cycles,
+        stalls = diagram.stall_cycles(),
+        bubbles = diagram.bubbles(),
+        svg = svg::render(diagram),
+        forwarded = forwarded
+    )
+}
@@ -0,0 +1,243 @@
+pub mod html;
+pub mod svg;
+pub mod text;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    performance_calculator::{
+        data_hazard::{read_registers, register_name, written_register},
+        pipeline_calculator,
+    },
+    riscv_core::instruction::{Instruction, OpCodeType},
+};
+
+// Name of the technique that runs the program as it is
+pub const ORIGINAL_TECHNIQUE: &str = "original";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DiagramFormat {
+    #[default]
+    Text,
+    Svg,
+    Html,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    If,
+    Id,
+    Ex,
+    Mem,
+    Wb,
+    // The instruction waits in IF or ID for an operand
+    Stall,
+}
+
+impl Stage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Stage::If => "IF",
+            Stage::Id => "ID",
+            Stage::Ex => "EX",
+            Stage::Mem => "MEM",
+            Stage::Wb => "WB",
+            Stage::Stall => "--",
+        }
+    }
+}
+
+pub struct DiagramRow {
+    pub label: String,
+    // NOPs inserted by the technique, drawn as bubbles
+    pub is_bubble: bool,
+    // The stage the instruction is in, starting at first_cycle
+    pub first_cycle: usize,
+    pub stages: Vec<Stage>,
+}
+
+// A result forwarded from the end of the producer's stage to the consumer's EX
+pub struct ForwardingArrow {
+    pub producer: usize,
+    pub consumer: usize,
+    pub register: String,
+    pub from_cycle: usize,
+    pub to_cycle: usize,
+}
+
+pub struct PipelineDiagram {
+    pub technique_name: String,
+    pub forwarding: bool,
+    pub rows: Vec<DiagramRow>,
+    pub arrows: Vec<ForwardingArrow>,
+    pub total_cycles: usize,
+}
+
+// e.g. "add x5, x6, x7", immediates are left out
+pub fn instruction_label(inst: &Instruction) -> String {
+    if inst.is_nop() {
+        return "nop".to_string();
+    }
+
+    let mnemonic = inst.mnemonic();
+    let rd = register_name(inst.rd());
+    let rs1 = register_name(inst.rs1());
+    let rs2 = register_name(inst.rs2());
+
+    match inst.opcode() {
+        OpCodeType::R => format!("{} {}, {}, {}", mnemonic, rd, rs1, rs2),
+        OpCodeType::I => format!("{} {}, {}", mnemonic, rd, rs1),
+        OpCodeType::L => format!("{} {}, ({})", mnemonic, rd, rs1),
+        OpCodeType::S => format!("{} {}, ({})", mnemonic, rs2, rs1),
+        OpCodeType::B => format!("{} {}, {}", mnemonic, rs1, rs2),
+        OpCodeType::U | OpCodeType::J => format!("{} {}", mnemonic, rd),
+    }
+}
+
+impl PipelineDiagram {
+    // The program produced by the technique ("original" or one of pipeline_calculator::TECHNIQUE_NAMES)
+    pub fn for_technique(
+        technique_name: &str,
+        instructions: &[Instruction],
+    ) -> Option<PipelineDiagram> {
+        if technique_name == ORIGINAL_TECHNIQUE {
+            return Some(PipelineDiagram::from_instructions(
+                technique_name,
+                instructions,
+                false,
+            ));
+        }
+
+        pipeline_calculator::technique(technique_name, instructions).map(|technique| {
+            PipelineDiagram::from_instructions(
+                technique_name,
+                &technique.instructions,
+                technique.forwarding,
+            )
+        })
+    }
+
+    // Five stage pipeline (IF, ID, EX, MEM, WB) that stalls in ID until its operands are ready.
+    // Registers are written in the first half of WB and read in the second half of ID.
+    // Control hazards are not modeled, like in the techniques
+    pub fn from_instructions(
+        technique_name: &str,
+        instructions: &[Instruction],
+        forwarding: bool,
+    ) -> PipelineDiagram {
+        let mut rows: Vec<DiagramRow> = Vec::new();
+        let mut arrows = Vec::new();
+        // Cycle of the ID and EX stages of each instruction
+        let mut id_cycles: Vec<usize> = Vec::new();
+        let mut ex_cycles: Vec<usize> = Vec::new();
+        let mut last_writer: HashMap<u8, usize> = HashMap::new();
+
+        for (index, inst) in instructions.iter().enumerate() {
+            // Fetched when the previous instruction leaves IF, then waits there while it is stalled in ID
+            let (if_cycle, id_cycle) = match index {
+                0 => (0, 1),
+                _ => (
+                    id_cycles[index - 1],
+                    (id_cycles[index - 1] + 1).max(ex_cycles[index - 1]),
+                ),
+            };
+
+            let mut ex_cycle = id_cycle + 1;
+            if index > 0 {
+                ex_cycle = ex_cycle.max(ex_cycles[index - 1] + 1);
+            }
+
+            let mut forwarded = Vec::new();
+            for register in read_registers(inst) {
+                let producer = match last_writer.get(&register) {
+                    Some(producer) => *producer,
+                    None => continue,
+                };
+
+                let producer_is_load = matches!(instructions[producer].opcode(), OpCodeType::L);
+                // Without forwarding the value is read in ID, in the producer's WB cycle at the earliest
+                let register_file_ready = ex_cycles[producer] + 2 + 1;
+                // With forwarding it goes from the end of EX (or MEM, for loads) to the start of EX
+                let forward_from = ex_cycles[producer] + usize::from(producer_is_load);
+
+                if forwarding && forward_from + 1 < register_file_ready {
+                    ex_cycle = ex_cycle.max(forward_from + 1);
+                    forwarded.push((producer, register, forward_from));
+                } else {
+                    ex_cycle = ex_cycle.max(register_file_ready);
+                }
+            }
+
+            for (producer, register, from_cycle) in forwarded {
+                // Only draw the arrow when the register file would not have been enough
+                if ex_cycle < ex_cycles[producer] + 3 {
+                    arrows.push(ForwardingArrow {
+                        producer,
+                        consumer: index,
+                        register: register_name(register),
+                        from_cycle,
+                        to_cycle: ex_cycle,
+                    });
+                }
+            }
+
+            let mut stages = vec![Stage::If];
+            stages.extend(vec![Stage::Stall; id_cycle - if_cycle - 1]);
+            stages.push(Stage::Id);
+            stages.extend(vec![Stage::Stall; ex_cycle - id_cycle - 1]);
+            stages.extend([Stage::Ex, Stage::Mem, Stage::Wb]);
+
+            if let Some(register) = written_register(inst) {
+                last_writer.insert(register, index);
+            }
+
+            id_cycles.push(id_cycle);
+            ex_cycles.push(ex_cycle);
+            rows.push(DiagramRow {
+                label: instruction_label(inst),
+                is_bubble: inst.is_nop(),
+                first_cycle: if_cycle,
+                stages,
+            });
+        }
+
+        let total_cycles = ex_cycles.last().map(|ex_cycle| ex_cycle + 3).unwrap_or(0);
+
+        PipelineDiagram {
+            technique_name: technique_name.to_string(),
+            forwarding,
+            rows,
+            arrows,
+            total_cycles,
+        }
+    }
+
+    pub fn stall_cycles(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| !row.is_bubble)
+            .map(|row| {
+                row.stages
+                    .iter()
+                    .filter(|stage| **stage == Stage::Stall)
+                    .count()
+            })
+            .sum()
+    }
+
+    pub fn bubbles(&self) -> usize {
+        self.rows.iter().filter(|row| row.is_bubble).count()
+    }
+
+    pub fn render(&self, format: DiagramFormat) -> String {
+        match format {
+            DiagramFormat::Text => text::render(self),
+            DiagramFormat::Svg => svg::render(self),
+            DiagramFormat::Html => html::render(self),
+        }
+    }
+}
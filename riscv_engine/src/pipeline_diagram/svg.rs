@@ -0,0 +1,109 @@
+use super::{PipelineDiagram, Stage};
+
+const CELL_WIDTH: usize = 44;
+const CELL_HEIGHT: usize = 24;
+const LABEL_WIDTH: usize = 180;
+const HEADER_HEIGHT: usize = 28;
+
+fn stage_color(stage: Stage) -> &'static str {
+    match stage {
+        Stage::If => "#a6cee3",
+        Stage::Id => "#b2df8a",
+        Stage::Ex => "#fdbf6f",
+        Stage::Mem => "#cab2d6",
+        Stage::Wb => "#fb9a99",
+        Stage::Stall => "url(#stall)",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn cell_x(cycle: usize) -> usize {
+    LABEL_WIDTH + cycle * CELL_WIDTH
+}
+
+fn row_y(index: usize) -> usize {
+    HEADER_HEIGHT + index * CELL_HEIGHT
+}
+
+// Colored cell per stage, hatched stalls, gray bubbles and an arrow per forwarded result
+pub fn render(diagram: &PipelineDiagram) -> String {
+    let width = cell_x(diagram.total_cycles) + 8;
+    let height = row_y(diagram.rows.len()) + 8;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n",
+        width, height
+    );
+    svg.push_str(
+        "<defs>\n\
+         <pattern id=\"stall\" width=\"6\" height=\"6\" patternUnits=\"userSpaceOnUse\" patternTransform=\"rotate(45)\">\
+         <rect width=\"6\" height=\"6\" fill=\"#ffffff\"/><line x1=\"0\" y1=\"0\" x2=\"0\" y2=\"6\" stroke=\"#999999\" stroke-width=\"2\"/></pattern>\n\
+         <marker id=\"arrow\" markerWidth=\"8\" markerHeight=\"8\" refX=\"7\" refY=\"4\" orient=\"auto\">\
+         <path d=\"M0,0 L8,4 L0,8 z\" fill=\"#d62728\"/></marker>\n\
+         </defs>\n",
+    );
+
+    for cycle in 0..diagram.total_cycles {
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+            cell_x(cycle) + CELL_WIDTH / 2,
+            HEADER_HEIGHT - 10,
+            cycle + 1
+        ));
+    }
+
+    for (index, row) in diagram.rows.iter().enumerate() {
+        let y = row_y(index);
+        svg.push_str(&format!(
+            "<text x=\"4\" y=\"{}\" fill=\"{}\">{}</text>\n",
+            y + CELL_HEIGHT / 2 + 4,
+            if row.is_bubble { "#999999" } else { "#000000" },
+            escape(&row.label)
+        ));
+
+        for (offset, stage) in row.stages.iter().enumerate() {
+            let x = cell_x(row.first_cycle + offset);
+            let fill = if row.is_bubble {
+                "#eeeeee"
+            } else {
+                stage_color(*stage)
+            };
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"#ffffff\"/>\n",
+                x,
+                y,
+                CELL_WIDTH,
+                CELL_HEIGHT,
+                fill
+            ));
+            if *stage != Stage::Stall {
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" fill=\"{}\">{}</text>\n",
+                    x + CELL_WIDTH / 2,
+                    y + CELL_HEIGHT / 2 + 4,
+                    if row.is_bubble { "#999999" } else { "#000000" },
+                    stage.as_str()
+                ));
+            }
+        }
+    }
+
+    for arrow in &diagram.arrows {
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#d62728\" stroke-width=\"2\" marker-end=\"url(#arrow)\"><title>{}</title></line>\n",
+            cell_x(arrow.from_cycle + 1) - 4,
+            row_y(arrow.producer) + CELL_HEIGHT / 2,
+            cell_x(arrow.to_cycle) + 4,
+            row_y(arrow.consumer) + CELL_HEIGHT / 2,
+            arrow.register
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
@@ -0,0 +1,59 @@
+use super::PipelineDiagram;
+
+const CELL_WIDTH: usize = 4;
+
+// Cycle grid, one line per instruction, followed by the forwarded results
+pub fn render(diagram: &PipelineDiagram) -> String {
+    let label_width = diagram
+        .rows
+        .iter()
+        .map(|row| row.label.len())
+        .max()
+        .unwrap_or(0)
+        .max("cycle".len());
+
+    let mut output = format!(
+        "technique: {} (forwarding: {})\n",
+        diagram.technique_name,
+        if diagram.forwarding { "yes" } else { "no" }
+    );
+
+    output.push_str(&format!("{:<width$} |", "cycle", width = label_width));
+    for cycle in 1..=diagram.total_cycles {
+        output.push_str(&format!("{:>width$}", cycle, width = CELL_WIDTH));
+    }
+    output.push('\n');
+    output.push_str(&"-".repeat(label_width + 2 + CELL_WIDTH * diagram.total_cycles));
+    output.push('\n');
+
+    for row in &diagram.rows {
+        output.push_str(&format!("{:<width$} |", row.label, width = label_width));
+        output.push_str(&" ".repeat(CELL_WIDTH * row.first_cycle));
+        for stage in &row.stages {
+            // Bubbles go through the pipeline without doing anything
+            let cell = if row.is_bubble { "." } else { stage.as_str() };
+            output.push_str(&format!("{:>width$}", cell, width = CELL_WIDTH));
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&format!(
+        "\ncycles: {}, stalls: {}, bubbles: {}\n",
+        diagram.total_cycles,
+        diagram.stall_cycles(),
+        diagram.bubbles()
+    ));
+
+    for arrow in &diagram.arrows {
+        output.push_str(&format!(
+            "forwarding: {} from instruction {} (end of cycle {}) to instruction {} (cycle {})\n",
+            arrow.register,
+            arrow.producer + 1,
+            arrow.from_cycle + 1,
+            arrow.consumer + 1,
+            arrow.to_cycle + 1
+        ));
+    }
+
+    output
+}
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use crate::riscv_core::instruction::{Instruction, INSTRUCTION_SIZE};
+
+// How a bin dump is written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpFormat {
+    // One instruction per line, 32 characters of 0 and 1
+    BinaryText,
+    // One instruction per line, 8 hexadecimal digits with an optional 0x prefix
+    HexadecimalText,
+}
+
+// A bin dump normalized to binary text, one instruction per line
+#[derive(Clone, Debug)]
+pub struct Dump {
+    pub format: DumpFormat,
+    pub text: String,
+    pub instruction_count: usize,
+}
+
+// Converts a hexadecimal text line ("0x00500293" or "00500293") to binary text
+fn hex_line_to_binary(line: &str) -> Option<String> {
+    let digits = line.trim_start_matches("0x").trim_start_matches("0X");
+    if digits.len() != INSTRUCTION_SIZE / 4 {
+        return None;
+    }
+
+    u32::from_str_radix(digits, 16)
+        .ok()
+        .map(|word| format!("{:032b}", word))
+}
+
+// Decodes every line, or lists the lines that are not an instruction as ("file[line]", message)
+fn decode_lines<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Instruction>, Vec<(String, String)>> {
+    let mut instructions = Vec::with_capacity(lines.len());
+    let mut errors = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        match Instruction::new(line.as_ref()) {
+            Ok(inst) => instructions.push(inst),
+            Err(message) => errors.push((format!("file[{}]", index + 1), message)),
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(instructions),
+        false => Err(errors),
+    }
+}
+
+impl Dump {
+    // Detects the format and checks every instruction. Errors come as (field, message), the
+    // field being "file" or "file[line]"
+    pub fn parse(text: &str) -> Result<Dump, Vec<(String, String)>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(vec![("file".to_string(), "must not be empty".to_string())]);
+        }
+
+        let lines: Vec<&str> = text.lines().map(|line| line.trim()).collect();
+
+        let is_hexadecimal = lines.iter().all(|line| hex_line_to_binary(line).is_some());
+        let (format, binary_lines) = if is_hexadecimal {
+            let binary_lines: Vec<String> = lines
+                .iter()
+                .filter_map(|line| hex_line_to_binary(line))
+                .collect();
+            (DumpFormat::HexadecimalText, binary_lines)
+        } else {
+            let binary_lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+            (DumpFormat::BinaryText, binary_lines)
+        };
+
+        decode_lines(&binary_lines)?;
+
+        Ok(Dump {
+            format,
+            instruction_count: binary_lines.len(),
+            text: binary_lines.join("\n"),
+        })
+    }
+}
+
+// A decoded bin dump. The instructions are shared, so a cached program is not copied per request
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub id: String,
+    pub instructions: Arc<[Instruction]>,
+}
+
+impl Program {
+    // Binary text, one instruction per line. Lines that are not an instruction come back as in
+    // Dump::parse, e.g. stored files that were never checked
+    pub fn from_text(id: &str, text: &str) -> Result<Program, Vec<(String, String)>> {
+        let lines: Vec<&str> = text.trim().lines().map(|line| line.trim()).collect();
+
+        Ok(Program {
+            id: id.to_string(),
+            instructions: decode_lines(&lines)?.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_lines_are_errors_instead_of_panics() {
+        // addi x5, x0, 5
+        let program = Program::from_text("ok", " 00000000010100000000001010010011 \n").unwrap();
+        assert_eq!(program.instructions.len(), 1);
+        assert!(Program::from_text("empty", "")
+            .unwrap()
+            .instructions
+            .is_empty());
+
+        let errors = Program::from_text(
+            "legacy",
+            "00000000010100000000001010010011\n0x00500293\n11111111111111111111111111111111",
+        )
+        .unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|(field, _)| field.as_str()).collect();
+        assert_eq!(fields, vec!["file[2]", "file[3]"]);
+    }
+}
@@ -0,0 +1,69 @@
+use super::{AnalysisReport, Block};
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Standalone page, it can be printed to PDF from the browser
+pub fn render(report: &AnalysisReport) -> String {
+    let mut body = format!("<h1>{}</h1>\n", escape(&report.title));
+
+    for block in report.blocks.iter() {
+        match block {
+            Block::Heading(level, text) => {
+                let level = (*level).min(6);
+                body.push_str(&format!("<h{}>{}</h{}>\n", level, escape(text), level));
+            }
+            Block::Paragraph(text) => body.push_str(&format!("<p>{}</p>\n", escape(text))),
+            Block::List(items) => {
+                body.push_str("<ul>\n");
+                for item in items {
+                    body.push_str(&format!("<li>{}</li>\n", escape(item)));
+                }
+                body.push_str("</ul>\n");
+            }
+            Block::Table { headers, rows } => {
+                body.push_str("<table>\n<tr>");
+                for header in headers {
+                    body.push_str(&format!("<th>{}</th>", escape(header)));
+                }
+                body.push_str("</tr>\n");
+                for row in rows {
+                    body.push_str("<tr>");
+                    for cell in row {
+                        body.push_str(&format!("<td>{}</td>", escape(cell)));
+                    }
+                    body.push_str("</tr>\n");
+                }
+                body.push_str("</table>\n");
+            }
+            Block::Listing(lines) => {
+                body.push_str(&format!("<pre>{}</pre>\n", escape(&lines.join("\n"))));
+            }
+        }
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 24px; max-width: 1100px; }}
+table {{ border-collapse: collapse; margin: 8px 0; }}
+th, td {{ border: 1px solid #cccccc; padding: 4px 8px; text-align: left; }}
+th {{ background: #f0f0f0; }}
+pre {{ background: #f7f7f7; padding: 8px; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        title = escape(&report.title),
+        body = body
+    )
+}
@@ -0,0 +1,45 @@
+use super::{AnalysisReport, Block};
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+pub fn render(report: &AnalysisReport) -> String {
+    let mut output = format!("# {}\n", report.title);
+
+    for block in report.blocks.iter() {
+        output.push('\n');
+        match block {
+            Block::Heading(level, text) => {
+                output.push_str(&format!("{} {}\n", "#".repeat(*level), text));
+            }
+            Block::Paragraph(text) => {
+                output.push_str(text);
+                output.push('\n');
+            }
+            Block::List(items) => {
+                for item in items {
+                    output.push_str(&format!("- {}\n", item));
+                }
+            }
+            Block::Table { headers, rows } => {
+                output.push_str(&format!("| {} |\n", headers.join(" | ")));
+                output.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|cell| escape_cell(cell)).collect();
+                    output.push_str(&format!("| {} |\n", cells.join(" | ")));
+                }
+            }
+            Block::Listing(lines) => {
+                output.push_str("```\n");
+                for line in lines {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+                output.push_str("```\n");
+            }
+        }
+    }
+
+    output
+}
@@ -0,0 +1,211 @@
+pub mod html;
+pub mod markdown;
+pub mod monocycle;
+pub mod pipeline;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{organization::Organization, program::Program};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+pub enum Block {
+    // Level 1 is the report title
+    Heading(usize, String),
+    Paragraph(String),
+    List(Vec<String>),
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+    // Preformatted lines, e.g. an instruction listing
+    Listing(Vec<String>),
+}
+
+// Written analysis of a calculator run, rendered as Markdown or HTML
+pub struct AnalysisReport {
+    pub title: String,
+    pub blocks: Vec<Block>,
+}
+
+impl AnalysisReport {
+    pub fn new(title: &str) -> AnalysisReport {
+        AnalysisReport {
+            title: title.to_string(),
+            blocks: Vec::new(),
+        }
+    }
+
+    // Nests each report under its title, e.g. one per bin file
+    pub fn combine(title: &str, reports: Vec<AnalysisReport>) -> AnalysisReport {
+        let mut combined = AnalysisReport::new(title);
+
+        for report in reports {
+            combined.heading(2, &report.title);
+            combined
+                .blocks
+                .extend(report.blocks.into_iter().map(|block| match block {
+                    Block::Heading(level, text) => Block::Heading(level + 1, text),
+                    block => block,
+                }));
+        }
+
+        combined
+    }
+
+    pub fn heading(&mut self, level: usize, text: &str) {
+        self.blocks.push(Block::Heading(level, text.to_string()));
+    }
+
+    pub fn paragraph(&mut self, text: String) {
+        self.blocks.push(Block::Paragraph(text));
+    }
+
+    pub fn list(&mut self, items: Vec<String>) {
+        self.blocks.push(Block::List(items));
+    }
+
+    pub fn table(&mut self, headers: &[&str], rows: Vec<Vec<String>>) {
+        self.blocks.push(Block::Table {
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows,
+        });
+    }
+
+    pub fn listing(&mut self, lines: Vec<String>) {
+        self.blocks.push(Block::Listing(lines));
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => markdown::render(self),
+            ReportFormat::Html => html::render(self),
+        }
+    }
+}
+
+// Three decimal places, like the formatted times
+pub fn number(value: f32) -> String {
+    (((value as f64) * 1000.0).round() / 1000.0).to_string()
+}
+
+pub fn percentage(part: usize, total: usize) -> String {
+    if total == 0 {
+        return "0%".to_string();
+    }
+
+    format!("{}%", number(part as f32 * 100.0 / total as f32))
+}
+
+// Clock and CPI of each instruction format, plus the overrides of the organization
+pub fn organization_rows(organization: &Organization) -> Vec<Vec<String>> {
+    let mut rows = vec![
+        vec!["R".to_string(), number(organization.cpi_instruction_r)],
+        vec!["I".to_string(), number(organization.cpi_instruction_i)],
+        vec!["L".to_string(), number(organization.cpi_instruction_l)],
+        vec!["S".to_string(), number(organization.cpi_instruction_s)],
+        vec!["B".to_string(), number(organization.cpi_instruction_b)],
+        vec!["U".to_string(), number(organization.cpi_instruction_u)],
+        vec!["J".to_string(), number(organization.cpi_instruction_j)],
+    ];
+
+    for (key, cpi) in organization.cpi_map.iter() {
+        rows.push(vec![key.clone(), number(*cpi)]);
+    }
+
+    rows
+}
+
+// How many times each mnemonic appears in the program, most used first
+pub fn instruction_mix(program: &Program) -> Vec<(&'static str, usize)> {
+    let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+    for inst in program.instructions.iter() {
+        *counts.entry(inst.mnemonic()).or_default() += 1;
+    }
+
+    let mut mix: Vec<(&'static str, usize)> = counts.into_iter().collect();
+    mix.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    mix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        organization::tests::organization,
+        performance_calculator::monocycle_calculator::PerformanceCalculator,
+        riscv_core::instruction::Instruction,
+    };
+
+    fn sample() -> AnalysisReport {
+        let mut report = AnalysisReport::new("Run <1>");
+        report.heading(2, "Results");
+        report.paragraph("A & B".to_string());
+        report.list(vec!["one".to_string()]);
+        report.table(
+            &["Key", "Value"],
+            vec![vec!["a|b".to_string(), "1".to_string()]],
+        );
+        report.listing(vec!["add x6, x5, x5".to_string()]);
+        report
+    }
+
+    #[test]
+    fn markdown_and_html_render_every_block() {
+        assert_eq!(
+            sample().render(ReportFormat::Markdown),
+            "# Run <1>\n\n## Results\n\nA & B\n\n- one\n\n| Key | Value |\n| --- | --- |\n\
+             | a\\|b | 1 |\n\n```\nadd x6, x5, x5\n```\n"
+        );
+
+        let html = sample().render(ReportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Run &lt;1&gt;</title>"));
+        assert!(html.contains("<h2>Results</h2>\n<p>A &amp; B</p>\n<ul>\n<li>one</li>\n</ul>\n"));
+        assert!(
+            html.contains("<tr><th>Key</th><th>Value</th></tr>\n<tr><td>a|b</td><td>1</td></tr>")
+        );
+        assert!(html.contains("<pre>add x6, x5, x5</pre>"));
+    }
+
+    #[test]
+    fn combined_reports_nest_their_headings() {
+        let combined = AnalysisReport::combine("Suite", vec![sample(), sample()]);
+        let markdown = combined.render(ReportFormat::Markdown);
+
+        assert!(markdown.starts_with("# Suite\n\n## Run <1>\n\n### Results\n"));
+        assert_eq!(markdown.matches("## Run <1>").count(), 2);
+    }
+
+    #[test]
+    fn monocycle_report_explains_the_speedup() {
+        // addi x5, x0, 5 and add x6, x5, x5
+        let program = Program {
+            id: "sample".to_string(),
+            instructions: [0x0050_0293, 0x0052_8333]
+                .iter()
+                .map(|word| Instruction::from_word(*word).unwrap())
+                .collect(),
+        };
+        let fast = organization("fast", [1.0; 7]);
+        let slow = organization("slow", [2.0; 7]);
+        let performance = PerformanceCalculator::monocycle_from(&fast, &slow, &program);
+
+        let markdown =
+            monocycle::report(&fast, &slow, &program, &performance).render(ReportFormat::Markdown);
+        assert!(markdown.starts_with("# Monocycle analysis of sample: fast vs slow\n"));
+        assert!(markdown.contains("Bin file sample has 2 instructions."));
+        assert!(markdown.contains("| addi | 1 | 50% | 1 | 2 |"));
+        assert!(markdown.contains("| Total cycles | 2 | 4 |"));
+        assert!(markdown.contains("4 ns / 2 ns = 2, so"));
+    }
+}
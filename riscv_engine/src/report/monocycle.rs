@@ -0,0 +1,227 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    organization::Organization,
+    performance_calculator::monocycle_calculator::{format_clock, PerformanceCalculator},
+    program::Program,
+    units,
+};
+
+use super::{instruction_mix, number, percentage, AnalysisReport};
+
+fn cpi_rows(organization_a: &Organization, organization_b: &Organization) -> Vec<Vec<String>> {
+    let mut rows = vec![
+        (
+            "R",
+            organization_a.cpi_instruction_r,
+            organization_b.cpi_instruction_r,
+        ),
+        (
+            "I",
+            organization_a.cpi_instruction_i,
+            organization_b.cpi_instruction_i,
+        ),
+        (
+            "L",
+            organization_a.cpi_instruction_l,
+            organization_b.cpi_instruction_l,
+        ),
+        (
+            "S",
+            organization_a.cpi_instruction_s,
+            organization_b.cpi_instruction_s,
+        ),
+        (
+            "B",
+            organization_a.cpi_instruction_b,
+            organization_b.cpi_instruction_b,
+        ),
+        (
+            "U",
+            organization_a.cpi_instruction_u,
+            organization_b.cpi_instruction_u,
+        ),
+        (
+            "J",
+            organization_a.cpi_instruction_j,
+            organization_b.cpi_instruction_j,
+        ),
+    ]
+    .into_iter()
+    .map(|(format, cpi_a, cpi_b)| vec![format.to_string(), number(cpi_a), number(cpi_b)])
+    .collect::<Vec<Vec<String>>>();
+
+    // Overrides only one of the organizations has fall back to its format CPI
+    let keys: BTreeSet<&String> = organization_a
+        .cpi_map
+        .keys()
+        .chain(organization_b.cpi_map.keys())
+        .collect();
+    for key in keys {
+        let cpi = |organization: &Organization| match organization.cpi_map.get(key) {
+            Some(cpi) => number(*cpi),
+            None => "-".to_string(),
+        };
+        rows.push(vec![key.clone(), cpi(organization_a), cpi(organization_b)]);
+    }
+
+    rows
+}
+
+// Texec = instructions * CPI * Tclock, written out for one organization
+fn execution_time_formula(
+    organization: &Organization,
+    total_instructions: usize,
+    average_cpi: f32,
+    execution_time: f32,
+) -> String {
+    format!(
+        "{}: {} instructions × {} CPI × {} = {}",
+        organization.id,
+        total_instructions,
+        number(average_cpi),
+        units::format_time(organization.clock_period),
+        units::format_time(execution_time)
+    )
+}
+
+// Inputs, instruction mix, results and speedup of a monocycle comparison
+pub fn report(
+    organization_a: &Organization,
+    organization_b: &Organization,
+    program: &Program,
+    performance: &PerformanceCalculator,
+) -> AnalysisReport {
+    let result = &performance.result;
+    let total_instructions = program.instructions.len();
+    let mut report = AnalysisReport::new(&format!(
+        "Monocycle analysis of {}: {} vs {}",
+        program.id, organization_a.id, organization_b.id
+    ));
+
+    report.heading(2, "Inputs");
+    report.paragraph(format!(
+        "Bin file {} has {} instructions.",
+        program.id, total_instructions
+    ));
+    report.table(
+        &["Organization", "Clock"],
+        vec![
+            vec![organization_a.id.clone(), format_clock(organization_a)],
+            vec![organization_b.id.clone(), format_clock(organization_b)],
+        ],
+    );
+    report.heading(3, "CPI");
+    report.table(
+        &[
+            "Format or instruction",
+            &organization_a.id,
+            &organization_b.id,
+        ],
+        cpi_rows(organization_a, organization_b),
+    );
+
+    report.heading(2, "Instruction mix");
+    report.table(
+        &[
+            "Instruction",
+            "Count",
+            "Share",
+            &format!("Cycles on {}", organization_a.id),
+            &format!("Cycles on {}", organization_b.id),
+        ],
+        instruction_mix(program)
+            .into_iter()
+            .map(|(mnemonic, count)| {
+                let cycles = |organization: &Organization| {
+                    program
+                        .instructions
+                        .iter()
+                        .filter(|&inst| inst.mnemonic() == mnemonic)
+                        .map(|inst| organization.cpi_for(inst))
+                        .sum::<f32>()
+                };
+
+                vec![
+                    mnemonic.to_string(),
+                    count.to_string(),
+                    percentage(count, total_instructions),
+                    number(cycles(organization_a)),
+                    number(cycles(organization_b)),
+                ]
+            })
+            .collect(),
+    );
+
+    report.heading(2, "Results");
+    report.table(
+        &["Metric", &organization_a.id, &organization_b.id],
+        vec![
+            vec![
+                "Total cycles".to_string(),
+                number(result.total_cicles_organization_a),
+                number(result.total_cicles_organization_b),
+            ],
+            vec![
+                "Average CPI".to_string(),
+                number(result.average_cpi_organization_a),
+                number(result.average_cpi_organization_b),
+            ],
+            vec![
+                "Execution time".to_string(),
+                result.formatted_execution_time_organization_a.clone(),
+                result.formatted_execution_time_organization_b.clone(),
+            ],
+            vec![
+                "MIPS".to_string(),
+                number(result.mips_organization_a),
+                number(result.mips_organization_b),
+            ],
+        ],
+    );
+
+    report.heading(2, "Speedup");
+    report.paragraph(
+        "The execution time is the number of instructions times the average CPI times the clock period:"
+            .to_string(),
+    );
+    report.list(vec![
+        execution_time_formula(
+            organization_a,
+            total_instructions,
+            result.average_cpi_organization_a,
+            result.execution_time_organization_a,
+        ),
+        execution_time_formula(
+            organization_b,
+            total_instructions,
+            result.average_cpi_organization_b,
+            result.execution_time_organization_b,
+        ),
+    ]);
+
+    let (time_a, time_b) = (
+        result.execution_time_organization_a,
+        result.execution_time_organization_b,
+    );
+    let comparison = &result.comparison;
+    report.paragraph(if comparison.equally_fast {
+        format!(
+            "Both take the same time, so {} and {} are equally fast on {}.",
+            organization_a.id, organization_b.id, program.id
+        )
+    } else {
+        format!(
+            "The speedup is the slower time divided by the faster one: {} / {} = {}, so {} is {} times faster than {} on {}.",
+            units::format_time(time_a.max(time_b)),
+            units::format_time(time_a.min(time_b)),
+            number(comparison.speedup),
+            comparison.winner,
+            number(comparison.speedup),
+            comparison.loser,
+            program.id
+        )
+    });
+
+    report
+}
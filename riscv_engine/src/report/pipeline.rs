@@ -0,0 +1,299 @@
+use crate::{
+    organization::Organization,
+    performance_calculator::{
+        data_hazard::{hazard_report, HazardKind},
+        memory_dependency::{memory_dependencies, AliasKind},
+        monocycle_calculator::format_clock,
+        pipeline_calculator::{PerformanceCalculator, TechniqueProgram},
+    },
+    pipeline_diagram::instruction_label,
+    program::Program,
+    riscv_core::instruction::Instruction,
+    units,
+};
+
+use super::{instruction_mix, number, organization_rows, percentage, AnalysisReport};
+
+fn nops(instructions: &[Instruction]) -> usize {
+    instructions.iter().filter(|&inst| inst.is_nop()).count()
+}
+
+fn kind_name(kind: HazardKind) -> &'static str {
+    match kind {
+        HazardKind::Raw => "RAW",
+        HazardKind::War => "WAR",
+        HazardKind::Waw => "WAW",
+    }
+}
+
+// Dependencies close enough to stall the pipeline, instructions numbered from 1
+fn hazard_rows(program: &Program) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+
+    for instruction_report in hazard_report(&program.instructions) {
+        for dependency in instruction_report.dependencies {
+            if dependency.stalls_without_forwarding == 0 {
+                continue;
+            }
+
+            rows.push(vec![
+                (dependency.consumer + 1).to_string(),
+                instruction_label(&program.instructions[dependency.consumer]),
+                kind_name(dependency.kind).to_string(),
+                dependency.register,
+                format!(
+                    "{} ({})",
+                    dependency.producer + 1,
+                    instruction_label(&program.instructions[dependency.producer])
+                ),
+                dependency.distance.to_string(),
+                dependency.stalls_without_forwarding.to_string(),
+                dependency.stalls_with_forwarding.to_string(),
+            ]);
+        }
+    }
+
+    rows
+}
+
+fn alias_name(alias: AliasKind) -> &'static str {
+    match alias {
+        AliasKind::NoAlias => "no",
+        AliasKind::MayAlias => "may",
+        AliasKind::MustAlias => "must",
+    }
+}
+
+// Loads and stores that may touch the same bytes, instructions numbered from 1
+fn memory_rows(program: &Program) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+
+    for dependency in memory_dependencies(&program.instructions)
+        .into_iter()
+        .flatten()
+    {
+        rows.push(vec![
+            (dependency.consumer + 1).to_string(),
+            instruction_label(&program.instructions[dependency.consumer]),
+            kind_name(dependency.kind).to_string(),
+            format!(
+                "{} ({})",
+                dependency.producer + 1,
+                instruction_label(&program.instructions[dependency.producer])
+            ),
+            dependency.distance.to_string(),
+            alias_name(dependency.alias).to_string(),
+        ]);
+    }
+
+    rows
+}
+
+fn listing(instructions: &[Instruction]) -> Vec<String> {
+    let width = instructions.len().to_string().len();
+
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, inst)| {
+            format!(
+                "{:>width$}  {}  {}",
+                index + 1,
+                inst.binary_text(),
+                instruction_label(inst),
+                width = width
+            )
+        })
+        .collect()
+}
+
+// Inputs, instruction mix, hazards, technique results and listings of a pipeline run
+pub fn report(
+    organization: &Organization,
+    program: &Program,
+    techniques: &[TechniqueProgram],
+    performance: &PerformanceCalculator,
+) -> AnalysisReport {
+    let total_instructions = program.instructions.len();
+    let mut report = AnalysisReport::new(&format!(
+        "Pipeline analysis of {} on {}",
+        program.id, organization.id
+    ));
+
+    report.heading(2, "Inputs");
+    report.paragraph(format!(
+        "Bin file {} has {} instructions. Organization {} has a clock of {}.",
+        program.id,
+        total_instructions,
+        organization.id,
+        format_clock(organization)
+    ));
+    report.table(
+        &["Format or instruction", "CPI"],
+        organization_rows(organization),
+    );
+
+    report.heading(2, "Instruction mix");
+    report.table(
+        &["Instruction", "Count", "Share", "Cycles"],
+        instruction_mix(program)
+            .into_iter()
+            .map(|(mnemonic, count)| {
+                let cycles: f32 = program
+                    .instructions
+                    .iter()
+                    .filter(|&inst| inst.mnemonic() == mnemonic)
+                    .map(|inst| organization.cpi_for(inst))
+                    .sum();
+
+                vec![
+                    mnemonic.to_string(),
+                    count.to_string(),
+                    percentage(count, total_instructions),
+                    number(cycles),
+                ]
+            })
+            .collect(),
+    );
+
+    report.heading(2, "Hazards");
+    let hazards = hazard_rows(program);
+    if hazards.is_empty() {
+        report.paragraph(
+            "No instruction depends on one of the two instructions before it.".to_string(),
+        );
+    } else {
+        report.paragraph(
+            "Dependencies on one of the two previous instructions. Without forwarding they cost the NOPs the techniques insert; with forwarding only a load followed by a use of its result stalls."
+                .to_string(),
+        );
+        report.table(
+            &[
+                "#",
+                "Instruction",
+                "Kind",
+                "Register",
+                "Producer",
+                "Distance",
+                "Stalls without forwarding",
+                "Stalls with forwarding",
+            ],
+            hazards,
+        );
+    }
+
+    report.heading(2, "Memory dependencies");
+    let memory = memory_rows(program);
+    if memory.is_empty() {
+        report.paragraph("No store may touch the bytes of a nearby load or store.".to_string());
+    } else {
+        report.paragraph(
+            "Loads and stores that may touch the same bytes, compared by base register and offset. The reorder techniques never move one above the other."
+                .to_string(),
+        );
+        report.table(
+            &["#", "Instruction", "Kind", "Producer", "Distance", "Alias"],
+            memory,
+        );
+    }
+
+    report.heading(2, "Results");
+    let mut rows = Vec::new();
+    for technique_result in performance.results.iter() {
+        let technique = techniques
+            .iter()
+            .find(|technique| technique.technique_name == technique_result.technique_name);
+        let instructions = technique
+            .map(|technique| technique.instructions.as_slice())
+            .unwrap_or(&program.instructions);
+        let inserted = nops(instructions) - nops(&program.instructions).min(nops(instructions));
+
+        rows.push(vec![
+            technique_result.technique_name.clone(),
+            match technique {
+                Some(technique) if technique.forwarding => "yes".to_string(),
+                _ => "no".to_string(),
+            },
+            instructions.len().to_string(),
+            inserted.to_string(),
+            percentage(inserted, total_instructions),
+            number(technique_result.total_cicles),
+            number(technique_result.cicles_diference),
+            number(technique_result.average_cpi),
+            technique_result.formatted_execution_time.clone(),
+            number(technique_result.mips),
+            number(technique_result.performance),
+        ]);
+    }
+    report.table(
+        &[
+            "Technique",
+            "Forwarding",
+            "Instructions",
+            "NOPs inserted",
+            "Instruction overhead",
+            "Total cycles",
+            "Cycle difference",
+            "Average CPI",
+            "Execution time",
+            "MIPS",
+            "Performance",
+        ],
+        rows,
+    );
+    for technique_result in performance.results.iter() {
+        if technique_result.false_dependencies_removed > 0 {
+            report.paragraph(format!(
+                "{} moved destinations to free registers, removing {} WAR and WAW dependencies before inserting NOPs.",
+                technique_result.technique_name, technique_result.false_dependencies_removed
+            ));
+        }
+    }
+
+    report.heading(2, "Speedup");
+    report.paragraph(
+        "The execution time is the number of instructions times the average CPI times the clock period. The performance of a technique is the execution time of the original program divided by its own, so the NOPs it inserts show up as a performance below 1."
+            .to_string(),
+    );
+    report.list(
+        performance
+            .results
+            .iter()
+            .map(|technique_result| {
+                format!(
+                    "{}: {} × {} = {}, performance {}",
+                    technique_result.technique_name,
+                    number(technique_result.total_cicles),
+                    units::format_time(organization.clock_period),
+                    technique_result.formatted_execution_time,
+                    number(technique_result.performance)
+                )
+            })
+            .collect(),
+    );
+
+    let technique_results = performance.results.iter().skip(1);
+    let fastest = technique_results
+        .clone()
+        .min_by(|a, b| a.execution_time.total_cmp(&b.execution_time));
+    let slowest = technique_results.max_by(|a, b| a.execution_time.total_cmp(&b.execution_time));
+    if let (Some(fastest), Some(slowest)) = (fastest, slowest) {
+        report.paragraph(format!(
+            "{} is the fastest technique, {} times faster than {}. From fastest to slowest: {}.",
+            fastest.technique_name,
+            number(slowest.execution_time / fastest.execution_time),
+            slowest.technique_name,
+            performance.basic_information.best_performance.join(", ")
+        ));
+    }
+
+    report.heading(2, "Transformed listings");
+    report.heading(3, "original");
+    report.listing(listing(&program.instructions));
+    for technique in techniques {
+        report.heading(3, &technique.technique_name);
+        report.listing(listing(&technique.instructions));
+    }
+
+    report
+}
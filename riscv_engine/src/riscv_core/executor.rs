@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use crate::riscv_core::instruction::{Instruction, OpCodeType, INSTRUCTION_SIZE, ZERO_REGISTER};
+
+// Where RARS places the text segment and the stack, so auipc and sp match the dumps
+pub const TEXT_BASE: u32 = 0x0040_0000;
+pub const STACK_POINTER: u32 = 0x7fff_effc;
+pub const GLOBAL_POINTER: u32 = 0x1000_8000;
+// Stops programs that never reach an exit
+pub const MAX_STEPS: usize = 1_000_000;
+
+const INSTRUCTION_BYTES: u32 = (INSTRUCTION_SIZE / 8) as u32;
+const SP: usize = 2;
+const GP: usize = 3;
+const A7: usize = 17;
+// exit, exit2 and the Linux exit
+const EXIT_CALLS: [u32; 3] = [10, 17, 93];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessKind {
+    Fetch,
+    Load,
+    Store,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryAccess {
+    pub kind: AccessKind,
+    pub address: u32,
+    pub bytes: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    // An exit environment call
+    Exit,
+    // Ran past the last instruction
+    EndOfProgram,
+    // Jumped outside the text segment or to an instruction that is not aligned
+    InvalidJump,
+    Breakpoint,
+    StepLimit,
+}
+
+#[derive(Clone, Debug)]
+pub struct Execution {
+    // Program index of every executed instruction, in order
+    pub executed: Vec<usize>,
+    pub trace: Vec<MemoryAccess>,
+    pub registers: [u32; 32],
+    pub stop: StopReason,
+}
+
+fn load(memory: &HashMap<u32, u8>, address: u32, bytes: u32) -> u32 {
+    (0..bytes).fold(0, |value, byte| {
+        let byte_value = *memory.get(&address.wrapping_add(byte)).unwrap_or(&0) as u32;
+        value | (byte_value << (8 * byte))
+    })
+}
+
+fn store(memory: &mut HashMap<u32, u8>, address: u32, bytes: u32, value: u32) {
+    for byte in 0..bytes {
+        memory.insert(address.wrapping_add(byte), (value >> (8 * byte)) as u8);
+    }
+}
+
+fn sign_extend(value: u32, bytes: u32) -> u32 {
+    let shift = 32 - 8 * bytes;
+    (((value << shift) as i32) >> shift) as u32
+}
+
+fn alu(mnemonic: &str, a: u32, b: u32) -> u32 {
+    let shamt = b & 0x1f;
+
+    match mnemonic {
+        "add" | "addi" => a.wrapping_add(b),
+        "sub" => a.wrapping_sub(b),
+        "sll" | "slli" => a << shamt,
+        "slt" | "slti" => u32::from((a as i32) < (b as i32)),
+        "sltu" | "sltiu" => u32::from(a < b),
+        "xor" | "xori" => a ^ b,
+        "srl" | "srli" => a >> shamt,
+        "sra" | "srai" => ((a as i32) >> shamt) as u32,
+        "or" | "ori" => a | b,
+        "and" | "andi" => a & b,
+        "mul" => a.wrapping_mul(b),
+        "mulh" => ((a as i32 as i64 * b as i32 as i64) >> 32) as u32,
+        "mulhsu" => ((a as i32 as i64 * b as i64) >> 32) as u32,
+        "mulhu" => ((a as u64 * b as u64) >> 32) as u32,
+        // Division by zero and overflow give the results the specification defines
+        "div" => match b {
+            0 => u32::MAX,
+            _ => (a as i32).wrapping_div(b as i32) as u32,
+        },
+        "divu" => a.checked_div(b).unwrap_or(u32::MAX),
+        "rem" => match b {
+            0 => a,
+            _ => (a as i32).wrapping_rem(b as i32) as u32,
+        },
+        "remu" => a.checked_rem(b).unwrap_or(a),
+        _ => 0,
+    }
+}
+
+fn branch_taken(mnemonic: &str, a: u32, b: u32) -> bool {
+    match mnemonic {
+        "beq" => a == b,
+        "bne" => a != b,
+        "blt" => (a as i32) < (b as i32),
+        "bge" => (a as i32) >= (b as i32),
+        "bltu" => a < b,
+        "bgeu" => a >= b,
+        _ => false,
+    }
+}
+
+fn access_bytes(inst: &Instruction) -> u32 {
+    match inst.funct3() & 0b11 {
+        0b00 => 1,
+        0b01 => 2,
+        _ => 4,
+    }
+}
+
+// Runs the program from its first instruction, with the text at TEXT_BASE and memory zeroed, and
+// records every fetch, load and store. CSR instructions, fence and the environment calls that are
+// not an exit do nothing
+pub fn execute(instructions: &[Instruction], max_steps: usize) -> Execution {
+    let mut registers = [0u32; 32];
+    registers[SP] = STACK_POINTER;
+    registers[GP] = GLOBAL_POINTER;
+    let mut memory: HashMap<u32, u8> = HashMap::new();
+    let mut executed = Vec::new();
+    let mut trace = Vec::new();
+    let text_end = TEXT_BASE + instructions.len() as u32 * INSTRUCTION_BYTES;
+
+    let mut pc = TEXT_BASE;
+    let stop = loop {
+        if pc == text_end {
+            break StopReason::EndOfProgram;
+        }
+        if pc < TEXT_BASE || pc > text_end || !(pc - TEXT_BASE).is_multiple_of(INSTRUCTION_BYTES) {
+            break StopReason::InvalidJump;
+        }
+        if executed.len() == max_steps {
+            break StopReason::StepLimit;
+        }
+
+        let index = ((pc - TEXT_BASE) / INSTRUCTION_BYTES) as usize;
+        let inst = &instructions[index];
+        executed.push(index);
+        trace.push(MemoryAccess {
+            kind: AccessKind::Fetch,
+            address: pc,
+            bytes: INSTRUCTION_BYTES,
+        });
+
+        let rs1 = registers[inst.rs1() as usize];
+        let rs2 = registers[inst.rs2() as usize];
+        let immediate = inst.immediate() as u32;
+        let mnemonic = inst.mnemonic();
+        let mut next = pc.wrapping_add(INSTRUCTION_BYTES);
+        let mut result = None;
+
+        match inst.opcode() {
+            OpCodeType::R => result = Some(alu(mnemonic, rs1, rs2)),
+            OpCodeType::I => match mnemonic {
+                "jalr" => {
+                    result = Some(next);
+                    next = rs1.wrapping_add(immediate) & !1;
+                }
+                "ecall" if EXIT_CALLS.contains(&registers[A7]) => {
+                    break StopReason::Exit;
+                }
+                "ebreak" => break StopReason::Breakpoint,
+                "ecall" | "fence" | "csrrw" | "csrrs" | "csrrc" | "csrrwi" | "csrrsi"
+                | "csrrci" => {}
+                // Shifts only take the low 5 bits, so the funct7 of srai does not matter
+                _ => result = Some(alu(mnemonic, rs1, immediate)),
+            },
+            OpCodeType::L => {
+                let address = rs1.wrapping_add(immediate);
+                let bytes = access_bytes(inst);
+                trace.push(MemoryAccess {
+                    kind: AccessKind::Load,
+                    address,
+                    bytes,
+                });
+                let value = load(&memory, address, bytes);
+                result = Some(match mnemonic {
+                    "lbu" | "lhu" => value,
+                    _ => sign_extend(value, bytes),
+                });
+            }
+            OpCodeType::S => {
+                let address = rs1.wrapping_add(immediate);
+                let bytes = access_bytes(inst);
+                trace.push(MemoryAccess {
+                    kind: AccessKind::Store,
+                    address,
+                    bytes,
+                });
+                store(&mut memory, address, bytes, rs2);
+            }
+            OpCodeType::B => {
+                if branch_taken(mnemonic, rs1, rs2) {
+                    next = pc.wrapping_add(immediate);
+                }
+            }
+            OpCodeType::U => {
+                result = Some(match mnemonic {
+                    "lui" => immediate,
+                    _ => pc.wrapping_add(immediate),
+                })
+            }
+            OpCodeType::J => {
+                result = Some(next);
+                next = pc.wrapping_add(immediate);
+            }
+        }
+
+        if let Some(value) = result.filter(|_| inst.rd() != ZERO_REGISTER) {
+            registers[inst.rd() as usize] = value;
+        }
+        pc = next;
+    };
+
+    Execution {
+        executed,
+        trace,
+        registers,
+        stop,
+    }
+}
@@ -0,0 +1,422 @@
+use crate::constants::NOP_WORD;
+
+/////////////////////////////////////////
+/*
+Importante:
+Cada formato de insrução terá um CPI:
+R,
+I,
+S,
+B,
+U,
+J,
+L,
+*/
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum OpCodeType {
+    R,
+    I,
+    L,
+    S,
+    B,
+    U,
+    J,
+}
+
+// One instruction as its 32 bit word, the fields are decoded when they are read
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    word: u32,
+    opcode: OpCodeType,
+}
+
+// Registers an instruction reads and writes. x0 is left out: it always reads 0 and writes to it are
+// dropped, so it never carries a dependency
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Operands {
+    pub reads: [Option<u8>; 2],
+    pub write: Option<u8>,
+}
+
+impl Operands {
+    fn new(reads: [Option<u8>; 2], write: Option<u8>) -> Operands {
+        let register =
+            |register: Option<u8>| register.filter(|&register| register != ZERO_REGISTER);
+        let first = register(reads[0]);
+        let second = register(reads[1]).filter(|&register| Some(register) != first);
+
+        Operands {
+            reads: [first, second],
+            write: register(write),
+        }
+    }
+}
+
+pub const INSTRUCTION_SIZE: usize = 32;
+
+pub const ZERO_REGISTER: u8 = 0;
+// Environment calls take the service number in a7 and the argument in a0, and return in a0
+// (the RARS and Venus convention)
+const A0: u8 = 10;
+const A7: u8 = 17;
+
+// Every mnemonic Instruction::mnemonic can decode (RV32I + RV32M)
+pub const MNEMONICS: &[&str] = &[
+    "add", "sub", "sll", "slt", "sltu", "xor", "srl", "sra", "or", "and", "mul", "mulh", "mulhsu",
+    "mulhu", "div", "divu", "rem", "remu", "addi", "slli", "slti", "sltiu", "xori", "srai", "srli",
+    "ori", "andi", "jalr", "fence", "ebreak", "ecall", "csrrw", "csrrs", "csrrc", "csrrwi",
+    "csrrsi", "csrrci", "lb", "lh", "lw", "lbu", "lhu", "sb", "sh", "sw", "beq", "bne", "blt",
+    "bge", "bltu", "bgeu", "lui", "auipc", "jal",
+];
+
+fn opcode_type(opcode_bits: u32) -> Option<OpCodeType> {
+    match opcode_bits {
+        0b0110011 => Some(OpCodeType::R),
+        0b1110011 | 0b0010011 | 0b0001111 | 0b1100111 => Some(OpCodeType::I),
+        0b0000011 => Some(OpCodeType::L),
+        0b0100011 => Some(OpCodeType::S),
+        0b1100011 => Some(OpCodeType::B),
+        0b0110111 | 0b0010111 => Some(OpCodeType::U),
+        0b1101111 => Some(OpCodeType::J),
+        _ => None,
+    }
+}
+
+impl Instruction {
+    pub const NOP: Instruction = Instruction {
+        word: NOP_WORD,
+        opcode: OpCodeType::I,
+    };
+
+    // Binary text, e.g. "00000000000000000000000000010011". The error says why a dump line is
+    // not an instruction
+    pub fn new(instruction: &str) -> Result<Self, String> {
+        if instruction.len() != INSTRUCTION_SIZE {
+            return Err(format!(
+                "expected {} bits, found {}",
+                INSTRUCTION_SIZE,
+                instruction.len()
+            ));
+        }
+
+        if !instruction.chars().all(|bit| bit == '0' || bit == '1') {
+            return Err("instruction must only contain 0 and 1".to_string());
+        }
+
+        let word = u32::from_str_radix(instruction, 2).unwrap_or_default();
+        Instruction::from_word(word).ok_or_else(|| {
+            format!(
+                "opcode {} not implemented",
+                &instruction[INSTRUCTION_SIZE - 7..]
+            )
+        })
+    }
+
+    pub fn from_word(word: u32) -> Option<Self> {
+        opcode_type(word & 0x7f).map(|opcode| Instruction { word, opcode })
+    }
+
+    pub fn word(&self) -> u32 {
+        self.word
+    }
+
+    pub fn binary_text(&self) -> String {
+        format!("{:032b}", self.word)
+    }
+
+    pub fn opcode(&self) -> OpCodeType {
+        self.opcode
+    }
+
+    // 00000000000000000000 00000 0000000
+    pub fn rd(&self) -> u8 {
+        ((self.word >> 7) & 0x1f) as u8
+    }
+
+    // 000000000000 00000 000000000000000
+    pub fn rs1(&self) -> u8 {
+        ((self.word >> 15) & 0x1f) as u8
+    }
+
+    // 0000000 00000 00000000000000000000
+    pub fn rs2(&self) -> u8 {
+        ((self.word >> 20) & 0x1f) as u8
+    }
+
+    // Same instruction with another register in the field, whatever the format
+    pub fn with_rd(&self, register: u8) -> Instruction {
+        self.with_field(7, register)
+    }
+
+    pub fn with_rs1(&self, register: u8) -> Instruction {
+        self.with_field(15, register)
+    }
+
+    pub fn with_rs2(&self, register: u8) -> Instruction {
+        self.with_field(20, register)
+    }
+
+    fn with_field(&self, shift: u32, register: u8) -> Instruction {
+        Instruction {
+            word: (self.word & !(0x1f << shift)) | ((register as u32 & 0x1f) << shift),
+            opcode: self.opcode,
+        }
+    }
+
+    // 00000000000000000 000 000000000000
+    pub fn funct3(&self) -> u8 {
+        ((self.word >> 12) & 0x7) as u8
+    }
+
+    // 0000000 0000000000000000000000000
+    pub fn funct7(&self) -> u8 {
+        (self.word >> 25) as u8
+    }
+
+    // Sign-extended immediate of the format (U keeps it in the upper 20 bits), 0 for R
+    pub fn immediate(&self) -> i32 {
+        let word = self.word;
+        let signed = word as i32;
+
+        match self.opcode {
+            OpCodeType::R => 0,
+            OpCodeType::I | OpCodeType::L => signed >> 20,
+            OpCodeType::S => ((signed >> 25) << 5) | ((word >> 7) & 0x1f) as i32,
+            OpCodeType::B => {
+                ((signed >> 31) << 12)
+                    | (((word >> 7) & 0x1) << 11) as i32
+                    | (((word >> 25) & 0x3f) << 5) as i32
+                    | (((word >> 8) & 0xf) << 1) as i32
+            }
+            OpCodeType::U => signed & !0xfff,
+            OpCodeType::J => {
+                ((signed >> 31) << 20)
+                    | (word & 0xff000) as i32
+                    | (((word >> 20) & 0x1) << 11) as i32
+                    | (((word >> 21) & 0x3ff) << 1) as i32
+            }
+        }
+    }
+
+    // Same branch or jal jumping offset bytes away. None for other formats, or when the offset
+    // does not fit (±4 KiB for branches, ±1 MiB for jal) or is not even
+    pub fn with_offset(&self, offset: i32) -> Option<Instruction> {
+        let bits = offset as u32;
+        let (range, word) = match self.opcode {
+            OpCodeType::B => (
+                1 << 12,
+                (self.word & 0x01fff07f)
+                    | (((bits >> 12) & 0x1) << 31)
+                    | (((bits >> 5) & 0x3f) << 25)
+                    | (((bits >> 1) & 0xf) << 8)
+                    | (((bits >> 11) & 0x1) << 7),
+            ),
+            OpCodeType::J => (
+                1 << 20,
+                (self.word & 0xfff)
+                    | (((bits >> 20) & 0x1) << 31)
+                    | (((bits >> 1) & 0x3ff) << 21)
+                    | (((bits >> 11) & 0x1) << 20)
+                    | (bits & 0xff000),
+            ),
+            _ => return None,
+        };
+
+        if offset % 2 != 0 || offset < -range || offset >= range {
+            return None;
+        }
+
+        Some(Instruction {
+            word,
+            opcode: self.opcode,
+        })
+    }
+
+    pub fn operands(&self) -> Operands {
+        let rd = Some(self.rd());
+        let rs1 = Some(self.rs1());
+        let rs2 = Some(self.rs2());
+
+        match self.opcode {
+            OpCodeType::R => Operands::new([rs1, rs2], rd),
+            // jalr, the CSR instructions and the loads also follow the rd <- rs1 pattern
+            OpCodeType::I => match self.mnemonic() {
+                "ecall" => Operands::new([Some(A7), Some(A0)], Some(A0)),
+                "ebreak" | "fence" => Operands::default(),
+                // The rs1 field holds an immediate
+                "csrrwi" | "csrrsi" | "csrrci" => Operands::new([None, None], rd),
+                _ => Operands::new([rs1, None], rd),
+            },
+            OpCodeType::L => Operands::new([rs1, None], rd),
+            OpCodeType::S | OpCodeType::B => Operands::new([rs1, rs2], None),
+            // lui, auipc and jal only have immediates besides rd
+            OpCodeType::U | OpCodeType::J => Operands::new([None, None], rd),
+        }
+    }
+
+    pub fn is_nop(&self) -> bool {
+        self.word == NOP_WORD
+    }
+
+    // Branches with a negative offset (sign bit set) jump backwards, usually closing a loop
+    pub fn is_backward_branch(&self) -> bool {
+        self.opcode == OpCodeType::B && self.word >> 31 == 1
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        let funct3 = self.funct3();
+        let funct7 = self.funct7();
+
+        match self.opcode {
+            OpCodeType::R => match (funct7, funct3) {
+                (0b0000000, 0b000) => "add",
+                (0b0100000, 0b000) => "sub",
+                (0b0000000, 0b001) => "sll",
+                (0b0000000, 0b010) => "slt",
+                (0b0000000, 0b011) => "sltu",
+                (0b0000000, 0b100) => "xor",
+                (0b0000000, 0b101) => "srl",
+                (0b0100000, 0b101) => "sra",
+                (0b0000000, 0b110) => "or",
+                (0b0000000, 0b111) => "and",
+                // RV32M
+                (0b0000001, 0b000) => "mul",
+                (0b0000001, 0b001) => "mulh",
+                (0b0000001, 0b010) => "mulhsu",
+                (0b0000001, 0b011) => "mulhu",
+                (0b0000001, 0b100) => "div",
+                (0b0000001, 0b101) => "divu",
+                (0b0000001, 0b110) => "rem",
+                (0b0000001, 0b111) => "remu",
+                _ => "unknown",
+            },
+            OpCodeType::I => match (self.word & 0x7f, funct3) {
+                (0b0010011, 0b000) => "addi",
+                (0b0010011, 0b001) => "slli",
+                (0b0010011, 0b010) => "slti",
+                (0b0010011, 0b011) => "sltiu",
+                (0b0010011, 0b100) => "xori",
+                (0b0010011, 0b101) if funct7 == 0b0100000 => "srai",
+                (0b0010011, 0b101) => "srli",
+                (0b0010011, 0b110) => "ori",
+                (0b0010011, 0b111) => "andi",
+                (0b1100111, _) => "jalr",
+                (0b0001111, _) => "fence",
+                (0b1110011, 0b000) if self.word >> 20 == 1 => "ebreak",
+                (0b1110011, 0b000) => "ecall",
+                (0b1110011, 0b001) => "csrrw",
+                (0b1110011, 0b010) => "csrrs",
+                (0b1110011, 0b011) => "csrrc",
+                (0b1110011, 0b101) => "csrrwi",
+                (0b1110011, 0b110) => "csrrsi",
+                (0b1110011, 0b111) => "csrrci",
+                _ => "unknown",
+            },
+            OpCodeType::L => match funct3 {
+                0b000 => "lb",
+                0b001 => "lh",
+                0b010 => "lw",
+                0b100 => "lbu",
+                0b101 => "lhu",
+                _ => "unknown",
+            },
+            OpCodeType::S => match funct3 {
+                0b000 => "sb",
+                0b001 => "sh",
+                0b010 => "sw",
+                _ => "unknown",
+            },
+            OpCodeType::B => match funct3 {
+                0b000 => "beq",
+                0b001 => "bne",
+                0b100 => "blt",
+                0b101 => "bge",
+                0b110 => "bltu",
+                0b111 => "bgeu",
+                _ => "unknown",
+            },
+            OpCodeType::U => match self.word & 0x7f {
+                0b0110111 => "lui",
+                _ => "auipc",
+            },
+            OpCodeType::J => "jal",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction(word: u32) -> Instruction {
+        Instruction::from_word(word).unwrap()
+    }
+
+    #[test]
+    fn words_round_trip_through_binary_text() {
+        assert!(std::mem::size_of::<Instruction>() <= 8);
+
+        let dumps = [
+            include_str!("../../../riscv_bin_dump/fatorial.txt"),
+            include_str!("../../../riscv_bin_dump/vetor_decrescente_bubblesort.txt"),
+        ];
+        for line in dumps.iter().flat_map(|dump| dump.lines()) {
+            let line = line.trim();
+            let inst = Instruction::new(line).unwrap();
+            assert_eq!(inst.binary_text(), line);
+            assert_eq!(Instruction::from_word(inst.word()), Some(inst));
+        }
+
+        // Opcode 1111111 is not RV32I
+        assert_eq!(Instruction::from_word(0xffff_ffff), None);
+        assert_eq!(
+            Instruction::new("11111111111111111111111111111111"),
+            Err("opcode 1111111 not implemented".to_string())
+        );
+        assert!(Instruction::new("0x00500293").is_err());
+    }
+
+    #[test]
+    fn fields_and_immediates_are_decoded_from_the_word() {
+        // add x7, x6, x5
+        let add = instruction(0x0053_03b3);
+        assert_eq!((add.rd(), add.rs1(), add.rs2()), (7, 6, 5));
+        assert_eq!((add.funct3(), add.funct7(), add.immediate()), (0, 0, 0));
+        assert_eq!(add.mnemonic(), "add");
+
+        // Changing one register keeps every other bit
+        let renamed = add.with_rd(9).with_rs2(11);
+        assert_eq!((renamed.rd(), renamed.rs1(), renamed.rs2()), (9, 6, 11));
+        assert_eq!(renamed.mnemonic(), "add");
+
+        // addi x5, x0, -1; sw x6, -4(x10); lui x5, 0x12345
+        assert_eq!(instruction(0xfff0_0293).immediate(), -1);
+        assert_eq!(instruction(0xfe65_2e23).immediate(), -4);
+        assert_eq!(instruction(0xfe65_2e23).mnemonic(), "sw");
+        assert_eq!(instruction(0x1234_52b7).immediate(), 0x1234_5000);
+
+        // beq x0, x0, +8 and -8
+        let forward = instruction(0x0000_0463);
+        let backward = instruction(0xfe00_0ce3);
+        assert_eq!(forward.immediate(), 8);
+        assert_eq!(backward.immediate(), -8);
+        assert!(backward.is_backward_branch() && !forward.is_backward_branch());
+        assert_eq!(forward.with_offset(-8), Some(backward));
+    }
+
+    #[test]
+    fn offsets_must_fit_the_format() {
+        let beq = instruction(0x0000_0463);
+        let jal = instruction(0x0000_006f);
+
+        assert_eq!(beq.with_offset(-4096).unwrap().immediate(), -4096);
+        assert_eq!(beq.with_offset(4096), None);
+        assert_eq!(beq.with_offset(6).unwrap().immediate(), 6);
+        assert_eq!(beq.with_offset(3), None);
+        assert_eq!(jal.with_offset(-2048).unwrap().immediate(), -2048);
+        assert_eq!(jal.with_offset(1 << 20), None);
+        // add x7, x6, x5 has no offset
+        assert_eq!(instruction(0x0053_03b3).with_offset(8), None);
+    }
+}
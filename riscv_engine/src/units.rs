@@ -0,0 +1,103 @@
+const TIME_UNITS: [(f64, &str); 5] = [
+    (1.0, "s"),
+    (1e-3, "ms"),
+    (1e-6, "us"),
+    (1e-9, "ns"),
+    (1e-12, "ps"),
+];
+
+const ENERGY_UNITS: [(f64, &str); 5] = [
+    (1.0, "J"),
+    (1e-3, "mJ"),
+    (1e-6, "uJ"),
+    (1e-9, "nJ"),
+    (1e-12, "pJ"),
+];
+
+const POWER_UNITS: [(f64, &str); 5] = [
+    (1.0, "W"),
+    (1e-3, "mW"),
+    (1e-6, "uW"),
+    (1e-9, "nW"),
+    (1e-12, "pW"),
+];
+
+const FREQUENCY_UNITS: [(f64, &str); 4] = [(1e9, "GHz"), (1e6, "MHz"), (1e3, "kHz"), (1.0, "Hz")];
+
+fn format_scaled(value: f64, units: &[(f64, &str)]) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format!("{} {}", value, units[0].1);
+    }
+
+    // Picks the largest unit that keeps the value >= 1, falling back to the smallest one.
+    // The tolerance absorbs f32 rounding, so 1e-9 still reads as "1 ns" and not "1000 ps"
+    let (scale, unit) = units
+        .iter()
+        .find(|(scale, _)| value.abs() >= *scale * (1.0 - 1e-6))
+        .unwrap_or(&units[units.len() - 1]);
+
+    let scaled = value / scale;
+    let rounded = (scaled * 1000.0).round() / 1000.0;
+
+    format!("{} {}", rounded, unit)
+}
+
+// e.g. 0.0000000015 -> "1.5 ns"
+pub fn format_time(seconds: f32) -> String {
+    format_scaled(seconds as f64, &TIME_UNITS)
+}
+
+// e.g. 2500000000 -> "2.5 GHz"
+pub fn format_frequency(hertz: f32) -> String {
+    format_scaled(hertz as f64, &FREQUENCY_UNITS)
+}
+
+// e.g. 0.0000000025 -> "2.5 nJ"
+pub fn format_energy(joules: f32) -> String {
+    format_scaled(joules as f64, &ENERGY_UNITS)
+}
+
+// e.g. 0.15 -> "150 mW"
+pub fn format_power(watts: f32) -> String {
+    format_scaled(watts as f64, &POWER_UNITS)
+}
+
+// MIPS = Total Instructions / (Texec * 10^6)
+pub fn mips(total_instructions: usize, execution_time: f32) -> f32 {
+    instructions_per_second(total_instructions, execution_time) / 1e6
+}
+
+pub fn instructions_per_second(total_instructions: usize, execution_time: f32) -> f32 {
+    if execution_time <= 0.0 {
+        return 0.0;
+    }
+
+    (total_instructions as f64 / execution_time as f64) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_take_the_largest_unit_above_one() {
+        assert_eq!(format_time(1.5e-9), "1.5 ns");
+        // Not "1000 ps", although 1e-9 is slightly below one nanosecond in f32
+        assert_eq!(format_time(1e-9), "1 ns");
+        assert_eq!(format_time(0.25), "250 ms");
+        assert_eq!(format_time(0.0), "0 s");
+        assert_eq!(format_frequency(2.5e9), "2.5 GHz");
+        assert_eq!(format_frequency(500.0), "500 Hz");
+        assert_eq!(format_energy(2.5e-9), "2.5 nJ");
+        assert_eq!(format_power(0.15), "150 mW");
+        // Below the smallest unit
+        assert_eq!(format_time(5e-13), "0.5 ps");
+    }
+
+    #[test]
+    fn mips_from_instructions_and_time() {
+        // 200 instructions in 1 us
+        assert!((mips(200, 1e-6) - 200.0).abs() < 1e-3);
+        assert_eq!(mips(200, 0.0), 0.0);
+    }
+}
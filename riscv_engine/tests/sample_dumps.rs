@@ -0,0 +1,371 @@
+use riscv_engine::{
+    performance_calculator::{
+        data_hazard::check_for_hazards,
+        liveness::{contains, jump_target, DataFlow},
+        loop_unrolling::{counted_loops, unroll_loops, CountedLoop},
+        memory_hierarchy::{Cache, CacheConfig, Replacement, WritePolicy},
+        memory_dependency::{alias, can_swap, memory_dependencies, tracking_starts, AliasKind},
+        pipeline_calculator,
+        register_renaming::rename_registers,
+        superscalar::{schedule, IssueRules, UnusedSlotReason},
+    },
+    pipeline_diagram::PipelineDiagram,
+    program::Program,
+    riscv_core::{
+        executor::{self, StopReason},
+        instruction::{Instruction, Operands},
+    },
+};
+
+fn program(name: &str) -> Program {
+    let dump = match name {
+        "fatorial" => include_str!("../../riscv_bin_dump/fatorial.txt"),
+        "laco5x" => include_str!("../../riscv_bin_dump/laco5x.txt"),
+        "raw_hazard" => include_str!("../../riscv_bin_dump/raw_hazard.txt"),
+        "test1_1" => include_str!("../../riscv_bin_dump/test1_1.txt"),
+        "vetor_decrescente_bubblesort" => {
+            include_str!("../../riscv_bin_dump/vetor_decrescente_bubblesort.txt")
+        }
+        "war_hazard" => include_str!("../../riscv_bin_dump/war_hazard.txt"),
+        "waw_hazard" => include_str!("../../riscv_bin_dump/waw_hazard.txt"),
+        _ => unreachable!("no sample dump named {}", name),
+    };
+
+    Program::from_text(name, dump).unwrap()
+}
+
+// NOPs inserted by only_nops, forwading_with_nops, reorder_with_only_nops,
+// forwading_and_reorder_with_nops and renaming_with_nops
+fn nops_per_technique(name: &str) -> Vec<usize> {
+    nops_per_technique_of(&program(name).instructions)
+}
+
+fn nops_per_technique_of(instructions: &[Instruction]) -> Vec<usize> {
+    pipeline_calculator::techniques(instructions)
+        .iter()
+        .map(|technique| {
+            technique
+                .instructions
+                .iter()
+                .filter(|inst| inst.is_nop())
+                .count()
+        })
+        .collect()
+}
+
+#[test]
+fn sample_dumps_nop_counts() {
+    let expected = [
+        ("fatorial", vec![10, 1, 10, 0, 10]),
+        ("laco5x", vec![22, 0, 22, 0, 22]),
+        ("raw_hazard", vec![4, 0, 4, 0, 4]),
+        ("test1_1", vec![0, 0, 0, 0, 0]),
+        // x0 conflicts used to add 3 NOPs after the jumps to the loop heads
+        ("vetor_decrescente_bubblesort", vec![17, 1, 17, 1, 17]),
+        ("war_hazard", vec![4, 1, 4, 1, 4]),
+        ("waw_hazard", vec![4, 0, 4, 0, 4]),
+    ];
+
+    for (name, nops) in expected {
+        assert_eq!(nops_per_technique(name), nops, "{}", name);
+    }
+}
+
+#[test]
+fn jumps_to_x0_do_not_conflict() {
+    let instructions = program("vetor_decrescente_bubblesort").instructions;
+    let hazards = check_for_hazards(&instructions);
+
+    // jal x0 (17 and 19) followed by addi x5, x5, 1 and addi x17, x0, 10
+    assert_eq!(hazards[16].highest_nops(), 0);
+    assert_eq!(hazards[18].highest_nops(), 0);
+    // addi x17, x0, 10 feeds the ecall service number in a7
+    assert_eq!(hazards[19].raw, 2);
+}
+
+#[test]
+fn operands_follow_the_mnemonic() {
+    let operands = |word: u32| Instruction::from_word(word).unwrap().operands();
+
+    // nop (addi x0, x0, 0)
+    assert_eq!(Instruction::NOP.operands(), Operands::default());
+    // jal x0, -56 and jal x1, -60
+    assert_eq!(
+        operands(0b11111100100111111111000001101111),
+        Operands::default()
+    );
+    assert_eq!(
+        operands(0b11111100010111111111000011101111),
+        Operands {
+            reads: [None, None],
+            write: Some(1),
+        }
+    );
+    // jalr x1, 0(x1)
+    assert_eq!(
+        operands(0b00000000000000001000000011100111),
+        Operands {
+            reads: [Some(1), None],
+            write: Some(1),
+        }
+    );
+    // lui x8, 0x3f41: the immediate is not read as registers
+    assert_eq!(
+        operands(0b00000011111101000001010000110111),
+        Operands {
+            reads: [None, None],
+            write: Some(8),
+        }
+    );
+    // addi x17, x0, 10
+    assert_eq!(
+        operands(0b00000000101000000000100010010011),
+        Operands {
+            reads: [None, None],
+            write: Some(17),
+        }
+    );
+    // ecall reads a7 and a0 and returns in a0
+    assert_eq!(
+        operands(0b00000000000000000000000001110011),
+        Operands {
+            reads: [Some(17), Some(10)],
+            write: Some(10),
+        }
+    );
+    // add x7, x7, x7 reads x7 once
+    assert_eq!(
+        operands(0b00000000011100111000001110110011),
+        Operands {
+            reads: [Some(7), None],
+            write: Some(7),
+        }
+    );
+}
+
+#[test]
+fn bubblesort_memory_dependencies() {
+    let instructions = program("vetor_decrescente_bubblesort").instructions;
+    let dependencies = memory_dependencies(&instructions);
+
+    // The swap stores to the addresses both loads read, x28 being x7 + 4
+    let pairs: Vec<(usize, usize, AliasKind)> = dependencies
+        .iter()
+        .flatten()
+        .map(|dependency| (dependency.producer, dependency.consumer, dependency.alias))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            (10, 13, AliasKind::MustAlias),
+            (11, 14, AliasKind::MustAlias)
+        ]
+    );
+    // Compared on their own, without addi x28, x7, 4 in view, the two stores may alias
+    let starts = tracking_starts(&instructions);
+    assert_eq!(
+        alias(&instructions, &starts, 13, 14),
+        Some(AliasKind::MayAlias)
+    );
+    assert!(!can_swap(&instructions, &starts, 10, 13));
+    // Two loads
+    assert!(can_swap(&instructions, &starts, 10, 11));
+}
+
+#[test]
+fn renaming_removes_false_dependencies() {
+    let program = Program::from_text(
+        "renaming",
+        // lw x5, 0(x10); addi x10, x11, 4; sw x12, 0(x13)
+        "00000000000001010010001010000011
+         00000000010001011000010100010011
+         00000000110001101010000000100011",
+    )
+    .unwrap();
+
+    let renaming = rename_registers(&program.instructions);
+    // addi x6, x11, 4: addi overwrote x10 right after lw read it (WAR). x5 is taken by lw, so
+    // the value moves to x6
+    assert_eq!(renaming.instructions[1].rd(), 6);
+    assert_eq!(renaming.renamed, 1);
+    assert_eq!(renaming.false_dependencies_removed, 1);
+
+    let nops: Vec<usize> = nops_per_technique_of(&program.instructions);
+    assert_eq!(nops[0], 2);
+    assert_eq!(nops[4], 0);
+}
+
+#[test]
+fn bubblesort_def_use_chains() {
+    let instructions = program("vetor_decrescente_bubblesort").instructions;
+    let data_flow = DataFlow::from_instructions(&instructions);
+
+    // The loop heads (4 and 6) and the swap (13 and 14) start basic blocks
+    let starts: Vec<usize> = data_flow.blocks.iter().map(|block| block.start).collect();
+    assert_eq!(starts, vec![0, 4, 5, 6, 7, 13, 15, 17, 19]);
+    // addi x6, x0, 0 and the increment of the inner loop both reach its three reads of x6
+    assert_eq!(data_flow.definition(5).unwrap().uses, vec![6, 7, 15]);
+    assert_eq!(data_flow.definition(15).unwrap().uses, vec![6, 7, 15]);
+    // x28 is read by the load and by the swap store
+    assert_eq!(data_flow.definition(9).unwrap().uses, vec![11, 14]);
+    assert!(contains(data_flow.liveness.live_in[4], 5));
+
+    // Only the a0 the final ecall returns is never read
+    let unused: Vec<usize> = data_flow
+        .unused_writes()
+        .map(|definition| definition.index)
+        .collect();
+    assert_eq!(unused, vec![20]);
+}
+
+#[test]
+fn unrolling_keeps_branches_on_their_targets() {
+    let instructions = program("vetor_decrescente_bubblesort").instructions;
+
+    // The outer loop holds the inner one, which branches over the swap
+    assert_eq!(
+        counted_loops(&instructions),
+        vec![CountedLoop {
+            head: 6,
+            latch: 16,
+            induction: 6,
+            step: 1,
+        }]
+    );
+
+    let unrolling = unroll_loops(&instructions, 2);
+    let targets: Vec<(usize, i64)> = unrolling
+        .instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, inst)| jump_target(index, inst).map(|target| (index, target)))
+        .collect();
+    assert_eq!(
+        targets,
+        vec![
+            // Outer exit test, moved down by the 10 new instructions
+            (4, 29),
+            // Each copy exits to addi x5, x5, 1 and branches over its own swap
+            (6, 27),
+            (12, 15),
+            (16, 27),
+            (22, 25),
+            // Back to the first test, and the outer loop
+            (26, 6),
+            (28, 4),
+        ]
+    );
+}
+
+#[test]
+fn single_issue_matches_the_pipeline_diagram() {
+    let names = [
+        "fatorial",
+        "laco5x",
+        "raw_hazard",
+        "test1_1",
+        "vetor_decrescente_bubblesort",
+        "war_hazard",
+        "waw_hazard",
+    ];
+
+    for name in names {
+        let instructions = program(name).instructions;
+        for forwarding in [false, true] {
+            let rules = IssueRules {
+                width: 1,
+                forwarding,
+                ..IssueRules::default()
+            };
+            let (issue_cycles, _) = schedule(&instructions, &rules);
+            let diagram = PipelineDiagram::from_instructions("original", &instructions, forwarding);
+
+            // Issue is the EX cycle, two after IF; the diagram counts cycles from IF of the first
+            assert_eq!(
+                issue_cycles.last().map(|cycle| cycle + 5),
+                Some(diagram.total_cycles),
+                "{} forwarding {}",
+                name,
+                forwarding
+            );
+        }
+    }
+}
+
+#[test]
+fn dual_issue_pairing_rules() {
+    let program = Program::from_text(
+        "pairing",
+        // lw x5, 0(x10); lw x6, 4(x10); add x7, x5, x6; addi x8, x0, 1;
+        // beq x7, x8, 8; addi x9, x0, 2
+        "00000000000001010010001010000011
+         00000000010001010010001100000011
+         00000000011000101000001110110011
+         00000000000100000000010000010011
+         00000000100000111000010001100011
+         00000000001000000000010010010011",
+    )
+    .unwrap();
+
+    let (issue_cycles, log) = schedule(&program.instructions, &IssueRules::default());
+    assert_eq!(issue_cycles, vec![0, 1, 3, 3, 4, 5]);
+
+    let reasons: Vec<Option<UnusedSlotReason>> = log.iter().map(|cycle| cycle.reason).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            // One memory port
+            Some(UnusedSlotReason::MemoryPort),
+            // add reads the second load, issued in the same cycle, then waits for it
+            Some(UnusedSlotReason::GroupDependency),
+            Some(UnusedSlotReason::OperandNotReady),
+            None,
+            // Nothing pairs after beq
+            Some(UnusedSlotReason::ControlTransfer),
+            Some(UnusedSlotReason::ProgramEnd),
+        ]
+    );
+}
+
+#[test]
+fn caches_follow_the_executed_trace() {
+    let program = program("fatorial");
+    let execution = executor::execute(&program.instructions, executor::MAX_STEPS);
+    assert_eq!(execution.stop, StopReason::EndOfProgram);
+    // 5! ends up in a1
+    assert_eq!(execution.registers[11], 120);
+    assert_eq!(execution.executed.len(), 63);
+
+    // Two-way set, blocks 0 and 2 and 4 share it: 0, 2, 0, 4, 0
+    let config = |replacement, write_policy| CacheConfig {
+        size: 32,
+        block_size: 8,
+        associativity: 2,
+        replacement,
+        write_policy,
+        miss_penalty: 10,
+    };
+    let hits = |replacement| {
+        let mut cache = Cache::new(config(replacement, WritePolicy::WriteBack));
+        [0, 16, 0, 32, 0]
+            .iter()
+            .filter(|address| cache.access(**address, false))
+            .count()
+    };
+    // LRU evicts 16 for 32, FIFO evicts 0
+    assert_eq!(hits(Replacement::Lru), 2);
+    assert_eq!(hits(Replacement::Fifo), 1);
+
+    // Store misses allocate only on write-back, which writes the dirty block when it is evicted
+    let mut write_back = Cache::new(config(Replacement::Lru, WritePolicy::WriteBack));
+    let mut write_through = Cache::new(config(Replacement::Lru, WritePolicy::WriteThrough));
+    for (address, write) in [(0, true), (0, false), (16, false), (32, false)] {
+        write_back.access(address, write);
+        write_through.access(address, write);
+    }
+    let (write_back, write_through) = (write_back.stats(), write_through.stats());
+    assert_eq!((write_back.hits, write_back.write_backs), (1, 1));
+    assert_eq!((write_through.hits, write_through.memory_writes), (0, 1));
+    assert_eq!(write_through.stall_cycles, 40);
+    assert_eq!(write_through.amat, 11.0);
+}
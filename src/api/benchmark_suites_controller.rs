@@ -0,0 +1,119 @@
+use actix_web::{HttpResponse, Result, web};
+
+use crate::{
+    config::db::Pool,
+    models::{
+        benchmark_suite::{BenchmarkSuite, BenchmarkSuiteDTO},
+        response::ResponseBody,
+    },
+    services::benchmark_suites_service,
+    utils::{constants, error::FieldError},
+};
+
+// GET api/benchmark_suites/{id}
+#[utoipa::path(
+    get,
+    path = "/api/benchmark_suites/{id}",
+    tag = "benchmark_suites",
+    params(("id" = String, Path, description = "Benchmark suite id")),
+    responses(
+        (status = 200, description = "The benchmark suite", body = ResponseBody<BenchmarkSuite>),
+        (status = 404, description = "Benchmark suite not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
+    match benchmark_suites_service::find_by_id(id.into_inner(), &pool).await {
+        Ok(benchmark_suite) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, benchmark_suite)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/benchmark_suites
+#[utoipa::path(
+    get,
+    path = "/api/benchmark_suites",
+    tag = "benchmark_suites",
+    responses(
+        (status = 200, description = "Every benchmark suite", body = ResponseBody<Vec<BenchmarkSuite>>),
+    )
+)]
+pub async fn find_all(pool: web::Data<Pool>) -> Result<HttpResponse> {
+    match benchmark_suites_service::find_all(&pool).await {
+        Ok(benchmark_suites) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, benchmark_suites)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/benchmark_suites
+#[utoipa::path(
+    post,
+    path = "/api/benchmark_suites",
+    tag = "benchmark_suites",
+    request_body = BenchmarkSuiteDTO,
+    responses(
+        (status = 200, description = "Benchmark suite created", body = ResponseBody<String>),
+        (status = 409, description = "Benchmark suite already exists", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn insert(
+    benchmark_suite_dto: web::Json<BenchmarkSuiteDTO>,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse> {
+    match benchmark_suites_service::insert(benchmark_suite_dto.0, &pool).await {
+        Ok(()) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, constants::EMPTY)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// PUT api/benchmark_suites/{id}
+#[utoipa::path(
+    put,
+    path = "/api/benchmark_suites/{id}",
+    tag = "benchmark_suites",
+    params(("id" = String, Path, description = "Benchmark suite id")),
+    request_body = BenchmarkSuiteDTO,
+    responses(
+        (status = 200, description = "Benchmark suite replaced", body = ResponseBody<String>),
+        (status = 404, description = "Benchmark suite not found", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn update(
+    id: web::Path<String>,
+    benchmark_suite_dto: web::Json<BenchmarkSuiteDTO>,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse> {
+    match benchmark_suites_service::update(id.into_inner(), benchmark_suite_dto.0, &pool).await {
+        Ok(()) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, constants::EMPTY)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// DELETE api/benchmark_suites/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/benchmark_suites/{id}",
+    tag = "benchmark_suites",
+    params(("id" = String, Path, description = "Benchmark suite id")),
+    responses(
+        (status = 200, description = "Benchmark suite deleted", body = ResponseBody<String>),
+        (status = 404, description = "Benchmark suite not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn delete(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
+    match benchmark_suites_service::delete(id.into_inner(), &pool).await {
+        Ok(()) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, constants::EMPTY)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
@@ -1,13 +1,52 @@
+use actix_multipart::Multipart;
 use actix_web::{
     HttpResponse,
-    Result, web::{self, Bytes},
+    Result, web::{self, Bytes, BytesMut},
 };
+use futures_util::TryStreamExt;
+use riscv_engine::{
+    performance_calculator::{data_hazard::InstructionHazardReport, liveness::LivenessReport},
+    pipeline_diagram::DiagramFormat,
+};
+use utoipa::ToSchema;
 
 use crate::{
-    config::db::Pool, models::response::ResponseBody, services::bin_files_service, utils::constants,
+    config::db::Pool,
+    models::{
+        bin_file::{BinFile, BinFileMetadata, PipelineDiagramQuery},
+        response::ResponseBody,
+    },
+    services::bin_files_service,
+    utils::{
+        constants,
+        error::{FieldError, ServiceError},
+    },
 };
 
+// Uploads bigger than this are refused, the biggest sample dumps have a few KB
+const MAX_UPLOAD_SIZE: usize = 10 * 1024 * 1024;
+
+// Form read by upload, only used to describe it in the OpenAPI document
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct BinFileUpload {
+    // Defaults to the filename without its extension
+    id: Option<String>,
+    #[schema(format = Binary, value_type = String)]
+    file: Vec<u8>,
+}
+
 // GET api/bin_files/{id}
+#[utoipa::path(
+    get,
+    path = "/api/bin_files/{id}",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id")),
+    responses(
+        (status = 200, description = "The bin file and its metadata", body = ResponseBody<BinFile>),
+        (status = 404, description = "Bin file not found", body = ResponseBody<String>),
+    )
+)]
 pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
     match bin_files_service::find_by_id(id.into_inner(), &pool).await {
         Ok(bin_file) => {
@@ -17,7 +56,89 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Pool>) -> Result<
     }
 }
 
+// GET api/bin_files/{id}/hazards
+#[utoipa::path(
+    get,
+    path = "/api/bin_files/{id}/hazards",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id")),
+    responses(
+        (status = 200, description = "Every dependency of each instruction", body = ResponseBody<Vec<InstructionHazardReport>>),
+        (status = 404, description = "Bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn find_hazards(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
+    match bin_files_service::find_hazards(id.into_inner(), &pool).await {
+        Ok(hazards) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, hazards)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/bin_files/{id}/liveness
+#[utoipa::path(
+    get,
+    path = "/api/bin_files/{id}/liveness",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id")),
+    responses(
+        (status = 200, description = "Live registers per instruction and basic block, def-use chains and unused writes", body = ResponseBody<LivenessReport>),
+        (status = 404, description = "Bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn liveness(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
+    match bin_files_service::liveness(id.into_inner(), &pool).await {
+        Ok(liveness) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, liveness)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/bin_files/{id}/pipeline_diagram
+#[utoipa::path(
+    get,
+    path = "/api/bin_files/{id}/pipeline_diagram",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id"), PipelineDiagramQuery),
+    responses(
+        (status = 200, description = "IF/ID/EX/MEM/WB cycle diagram with stalls, bubbles and forwarding", content(
+            (String = "text/plain"),
+            (String = "image/svg+xml"),
+            (String = "text/html"),
+        )),
+        (status = 404, description = "Bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Unknown technique", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn pipeline_diagram(
+    id: web::Path<String>,
+    query: web::Query<PipelineDiagramQuery>,
+    pool: web::Data<Pool>,
+) -> Result<HttpResponse> {
+    let query = query.into_inner();
+    let content_type = match query.format.unwrap_or_default() {
+        DiagramFormat::Text => "text/plain; charset=utf-8",
+        DiagramFormat::Svg => "image/svg+xml",
+        DiagramFormat::Html => "text/html; charset=utf-8",
+    };
+
+    match bin_files_service::pipeline_diagram(id.into_inner(), query, &pool).await {
+        Ok(diagram) => Ok(HttpResponse::Ok().content_type(content_type).body(diagram)),
+        Err(err) => Ok(err.response()),
+    }
+}
+
 // GET api/bin_files
+#[utoipa::path(
+    get,
+    path = "/api/bin_files",
+    tag = "bin_files",
+    responses(
+        (status = 200, description = "Every bin file", body = ResponseBody<Vec<BinFile>>),
+    )
+)]
 pub async fn find_all(pool: web::Data<Pool>) -> Result<HttpResponse> {
     match bin_files_service::find_all(&pool).await {
         Ok(bin_files) => {
@@ -28,34 +149,103 @@ pub async fn find_all(pool: web::Data<Pool>) -> Result<HttpResponse> {
 }
 
 // POST api/bin_files/{id}
+#[utoipa::path(
+    post,
+    path = "/api/bin_files/{id}",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id")),
+    request_body(
+        content = String,
+        content_type = "text/plain",
+        description = "One instruction per line, in binary or hexadecimal text"
+    ),
+    responses(
+        (status = 201, description = "Bin file created", body = ResponseBody<BinFileMetadata>),
+        (status = 409, description = "Bin file already exists", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
 pub async fn insert(
     id: web::Path<String>,
     bin_file_data: Bytes,
     pool: web::Data<Pool>,
 ) -> Result<HttpResponse> {
-    match bin_files_service::insert(id.to_string(), bin_file_data, &pool).await {
-        Ok(()) => {
-            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, constants::EMPTY)))
+    match bin_files_service::insert(id.into_inner(), None, bin_file_data, &pool).await {
+        Ok(metadata) => {
+            Ok(HttpResponse::Created().json(ResponseBody::new(constants::MESSAGE_OK, metadata)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/bin_files (multipart/form-data with a "file" part and an optional "id" part)
+#[utoipa::path(
+    post,
+    path = "/api/bin_files",
+    tag = "bin_files",
+    request_body(content = BinFileUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Bin file created", body = ResponseBody<BinFileMetadata>),
+        (status = 409, description = "Bin file already exists", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn upload(payload: Multipart, pool: web::Data<Pool>) -> Result<HttpResponse> {
+    let (id, original_filename, bin_file_data) = match read_upload(payload).await {
+        Ok(upload) => upload,
+        Err(err) => return Ok(err.response()),
+    };
+
+    match bin_files_service::insert(id, original_filename, bin_file_data, &pool).await {
+        Ok(metadata) => {
+            Ok(HttpResponse::Created().json(ResponseBody::new(constants::MESSAGE_OK, metadata)))
         }
         Err(err) => Ok(err.response()),
     }
 }
 
 // PUT api/bin_files/{id}
+#[utoipa::path(
+    put,
+    path = "/api/bin_files/{id}",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id")),
+    request_body(
+        content = String,
+        content_type = "text/plain",
+        description = "One instruction per line, in binary or hexadecimal text"
+    ),
+    responses(
+        (status = 200, description = "Bin file replaced", body = ResponseBody<BinFileMetadata>),
+        (status = 404, description = "Bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
 pub async fn update(
     id: web::Path<String>,
     bin_file_data: Bytes,
     pool: web::Data<Pool>,
 ) -> Result<HttpResponse> {
-    match bin_files_service::update(id.to_string(), bin_file_data, &pool).await {
-        Ok(()) => {
-            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, constants::EMPTY)))
+    match bin_files_service::update(id.into_inner(), bin_file_data, &pool).await {
+        Ok(metadata) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, metadata)))
         }
         Err(err) => Ok(err.response()),
     }
 }
 
 // DELETE api/bin_files/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/bin_files/{id}",
+    tag = "bin_files",
+    params(("id" = String, Path, description = "Bin file id")),
+    responses(
+        (status = 200, description = "Bin file deleted", body = ResponseBody<String>),
+        (status = 404, description = "Bin file not found", body = ResponseBody<String>),
+        (status = 409, description = "Bin file is used by a benchmark suite", body = ResponseBody<String>),
+    )
+)]
 pub async fn delete(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
     match bin_files_service::delete(id.into_inner(), &pool).await {
         Ok(()) => {
@@ -64,3 +254,66 @@ pub async fn delete(id: web::Path<String>, pool: web::Data<Pool>) -> Result<Http
         Err(err) => Ok(err.response()),
     }
 }
+
+// Returns the bin file id, the uploaded filename and the file content.
+// Without an "id" part, the id is the filename without its extension.
+async fn read_upload(
+    mut payload: Multipart,
+) -> Result<(String, Option<String>, Bytes), ServiceError> {
+    let mut id: Option<String> = None;
+    let mut original_filename: Option<String> = None;
+    let mut bin_file_data: Option<Bytes> = None;
+
+    let invalid_form =
+        |message: &str| ServiceError::validation(vec![FieldError::new("form", message)]);
+
+    while let Some(mut field) = payload
+        .try_next()
+        .await
+        .map_err(|err| invalid_form(&err.to_string()))?
+    {
+        let name = field.name().unwrap_or_default().to_string();
+        let filename = field
+            .content_disposition()
+            .and_then(|content_disposition| content_disposition.get_filename())
+            .map(|filename| filename.to_string());
+
+        let mut data = BytesMut::new();
+        while let Some(chunk) = field
+            .try_next()
+            .await
+            .map_err(|err| invalid_form(&err.to_string()))?
+        {
+            if data.len() + chunk.len() > MAX_UPLOAD_SIZE {
+                return Err(invalid_form("upload is too large"));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        match name.as_str() {
+            "id" => id = Some(String::from_utf8_lossy(&data).trim().to_string()),
+            "file" => {
+                original_filename = filename;
+                bin_file_data = Some(data.freeze());
+            }
+            _ => (),
+        }
+    }
+
+    let bin_file_data = bin_file_data.ok_or_else(|| {
+        ServiceError::validation(vec![FieldError::new("file", "must be present")])
+    })?;
+
+    let id = id
+        .or_else(|| {
+            original_filename
+                .as_ref()
+                .map(|filename| match filename.rsplit_once('.') {
+                    Some((stem, _)) => stem.to_string(),
+                    None => filename.clone(),
+                })
+        })
+        .ok_or_else(|| ServiceError::validation(vec![FieldError::new("id", "must be present")]))?;
+
+    Ok((id, original_filename, bin_file_data))
+}
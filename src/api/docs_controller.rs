@@ -0,0 +1,36 @@
+use actix_web::{HttpResponse, Result};
+use utoipa::OpenApi;
+
+use crate::config::openapi::ApiDoc;
+
+// Swagger UI assets come from a CDN, so the page needs internet access but the build doesn't
+const SWAGGER_UI_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8" />
+    <title>RISC-V Performance Checker API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+    <script>
+        window.onload = () => {
+            window.ui = SwaggerUIBundle({ url: "/api/openapi.json", dom_id: "#swagger-ui" });
+        };
+    </script>
+</body>
+</html>
+"##;
+
+// GET api/openapi.json
+pub async fn openapi() -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok().json(ApiDoc::openapi()))
+}
+
+// GET api/docs
+pub async fn swagger_ui() -> Result<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(SWAGGER_UI_PAGE))
+}
@@ -1,3 +1,5 @@
+pub mod benchmark_suites_controller;
 pub mod bin_files_controller;
+pub mod docs_controller;
 pub mod organizations_controller;
 pub mod performance_calculator_controller;
@@ -2,12 +2,25 @@ use actix_web::{HttpResponse, Result, web};
 
 use crate::{
     config::db::Pool,
-    models::{organization::OrganizationDTO, response::ResponseBody},
+    models::{
+        organization::{Organization, OrganizationDTO},
+        response::ResponseBody,
+    },
     services::organizations_service,
-    utils::constants,
+    utils::{constants, error::FieldError},
 };
 
 // GET api/organization/{id}
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{id}",
+    tag = "organizations",
+    params(("id" = String, Path, description = "Organization id")),
+    responses(
+        (status = 200, description = "The organization", body = ResponseBody<Organization>),
+        (status = 404, description = "Organization not found", body = ResponseBody<String>),
+    )
+)]
 pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
     match organizations_service::find_by_id(id.into_inner(), &pool).await {
         Ok(organization) => {
@@ -18,6 +31,14 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Pool>) -> Result<
 }
 
 // GET api/organization
+#[utoipa::path(
+    get,
+    path = "/api/organizations",
+    tag = "organizations",
+    responses(
+        (status = 200, description = "Every organization", body = ResponseBody<Vec<Organization>>),
+    )
+)]
 pub async fn find_all(pool: web::Data<Pool>) -> Result<HttpResponse> {
     match organizations_service::find_all(&pool).await {
         Ok(organizations) => {
@@ -28,6 +49,17 @@ pub async fn find_all(pool: web::Data<Pool>) -> Result<HttpResponse> {
 }
 
 // POST api/organization
+#[utoipa::path(
+    post,
+    path = "/api/organizations",
+    tag = "organizations",
+    request_body = OrganizationDTO,
+    responses(
+        (status = 200, description = "Organization created", body = ResponseBody<String>),
+        (status = 409, description = "Organization already exists", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
 pub async fn insert(
     organization_dto: web::Json<OrganizationDTO>,
     pool: web::Data<Pool>,
@@ -41,6 +73,18 @@ pub async fn insert(
 }
 
 // PUT api/organization/{id}
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{id}",
+    tag = "organizations",
+    params(("id" = String, Path, description = "Organization id")),
+    request_body = OrganizationDTO,
+    responses(
+        (status = 200, description = "Organization replaced", body = ResponseBody<String>),
+        (status = 404, description = "Organization not found", body = ResponseBody<String>),
+        (status = 422, description = "Invalid fields", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
 pub async fn update(
     id: web::Path<String>,
     organization_dto: web::Json<OrganizationDTO>,
@@ -55,6 +99,16 @@ pub async fn update(
 }
 
 // DELETE api/organization/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{id}",
+    tag = "organizations",
+    params(("id" = String, Path, description = "Organization id")),
+    responses(
+        (status = 200, description = "Organization deleted", body = ResponseBody<String>),
+        (status = 404, description = "Organization not found", body = ResponseBody<String>),
+    )
+)]
 pub async fn delete(id: web::Path<String>, pool: web::Data<Pool>) -> Result<HttpResponse> {
     match organizations_service::delete(id.into_inner(), &pool).await {
         Ok(()) => {
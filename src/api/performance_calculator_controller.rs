@@ -1,22 +1,62 @@
 use actix_web::{HttpResponse, Result, web};
+use riscv_engine::{
+    performance_calculator::{
+        design_space::SweepCalculator,
+        loop_unrolling::LoopUnrollingCalculator,
+        memory_hierarchy::CacheCalculator,
+        monocycle_calculator::PerformanceCalculator as MonocyclePerformance,
+        pipeline_calculator::PerformanceCalculator as PipelinePerformance,
+        suite_calculator::{MonocycleSuiteCalculator, PipelineSuiteCalculator},
+        superscalar::SuperscalarCalculator,
+        tomasulo::TomasuloCalculator,
+    },
+    report::ReportFormat,
+};
 
 use crate::{
-    config::db::Pool,
-    models::response::ResponseBody,
-    performance_calculator::{
-        monocycle_calculator::PerformanceCalculatorDTO,
-        pipeline_calculator::PerformanceCalculatorPipelineDTO,
+    config::{cache::AnalysisCache, db::Pool},
+    models::{
+        performance_calculator::{
+            PerformanceCalculatorCacheDTO, PerformanceCalculatorDTO,
+            PerformanceCalculatorLoopUnrollingDTO, PerformanceCalculatorPipelineDTO,
+            PerformanceCalculatorPipelineSuiteDTO, PerformanceCalculatorSuiteDTO,
+            PerformanceCalculatorSuperscalarDTO, PerformanceCalculatorSweepDTO,
+            PerformanceCalculatorTomasuloDTO, ReportQuery,
+        },
+        response::ResponseBody,
     },
     services::performance_calculator_service,
-    utils::constants,
+    utils::{constants, error::FieldError, locale::RequestLocale},
 };
 
-// GET api/performance_calculator/calc_monocycle
-pub async fn calc_monocycle(
+// Every calculator answers both POST with a JSON body and GET with the same fields as query parameters
+
+// POST api/performance_calculator/calc_monocycle
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_monocycle",
+    tag = "performance_calculator",
+    description = "Compares two monocycle organizations on a bin file",
+    request_body = PerformanceCalculatorDTO,
+    params(RequestLocale),
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<MonocyclePerformance>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_monocycle_json(
     performance_calculator_dto: web::Json<PerformanceCalculatorDTO>,
+    locale: RequestLocale,
     pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
 ) -> Result<HttpResponse> {
-    match performance_calculator_service::calc_monocycle(performance_calculator_dto.0, &pool).await
+    match performance_calculator_service::calc_monocycle(
+        performance_calculator_dto.into_inner(),
+        locale.0,
+        &pool,
+        &cache,
+    )
+    .await
     {
         Ok(performance) => {
             Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
@@ -25,14 +65,477 @@ pub async fn calc_monocycle(
     }
 }
 
-// GET api/performance_calculator/calc_pipeline
-pub async fn calc_pipeline(
+// GET api/performance_calculator/calc_monocycle
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_monocycle",
+    tag = "performance_calculator",
+    description = "Compares two monocycle organizations on a bin file",
+    params(PerformanceCalculatorDTO, RequestLocale),
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<MonocyclePerformance>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_monocycle_query(
+    performance_calculator_dto: web::Query<PerformanceCalculatorDTO>,
+    locale: RequestLocale,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_monocycle(
+        performance_calculator_dto.into_inner(),
+        locale.0,
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_pipeline
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_pipeline",
+    tag = "performance_calculator",
+    description = "Compares the pipeline conflict techniques of an organization on a bin file",
+    request_body = PerformanceCalculatorPipelineDTO,
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<PipelinePerformance>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_pipeline_json(
     performance_calculator_pipeline_dto: web::Json<PerformanceCalculatorPipelineDTO>,
     pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
 ) -> Result<HttpResponse> {
     match performance_calculator_service::calc_pipeline(
-        performance_calculator_pipeline_dto.0,
+        performance_calculator_pipeline_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/calc_pipeline
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_pipeline",
+    tag = "performance_calculator",
+    description = "Compares the pipeline conflict techniques of an organization on a bin file",
+    params(PerformanceCalculatorPipelineDTO),
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<PipelinePerformance>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_pipeline_query(
+    performance_calculator_pipeline_dto: web::Query<PerformanceCalculatorPipelineDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_pipeline(
+        performance_calculator_pipeline_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_loop_unrolling
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_loop_unrolling",
+    tag = "performance_calculator",
+    description = "Compares the cycles per iteration of the counted loops of a bin file with each pipeline technique, before and after unrolling them",
+    request_body = PerformanceCalculatorLoopUnrollingDTO,
+    responses(
+        (status = 200, description = "The cycles per iteration of each loop", body = ResponseBody<LoopUnrollingCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Unroll factor out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_loop_unrolling_json(
+    performance_calculator_loop_unrolling_dto: web::Json<PerformanceCalculatorLoopUnrollingDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_loop_unrolling(
+        performance_calculator_loop_unrolling_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/calc_loop_unrolling
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_loop_unrolling",
+    tag = "performance_calculator",
+    description = "Compares the cycles per iteration of the counted loops of a bin file with each pipeline technique, before and after unrolling them",
+    params(PerformanceCalculatorLoopUnrollingDTO),
+    responses(
+        (status = 200, description = "The cycles per iteration of each loop", body = ResponseBody<LoopUnrollingCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Unroll factor out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_loop_unrolling_query(
+    performance_calculator_loop_unrolling_dto: web::Query<PerformanceCalculatorLoopUnrollingDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_loop_unrolling(
+        performance_calculator_loop_unrolling_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_superscalar
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_superscalar",
+    tag = "performance_calculator",
+    description = "Runs a bin file on an N-wide in-order superscalar pipeline: issue log, IPC and why issue slots went unused",
+    request_body = PerformanceCalculatorSuperscalarDTO,
+    responses(
+        (status = 200, description = "The issue log and IPC", body = ResponseBody<SuperscalarCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Width, memory ports or branch units out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_superscalar_json(
+    performance_calculator_superscalar_dto: web::Json<PerformanceCalculatorSuperscalarDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_superscalar(
+        performance_calculator_superscalar_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/calc_superscalar
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_superscalar",
+    tag = "performance_calculator",
+    description = "Runs a bin file on an N-wide in-order superscalar pipeline: issue log, IPC and why issue slots went unused",
+    params(PerformanceCalculatorSuperscalarDTO),
+    responses(
+        (status = 200, description = "The issue log and IPC", body = ResponseBody<SuperscalarCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Width, memory ports or branch units out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_superscalar_query(
+    performance_calculator_superscalar_dto: web::Query<PerformanceCalculatorSuperscalarDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_superscalar(
+        performance_calculator_superscalar_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_tomasulo
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_tomasulo",
+    tag = "performance_calculator",
+    description = "Runs a bin file on an out-of-order Tomasulo pipeline with a reorder buffer and compares it with the in-order pipeline techniques",
+    request_body = PerformanceCalculatorTomasuloDTO,
+    responses(
+        (status = 200, description = "Issue, execute, write result and commit cycles of each instruction", body = ResponseBody<TomasuloCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Reservation stations, functional units, ROB size or CDB width out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_tomasulo_json(
+    performance_calculator_tomasulo_dto: web::Json<PerformanceCalculatorTomasuloDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_tomasulo(
+        performance_calculator_tomasulo_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/calc_tomasulo
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_tomasulo",
+    tag = "performance_calculator",
+    description = "Runs a bin file on an out-of-order Tomasulo pipeline with a reorder buffer and compares it with the in-order pipeline techniques",
+    params(PerformanceCalculatorTomasuloDTO),
+    responses(
+        (status = 200, description = "Issue, execute, write result and commit cycles of each instruction", body = ResponseBody<TomasuloCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Reservation stations, functional units, ROB size or CDB width out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_tomasulo_query(
+    performance_calculator_tomasulo_dto: web::Query<PerformanceCalculatorTomasuloDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_tomasulo(
+        performance_calculator_tomasulo_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_cache
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_cache",
+    tag = "performance_calculator",
+    description = "Runs a bin file through L1 instruction and data caches and folds the miss stalls into its CPI and execution time",
+    request_body = PerformanceCalculatorCacheDTO,
+    responses(
+        (status = 200, description = "Hit rates, AMAT and stall cycles of both caches", body = ResponseBody<CacheCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Cache geometry or miss penalty out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_cache_json(
+    performance_calculator_cache_dto: web::Json<PerformanceCalculatorCacheDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_cache(
+        performance_calculator_cache_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/calc_cache
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_cache",
+    tag = "performance_calculator",
+    description = "Runs a bin file through L1 instruction and data caches and folds the miss stalls into its CPI and execution time",
+    params(PerformanceCalculatorCacheDTO),
+    responses(
+        (status = 200, description = "Hit rates, AMAT and stall cycles of both caches", body = ResponseBody<CacheCalculator>),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+        (status = 422, description = "Cache geometry or miss penalty out of range", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_cache_query(
+    performance_calculator_cache_dto: web::Query<PerformanceCalculatorCacheDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_cache(
+        performance_calculator_cache_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_sweep
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_sweep",
+    tag = "performance_calculator",
+    description = "Evaluates every combination of organization parameter values on a bin file or a benchmark suite and returns the Pareto front of execution time against a cost metric",
+    request_body = PerformanceCalculatorSweepDTO,
+    responses(
+        (status = 200, description = "Execution time and energy of every design point, and the Pareto front", body = ResponseBody<SweepCalculator>),
+        (status = 404, description = "Organization, bin file or benchmark suite not found", body = ResponseBody<String>),
+        (status = 422, description = "Unknown parameter, value out of range, too many points, or not exactly one of bin_file_name and suite_name", body = ResponseBody<Vec<FieldError>>),
+    )
+)]
+pub async fn calc_sweep(
+    performance_calculator_sweep_dto: web::Json<PerformanceCalculatorSweepDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_sweep(
+        performance_calculator_sweep_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_monocycle_suite
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_monocycle_suite",
+    tag = "performance_calculator",
+    description = "Compares two monocycle organizations on every bin file of a benchmark suite",
+    request_body = PerformanceCalculatorSuiteDTO,
+    params(RequestLocale),
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<MonocycleSuiteCalculator>),
+        (status = 404, description = "Organization, benchmark suite or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_monocycle_suite_json(
+    performance_calculator_suite_dto: web::Json<PerformanceCalculatorSuiteDTO>,
+    locale: RequestLocale,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_monocycle_suite(
+        performance_calculator_suite_dto.into_inner(),
+        locale.0,
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/calc_monocycle_suite
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_monocycle_suite",
+    tag = "performance_calculator",
+    description = "Compares two monocycle organizations on every bin file of a benchmark suite",
+    params(PerformanceCalculatorSuiteDTO, RequestLocale),
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<MonocycleSuiteCalculator>),
+        (status = 404, description = "Organization, benchmark suite or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_monocycle_suite_query(
+    performance_calculator_suite_dto: web::Query<PerformanceCalculatorSuiteDTO>,
+    locale: RequestLocale,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_monocycle_suite(
+        performance_calculator_suite_dto.into_inner(),
+        locale.0,
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// POST api/performance_calculator/calc_pipeline_suite
+#[utoipa::path(
+    post,
+    path = "/api/performance_calculator/calc_pipeline_suite",
+    tag = "performance_calculator",
+    description = "Compares the pipeline conflict techniques of an organization on every bin file of a benchmark suite",
+    request_body = PerformanceCalculatorPipelineSuiteDTO,
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<PipelineSuiteCalculator>),
+        (status = 404, description = "Organization, benchmark suite or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_pipeline_suite_json(
+    performance_calculator_pipeline_suite_dto: web::Json<PerformanceCalculatorPipelineSuiteDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_pipeline_suite(
+        performance_calculator_pipeline_suite_dto.into_inner(),
         &pool,
+        &cache,
     )
     .await
     {
@@ -42,3 +545,115 @@ pub async fn calc_pipeline(
         Err(err) => Ok(err.response()),
     }
 }
+
+// GET api/performance_calculator/calc_pipeline_suite
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/calc_pipeline_suite",
+    tag = "performance_calculator",
+    description = "Compares the pipeline conflict techniques of an organization on every bin file of a benchmark suite",
+    params(PerformanceCalculatorPipelineSuiteDTO),
+    responses(
+        (status = 200, description = "The performance comparison", body = ResponseBody<PipelineSuiteCalculator>),
+        (status = 404, description = "Organization, benchmark suite or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn calc_pipeline_suite_query(
+    performance_calculator_pipeline_suite_dto: web::Query<PerformanceCalculatorPipelineSuiteDTO>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    match performance_calculator_service::calc_pipeline_suite(
+        performance_calculator_pipeline_suite_dto.into_inner(),
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(performance) => {
+            Ok(HttpResponse::Ok().json(ResponseBody::new(constants::MESSAGE_OK, performance)))
+        }
+        Err(err) => Ok(err.response()),
+    }
+}
+
+fn report_content_type(report_query: &ReportQuery) -> &'static str {
+    match report_query.format.unwrap_or(ReportFormat::Markdown) {
+        ReportFormat::Markdown => "text/markdown; charset=utf-8",
+        ReportFormat::Html => "text/html; charset=utf-8",
+    }
+}
+
+// GET api/performance_calculator/report_monocycle
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/report_monocycle",
+    tag = "performance_calculator",
+    description = "Written analysis of calc_monocycle: inputs, instruction mix, results and speedup",
+    params(PerformanceCalculatorDTO, ReportQuery),
+    responses(
+        (status = 200, description = "The report", content(
+            (String = "text/markdown"),
+            (String = "text/html"),
+        )),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn report_monocycle(
+    performance_calculator_dto: web::Query<PerformanceCalculatorDTO>,
+    report_query: web::Query<ReportQuery>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    let report_query = report_query.into_inner();
+    let content_type = report_content_type(&report_query);
+
+    match performance_calculator_service::report_monocycle(
+        performance_calculator_dto.into_inner(),
+        report_query,
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(report) => Ok(HttpResponse::Ok().content_type(content_type).body(report)),
+        Err(err) => Ok(err.response()),
+    }
+}
+
+// GET api/performance_calculator/report_pipeline
+#[utoipa::path(
+    get,
+    path = "/api/performance_calculator/report_pipeline",
+    tag = "performance_calculator",
+    description = "Written analysis of calc_pipeline: inputs, instruction mix, hazards, technique results, speedup and transformed listings",
+    params(PerformanceCalculatorPipelineDTO, ReportQuery),
+    responses(
+        (status = 200, description = "The report", content(
+            (String = "text/markdown"),
+            (String = "text/html"),
+        )),
+        (status = 404, description = "Organization or bin file not found", body = ResponseBody<String>),
+    )
+)]
+pub async fn report_pipeline(
+    performance_calculator_pipeline_dto: web::Query<PerformanceCalculatorPipelineDTO>,
+    report_query: web::Query<ReportQuery>,
+    pool: web::Data<Pool>,
+    cache: web::Data<AnalysisCache>,
+) -> Result<HttpResponse> {
+    let report_query = report_query.into_inner();
+    let content_type = report_content_type(&report_query);
+
+    match performance_calculator_service::report_pipeline(
+        performance_calculator_pipeline_dto.into_inner(),
+        report_query,
+        &pool,
+        &cache,
+    )
+    .await
+    {
+        Ok(report) => Ok(HttpResponse::Ok().content_type(content_type).body(report)),
+        Err(err) => Ok(err.response()),
+    }
+}
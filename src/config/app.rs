@@ -24,26 +24,155 @@ pub fn config_services(cfg: &mut web::ServiceConfig) {
             )
             .service(
                 web::scope("/bin_files")
-                    .service(web::resource("").route(web::get().to(bin_files_controller::find_all)))
+                    .service(
+                        web::resource("")
+                            .route(web::get().to(bin_files_controller::find_all))
+                            .route(web::post().to(bin_files_controller::upload)),
+                    )
                     .service(
                         web::resource("/{id}")
                             .route(web::get().to(bin_files_controller::find_by_id))
-                            .route(web::put().to(bin_files_controller::insert))
+                            .route(web::post().to(bin_files_controller::insert))
                             .route(web::put().to(bin_files_controller::update))
                             .route(web::delete().to(bin_files_controller::delete)),
+                    )
+                    .service(
+                        web::resource("/{id}/hazards")
+                            .route(web::get().to(bin_files_controller::find_hazards)),
+                    )
+                    .service(
+                        web::resource("/{id}/liveness")
+                            .route(web::get().to(bin_files_controller::liveness)),
+                    )
+                    .service(
+                        web::resource("/{id}/pipeline_diagram")
+                            .route(web::get().to(bin_files_controller::pipeline_diagram)),
+                    ),
+            )
+            .service(
+                web::scope("/benchmark_suites")
+                    .service(
+                        web::resource("")
+                            .route(web::get().to(benchmark_suites_controller::find_all))
+                            .route(web::post().to(benchmark_suites_controller::insert)),
+                    )
+                    .service(
+                        web::resource("/{id}")
+                            .route(web::get().to(benchmark_suites_controller::find_by_id))
+                            .route(web::put().to(benchmark_suites_controller::update))
+                            .route(web::delete().to(benchmark_suites_controller::delete)),
                     ),
             )
             .service(
                 web::scope("/performance_calculator")
                     .service(
-                        web::resource("/calc_monocycle").route(
-                            web::get().to(performance_calculator_controller::calc_monocycle),
-                        ),
+                        web::resource("/calc_monocycle")
+                            .route(
+                                web::get()
+                                    .to(performance_calculator_controller::calc_monocycle_query),
+                            )
+                            .route(
+                                web::post()
+                                    .to(performance_calculator_controller::calc_monocycle_json),
+                            ),
                     )
                     .service(
                         web::resource("/calc_pipeline")
-                            .route(web::get().to(performance_calculator_controller::calc_pipeline)),
+                            .route(
+                                web::get()
+                                    .to(performance_calculator_controller::calc_pipeline_query),
+                            )
+                            .route(
+                                web::post()
+                                    .to(performance_calculator_controller::calc_pipeline_json),
+                            ),
+                    )
+                    .service(
+                        web::resource("/calc_loop_unrolling")
+                            .route(
+                                web::get().to(
+                                    performance_calculator_controller::calc_loop_unrolling_query,
+                                ),
+                            )
+                            .route(
+                                web::post().to(
+                                    performance_calculator_controller::calc_loop_unrolling_json,
+                                ),
+                            ),
+                    )
+                    .service(
+                        web::resource("/calc_superscalar")
+                            .route(
+                                web::get()
+                                    .to(performance_calculator_controller::calc_superscalar_query),
+                            )
+                            .route(
+                                web::post()
+                                    .to(performance_calculator_controller::calc_superscalar_json),
+                            ),
+                    )
+                    .service(
+                        web::resource("/calc_tomasulo")
+                            .route(
+                                web::get()
+                                    .to(performance_calculator_controller::calc_tomasulo_query),
+                            )
+                            .route(
+                                web::post()
+                                    .to(performance_calculator_controller::calc_tomasulo_json),
+                            ),
+                    )
+                    .service(
+                        web::resource("/calc_cache")
+                            .route(
+                                web::get().to(performance_calculator_controller::calc_cache_query),
+                            )
+                            .route(
+                                web::post().to(performance_calculator_controller::calc_cache_json),
+                            ),
+                    )
+                    .service(
+                        web::resource("/calc_sweep")
+                            .route(web::post().to(performance_calculator_controller::calc_sweep)),
+                    )
+                    .service(
+                        web::resource("/calc_monocycle_suite")
+                            .route(
+                                web::get().to(
+                                    performance_calculator_controller::calc_monocycle_suite_query,
+                                ),
+                            )
+                            .route(
+                                web::post().to(
+                                    performance_calculator_controller::calc_monocycle_suite_json,
+                                ),
+                            ),
+                    )
+                    .service(
+                        web::resource("/calc_pipeline_suite")
+                            .route(
+                                web::get().to(
+                                    performance_calculator_controller::calc_pipeline_suite_query,
+                                ),
+                            )
+                            .route(
+                                web::post().to(
+                                    performance_calculator_controller::calc_pipeline_suite_json,
+                                ),
+                            ),
+                    )
+                    .service(
+                        web::resource("/report_monocycle").route(
+                            web::get().to(performance_calculator_controller::report_monocycle),
+                        ),
+                    )
+                    .service(
+                        web::resource("/report_pipeline").route(
+                            web::get().to(performance_calculator_controller::report_pipeline),
+                        ),
                     ),
-            ),
+            )
+            .service(web::resource("/openapi.json").route(web::get().to(docs_controller::openapi)))
+            .service(web::resource("/docs").route(web::get().to(docs_controller::swagger_ui))),
     );
 }
@@ -0,0 +1,192 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use riscv_engine::{
+    organization::Organization,
+    performance_calculator::{
+        design_space::SweepCalculator,
+        loop_unrolling::LoopUnrollingCalculator,
+        memory_hierarchy::CacheCalculator,
+        monocycle_calculator,
+        pipeline_calculator::{self, TechniqueProgram},
+        suite_calculator::{MonocycleSuiteCalculator, PipelineSuiteCalculator},
+        superscalar::SuperscalarCalculator,
+        tomasulo::TomasuloCalculator,
+    },
+    riscv_core::instruction::Instruction,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+// Each cache is emptied when it reaches this many entries, stale entries are never looked up again
+const CACHE_CAPACITY: usize = 1024;
+
+// What an analysis result was computed from. Editing a bin file or an organization changes its hash,
+// so the old entries are simply not found anymore
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AnalysisKey {
+    // Bin file id, or benchmark suite name
+    pub program: String,
+    pub program_hash: String,
+    pub organization_hash: String,
+    pub technique: &'static str,
+    // Hash of the calculator settings, empty for the calculators without any
+    pub config_hash: String,
+}
+
+impl AnalysisKey {
+    pub fn new(
+        program: &str,
+        program_hash: &str,
+        organizations: &[&Organization],
+        technique: &'static str,
+    ) -> AnalysisKey {
+        AnalysisKey {
+            program: program.to_string(),
+            program_hash: program_hash.to_string(),
+            // The organizations as the calculators see them: every field, from the clock period
+            // and the CPIs to the energy map and the static power
+            organization_hash: snapshot_hash(&organizations),
+            technique,
+            config_hash: String::new(),
+        }
+    }
+
+    // The same analysis with other settings, e.g. another unroll factor or cache, is another entry
+    pub fn with_config(mut self, config: &impl Serialize) -> AnalysisKey {
+        self.config_hash = snapshot_hash(config);
+        self
+    }
+}
+
+fn snapshot_hash(value: &impl Serialize) -> String {
+    let snapshot = serde_json::to_vec(value).unwrap_or_default();
+    hex::encode(Sha256::digest(snapshot))
+}
+
+// Hash of the content hashes and weights of the programs of a suite, in order
+pub fn suite_hash(programs: &[(String, String, f32)]) -> String {
+    snapshot_hash(&programs)
+}
+
+pub struct Cache<K, V> {
+    entries: Mutex<HashMap<K, V>>,
+}
+
+impl<K, V> Default for Cache<K, V> {
+    fn default() -> Self {
+        Cache {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> {
+    // The lock is not held while computing, two identical requests may both compute the value
+    pub fn get_or_try_insert_with<E>(
+        &self,
+        key: K,
+        compute: impl FnOnce() -> Result<V, E>,
+    ) -> Result<V, E> {
+        if let Some(value) = self.entries.lock().unwrap().get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = compute()?;
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= CACHE_CAPACITY {
+            entries.clear();
+        }
+        entries.insert(key, value.clone());
+
+        Ok(value)
+    }
+}
+
+// Shared by every worker, see main
+#[derive(Default)]
+pub struct AnalysisCache {
+    // Decoded instructions by bin file content hash
+    pub programs: Cache<String, Arc<[Instruction]>>,
+    // Programs produced by the conflict techniques, by bin file content hash
+    pub techniques: Cache<String, Arc<Vec<TechniqueProgram>>>,
+    pub monocycle: Cache<AnalysisKey, monocycle_calculator::PerformanceCalculator>,
+    pub pipeline: Cache<AnalysisKey, pipeline_calculator::PerformanceCalculator>,
+    pub monocycle_suite: Cache<AnalysisKey, MonocycleSuiteCalculator>,
+    pub pipeline_suite: Cache<AnalysisKey, PipelineSuiteCalculator>,
+    pub loop_unrolling: Cache<AnalysisKey, LoopUnrollingCalculator>,
+    pub superscalar: Cache<AnalysisKey, SuperscalarCalculator>,
+    pub tomasulo: Cache<AnalysisKey, TomasuloCalculator>,
+    pub cache: Cache<AnalysisKey, CacheCalculator>,
+    pub sweep: Cache<AnalysisKey, SweepCalculator>,
+}
+
+#[cfg(test)]
+mod tests {
+    use riscv_engine::organization::OrganizationDefinition;
+
+    use super::*;
+
+    fn organization(edit: serde_json::Value) -> Organization {
+        let mut fields = serde_json::json!({
+            "id": "A",
+            "clock": 1.0,
+            "cpi_instruction_r": 1.0,
+            "cpi_instruction_i": 1.0,
+            "cpi_instruction_l": 2.0,
+            "cpi_instruction_s": 1.0,
+            "cpi_instruction_b": 1.0,
+            "cpi_instruction_u": 1.0,
+            "cpi_instruction_j": 1.0,
+        });
+        fields
+            .as_object_mut()
+            .unwrap()
+            .extend(edit.as_object().unwrap().clone());
+
+        let definition: OrganizationDefinition = serde_json::from_value(fields).unwrap();
+        Organization::from(definition)
+    }
+
+    fn key(organization: &Organization) -> AnalysisKey {
+        AnalysisKey::new("fatorial", "hash", &[organization], "pipeline")
+    }
+
+    #[test]
+    fn editing_an_organization_changes_the_key() {
+        let original = key(&organization(serde_json::json!({})));
+        assert!(original == key(&organization(serde_json::json!({}))));
+
+        for edit in [
+            serde_json::json!({ "clock": 2.0 }),
+            serde_json::json!({ "clock_unit": "mhz" }),
+            serde_json::json!({ "cpi_instruction_l": 3.0 }),
+            serde_json::json!({ "cpi_map": { "mul": 4.0 } }),
+            serde_json::json!({ "energy_map": { "r": 5.0 } }),
+            serde_json::json!({ "static_power": 0.5 }),
+        ] {
+            assert!(
+                original != key(&organization(edit.clone())),
+                "{} kept the key",
+                edit
+            );
+        }
+
+        // The same organization with another program or technique is another analysis
+        let organization = organization(serde_json::json!({}));
+        assert!(original != AnalysisKey::new("fatorial", "other", &[&organization], "pipeline"));
+        assert!(original != AnalysisKey::new("fatorial", "hash", &[&organization], "monocycle"));
+
+        // And so is the same calculator with other settings
+        let factor = |factor: usize| {
+            AnalysisKey::new("fatorial", "hash", &[&organization], "loop_unrolling")
+                .with_config(&factor)
+        };
+        assert!(factor(4) == factor(4));
+        assert!(factor(4) != factor(8));
+    }
+}
@@ -1,12 +1,15 @@
 use std::error::Error;
 
 use diesel::{
-    r2d2::{self, ConnectionManager},
+    connection::SimpleConnection,
+    r2d2::{self, ConnectionManager, CustomizeConnection},
     sqlite::Sqlite,
     SqliteConnection,
 };
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-use log::info;
+use log::{info, warn};
+
+use crate::models::bin_file::BinFile;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
@@ -14,10 +17,22 @@ pub type Connection = SqliteConnection;
 
 pub type Pool = r2d2::Pool<ConnectionManager<Connection>>;
 
+// SQLite only enforces REFERENCES constraints when asked to, once per connection
+#[derive(Debug)]
+struct ForeignKeysCustomizer;
+
+impl CustomizeConnection<Connection, r2d2::Error> for ForeignKeysCustomizer {
+    fn on_acquire(&self, conn: &mut Connection) -> Result<(), r2d2::Error> {
+        conn.batch_execute("PRAGMA foreign_keys = ON;")
+            .map_err(r2d2::Error::QueryError)
+    }
+}
+
 fn create_connection_pool(database_url: &str) -> Pool {
     let manager = ConnectionManager::<Connection>::new(database_url);
     Pool::builder()
         .test_on_check_out(true)
+        .connection_customizer(Box::new(ForeignKeysCustomizer))
         .build(manager)
         .expect("Could not build connection pool: {database_url}")
 }
@@ -40,5 +55,14 @@ pub fn migrate_and_config_db(database_url: &str) -> Pool {
 
     run_migrations(&mut conn).expect("Failed to run migrations.");
 
+    let invalid_files =
+        BinFile::backfill_metadata(&mut conn).expect("Failed to backfill bin file metadata.");
+    if !invalid_files.is_empty() {
+        warn!(
+            "Bin files with invalid instructions, left without metadata: {}",
+            invalid_files.join(", ")
+        );
+    }
+
     pool
 }
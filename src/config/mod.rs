@@ -1,2 +1,4 @@
 pub mod app;
+pub mod cache;
 pub mod db;
+pub mod openapi;
@@ -0,0 +1,122 @@
+use utoipa::OpenApi;
+
+use crate::api::*;
+
+// OpenAPI 3 description of every route, served at api/openapi.json. Schemas are collected from the DTOs
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "RISC-V Performance Checker",
+        description = "Compares the performance of RISC-V organizations on RV32I bin dumps"
+    ),
+    paths(
+        organizations_controller::find_all,
+        organizations_controller::insert,
+        organizations_controller::find_by_id,
+        organizations_controller::update,
+        organizations_controller::delete,
+        bin_files_controller::find_all,
+        bin_files_controller::upload,
+        bin_files_controller::find_by_id,
+        bin_files_controller::insert,
+        bin_files_controller::update,
+        bin_files_controller::delete,
+        bin_files_controller::find_hazards,
+        bin_files_controller::liveness,
+        bin_files_controller::pipeline_diagram,
+        benchmark_suites_controller::find_all,
+        benchmark_suites_controller::insert,
+        benchmark_suites_controller::find_by_id,
+        benchmark_suites_controller::update,
+        benchmark_suites_controller::delete,
+        performance_calculator_controller::calc_monocycle_query,
+        performance_calculator_controller::calc_monocycle_json,
+        performance_calculator_controller::calc_pipeline_query,
+        performance_calculator_controller::calc_pipeline_json,
+        performance_calculator_controller::calc_loop_unrolling_query,
+        performance_calculator_controller::calc_loop_unrolling_json,
+        performance_calculator_controller::calc_superscalar_query,
+        performance_calculator_controller::calc_superscalar_json,
+        performance_calculator_controller::calc_tomasulo_query,
+        performance_calculator_controller::calc_tomasulo_json,
+        performance_calculator_controller::calc_cache_query,
+        performance_calculator_controller::calc_cache_json,
+        performance_calculator_controller::calc_sweep,
+        performance_calculator_controller::calc_monocycle_suite_query,
+        performance_calculator_controller::calc_monocycle_suite_json,
+        performance_calculator_controller::calc_pipeline_suite_query,
+        performance_calculator_controller::calc_pipeline_suite_json,
+        performance_calculator_controller::report_monocycle,
+        performance_calculator_controller::report_pipeline,
+    ),
+    tags(
+        (name = "organizations", description = "Clock and CPIs of the organizations to compare"),
+        (name = "bin_files", description = "RV32I bin dumps, in binary or hexadecimal text"),
+        (name = "benchmark_suites", description = "Weighted sets of bin files"),
+        (name = "performance_calculator", description = "Monocycle and pipeline comparisons"),
+    )
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every "$ref" in the document, e.g. "#/components/schemas/ClockUnit"
+    fn references(value: &serde_json::Value, found: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, value) in object {
+                    match (key.as_str(), value.as_str()) {
+                        ("$ref", Some(reference)) => found.push(reference.to_string()),
+                        _ => references(value, found),
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => {
+                values.iter().for_each(|value| references(value, found))
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn every_reference_has_a_schema() {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let mut found = Vec::new();
+        references(&document, &mut found);
+        assert!(!found.is_empty());
+
+        for reference in found {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            assert!(
+                document["components"]["schemas"][name].is_object(),
+                "{} has no schema",
+                reference
+            );
+        }
+    }
+
+    #[test]
+    fn calculators_take_get_and_post() {
+        let document = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let paths = document["paths"].as_object().unwrap();
+
+        let calculators: Vec<&String> = paths
+            .keys()
+            .filter(|path| path.starts_with("/api/performance_calculator/calc_"))
+            .collect();
+        assert!(calculators.len() >= 8);
+
+        for path in calculators {
+            // The sweep parameters do not fit in a query string
+            if path.ends_with("calc_sweep") {
+                assert!(paths[path]["post"].is_object());
+                continue;
+            }
+            assert!(paths[path]["get"]["parameters"].is_array(), "{}", path);
+            assert!(paths[path]["post"]["requestBody"].is_object(), "{}", path);
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod api;
+pub mod config;
+pub mod models;
+pub mod schema;
+pub mod services;
+pub mod utils;
@@ -1,15 +1,7 @@
 use std::{env, io};
 
 use actix_web::{App, HttpServer, web};
-
-mod api;
-mod config;
-mod models;
-mod performance_calculator;
-mod riscv_core;
-mod schema;
-mod services;
-mod utils;
+use riscv_performance_checker::config;
 
 #[actix_rt::main]
 async fn main() -> io::Result<()> {
@@ -23,10 +15,13 @@ async fn main() -> io::Result<()> {
     let app_url = format!("{}:{}", &app_host, &app_port);
 
     let pool = config::db::migrate_and_config_db(&db_url);
+    // Created once, so every worker shares it
+    let cache = web::Data::new(config::cache::AnalysisCache::default());
 
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(cache.clone())
             .wrap(actix_web::middleware::Logger::default())
             .configure(config::app::config_services)
     })
@@ -0,0 +1,247 @@
+use diesel::{
+    Connection as _, ExpressionMethods, Insertable, Queryable, QueryDsl, QueryResult, RunQueryDsl,
+    Selectable,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    config::db::Connection,
+    schema::{benchmark_suite_bin_files, benchmark_suites},
+    utils::error::FieldError,
+};
+
+pub const DEFAULT_WEIGHT: f32 = 1.0;
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+pub struct BenchmarkSuite {
+    pub id: String,
+    pub bin_files: Vec<BenchmarkSuiteBinFile>,
+}
+
+#[derive(Clone, Queryable, Selectable, Serialize, Deserialize, ToSchema)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(table_name = crate::schema::benchmark_suite_bin_files)]
+pub struct BenchmarkSuiteBinFile {
+    pub bin_file_id: String,
+    pub weight: f32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BenchmarkSuiteDTO {
+    pub id: String,
+    pub bin_files: Vec<BenchmarkSuiteBinFileDTO>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BenchmarkSuiteBinFileDTO {
+    pub bin_file_id: String,
+    pub weight: Option<f32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::benchmark_suites)]
+struct NewBenchmarkSuite {
+    id: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::benchmark_suite_bin_files)]
+struct NewBenchmarkSuiteBinFile {
+    suite_id: String,
+    bin_file_id: String,
+    weight: f32,
+}
+
+impl BenchmarkSuiteDTO {
+    pub fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.id.trim().is_empty() {
+            errors.push(FieldError::new("id", "must not be empty"));
+        }
+
+        if self.bin_files.is_empty() {
+            errors.push(FieldError::new("bin_files", "must not be empty"));
+        }
+
+        for (index, bin_file) in self.bin_files.iter().enumerate() {
+            if let Some(weight) = bin_file.weight {
+                if !(weight.is_finite() && weight > 0.0) {
+                    errors.push(FieldError::new(
+                        &format!("bin_files[{}].weight", index),
+                        "must be greater than zero",
+                    ));
+                }
+            }
+
+            let is_duplicated = self.bin_files[..index]
+                .iter()
+                .any(|previous| previous.bin_file_id == bin_file.bin_file_id);
+            if is_duplicated {
+                errors.push(FieldError::new(
+                    &format!("bin_files[{}].bin_file_id", index),
+                    "must not be repeated in the suite",
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+impl BenchmarkSuite {
+    pub fn find_by_id(i: String, conn: &mut Connection) -> QueryResult<BenchmarkSuite> {
+        let suite_id = benchmark_suites::table
+            .select(benchmark_suites::id)
+            .find(i)
+            .get_result::<String>(conn)?;
+
+        let bin_files = BenchmarkSuite::find_bin_files(suite_id.clone(), conn)?;
+
+        Ok(BenchmarkSuite {
+            id: suite_id,
+            bin_files,
+        })
+    }
+
+    pub fn find_all(conn: &mut Connection) -> QueryResult<Vec<BenchmarkSuite>> {
+        let suite_ids = benchmark_suites::table
+            .select(benchmark_suites::id)
+            .load::<String>(conn)?;
+
+        suite_ids
+            .into_iter()
+            .map(|suite_id| {
+                let bin_files = BenchmarkSuite::find_bin_files(suite_id.clone(), conn)?;
+                Ok(BenchmarkSuite {
+                    id: suite_id,
+                    bin_files,
+                })
+            })
+            .collect()
+    }
+
+    pub fn insert(new_suite: BenchmarkSuiteDTO, conn: &mut Connection) -> QueryResult<usize> {
+        conn.transaction(|conn| {
+            diesel::insert_into(benchmark_suites::table)
+                .values(NewBenchmarkSuite {
+                    id: new_suite.id.clone(),
+                })
+                .execute(conn)?;
+
+            BenchmarkSuite::insert_bin_files(new_suite.id, new_suite.bin_files, conn)
+        })
+    }
+
+    pub fn update(
+        i: String,
+        update_suite: BenchmarkSuiteDTO,
+        conn: &mut Connection,
+    ) -> QueryResult<usize> {
+        conn.transaction(|conn| {
+            // Makes sure the suite exists before replacing its bin files
+            BenchmarkSuite::find_by_id(i.clone(), conn)?;
+
+            diesel::delete(
+                benchmark_suite_bin_files::table
+                    .filter(benchmark_suite_bin_files::suite_id.eq(i.clone())),
+            )
+            .execute(conn)?;
+
+            BenchmarkSuite::insert_bin_files(i, update_suite.bin_files, conn)
+        })
+    }
+
+    pub fn delete(i: String, conn: &mut Connection) -> QueryResult<usize> {
+        conn.transaction(|conn| {
+            BenchmarkSuite::find_by_id(i.clone(), conn)?;
+
+            diesel::delete(
+                benchmark_suite_bin_files::table
+                    .filter(benchmark_suite_bin_files::suite_id.eq(i.clone())),
+            )
+            .execute(conn)?;
+
+            diesel::delete(benchmark_suites::table.find(i)).execute(conn)
+        })
+    }
+
+    fn find_bin_files(
+        suite_id: String,
+        conn: &mut Connection,
+    ) -> QueryResult<Vec<BenchmarkSuiteBinFile>> {
+        benchmark_suite_bin_files::table
+            .filter(benchmark_suite_bin_files::suite_id.eq(suite_id))
+            .order(benchmark_suite_bin_files::bin_file_id)
+            .select((
+                benchmark_suite_bin_files::bin_file_id,
+                benchmark_suite_bin_files::weight,
+            ))
+            .load::<BenchmarkSuiteBinFile>(conn)
+    }
+
+    fn insert_bin_files(
+        suite_id: String,
+        bin_files: Vec<BenchmarkSuiteBinFileDTO>,
+        conn: &mut Connection,
+    ) -> QueryResult<usize> {
+        let new_bin_files: Vec<NewBenchmarkSuiteBinFile> = bin_files
+            .into_iter()
+            .map(|bin_file| NewBenchmarkSuiteBinFile {
+                suite_id: suite_id.clone(),
+                bin_file_id: bin_file.bin_file_id,
+                weight: bin_file.weight.unwrap_or(DEFAULT_WEIGHT),
+            })
+            .collect();
+
+        diesel::insert_into(benchmark_suite_bin_files::table)
+            .values(&new_bin_files)
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suite_bin_file(bin_file_id: &str, weight: Option<f32>) -> BenchmarkSuiteBinFileDTO {
+        BenchmarkSuiteBinFileDTO {
+            bin_file_id: bin_file_id.to_string(),
+            weight,
+        }
+    }
+
+    #[test]
+    fn invalid_suites_list_every_field() {
+        let suite = BenchmarkSuiteDTO {
+            id: String::new(),
+            bin_files: vec![
+                suite_bin_file("fatorial", None),
+                suite_bin_file("laco5x", Some(0.0)),
+                suite_bin_file("fatorial", Some(2.0)),
+            ],
+        };
+
+        let fields: Vec<String> = suite
+            .validate()
+            .into_iter()
+            .map(|error| error.field)
+            .collect();
+        assert_eq!(
+            fields,
+            vec!["id", "bin_files[1].weight", "bin_files[2].bin_file_id"]
+        );
+
+        let empty = BenchmarkSuiteDTO {
+            id: "empty".to_string(),
+            bin_files: Vec::new(),
+        };
+        let fields: Vec<String> = empty
+            .validate()
+            .into_iter()
+            .map(|error| error.field)
+            .collect();
+        assert_eq!(fields, vec!["bin_files"]);
+    }
+}
@@ -1,64 +1,244 @@
 use actix_web::web::Bytes;
 use diesel::{
-    Insertable, query_builder::AsChangeset, Queryable, QueryDsl, QueryResult, RunQueryDsl,
-    Selectable,
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    query_builder::AsChangeset,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::Text,
+    sqlite::{Sqlite, SqliteValue},
+    ExpressionMethods, Insertable, Queryable, QueryDsl, QueryResult, RunQueryDsl, Selectable,
+};
+use riscv_engine::{
+    pipeline_diagram::DiagramFormat,
+    program::{Dump, DumpFormat, Program},
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{config::db::Connection, schema::bin_files::dsl::*, utils::error::FieldError};
+
+// How the dump was written when it was uploaded. Files are always stored as binary text.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Serialize, Deserialize, AsExpression, FromSqlRow, ToSchema,
+)]
+#[diesel(sql_type = Text)]
+#[serde(rename_all = "snake_case")]
+pub enum BinFileFormat {
+    // One instruction per line, 32 characters of 0 and 1
+    BinaryText,
+    // One instruction per line, 8 hexadecimal digits with an optional 0x prefix
+    HexadecimalText,
+}
+
+impl BinFileFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BinFileFormat::BinaryText => "binary_text",
+            BinFileFormat::HexadecimalText => "hexadecimal_text",
+        }
+    }
+}
 
-use crate::{config::db::Connection, schema::bin_files::dsl::*};
+impl From<DumpFormat> for BinFileFormat {
+    fn from(dump_format: DumpFormat) -> BinFileFormat {
+        match dump_format {
+            DumpFormat::BinaryText => BinFileFormat::BinaryText,
+            DumpFormat::HexadecimalText => BinFileFormat::HexadecimalText,
+        }
+    }
+}
 
-#[derive(Queryable, Selectable, Serialize, Deserialize)]
+impl FromSql<Text, Sqlite> for BinFileFormat {
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+        let text = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        match text.as_str() {
+            "binary_text" => Ok(BinFileFormat::BinaryText),
+            "hexadecimal_text" => Ok(BinFileFormat::HexadecimalText),
+            _ => Err(format!("Unknown bin file format {}", text).into()),
+        }
+    }
+}
+
+impl ToSql<Text, Sqlite> for BinFileFormat {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.as_str());
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(Queryable, Selectable, Serialize, Deserialize, ToSchema)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 #[diesel(table_name = crate::schema::bin_files)]
 pub struct BinFile {
     pub id: String,
     // architecture: String,
     pub file: String,
+    pub original_filename: Option<String>,
+    pub format: BinFileFormat,
+    pub hash: String,
+    pub instruction_count: i32,
+}
+
+// Everything about a stored bin file except its content
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BinFileMetadata {
+    pub id: String,
+    pub original_filename: Option<String>,
+    pub format: BinFileFormat,
+    pub hash: String,
+    pub instruction_count: i32,
+}
+
+// Query of api/bin_files/{id}/pipeline_diagram
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PipelineDiagramQuery {
+    // "original" or one of the pipeline techniques, e.g. "forwading_with_nops"
+    pub technique: Option<String>,
+    // Defaults to text
+    #[param(inline)]
+    pub format: Option<DiagramFormat>,
 }
 
 #[derive(Insertable, Serialize, Deserialize, AsChangeset)]
 #[diesel(table_name = crate::schema::bin_files)]
+#[diesel(treat_none_as_null = true)]
 pub struct BinFileDTO {
-    id: String,
+    pub id: String,
     // architecture: String,
-    file: String,
+    pub file: String,
+    pub original_filename: Option<String>,
+    pub format: BinFileFormat,
+    pub hash: String,
+    pub instruction_count: i32,
+}
+
+fn hash_file(text_file: &str) -> String {
+    hex::encode(Sha256::digest(text_file.as_bytes()))
+}
+
+impl BinFileDTO {
+    // Detects the dump format, normalizes it to binary text and checks every instruction
+    pub fn from_upload(
+        i: String,
+        filename: Option<String>,
+        new_file: &Bytes,
+    ) -> Result<BinFileDTO, Vec<FieldError>> {
+        let mut errors = Vec::new();
+
+        if i.trim().is_empty() {
+            errors.push(FieldError::new("id", "must not be empty"));
+        }
+
+        let text_file = match std::str::from_utf8(new_file) {
+            Ok(text_file) => text_file,
+            Err(_) => {
+                errors.push(FieldError::new("file", "must be valid UTF-8 text"));
+                return Err(errors);
+            }
+        };
+
+        let dump = match Dump::parse(text_file) {
+            Ok(dump) => dump,
+            Err(dump_errors) => {
+                errors.extend(
+                    dump_errors
+                        .iter()
+                        .map(|(field, message)| FieldError::new(field, message)),
+                );
+                return Err(errors);
+            }
+        };
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(BinFileDTO {
+            id: i,
+            hash: hash_file(&dump.text),
+            instruction_count: dump.instruction_count as i32,
+            file: dump.text,
+            original_filename: filename,
+            format: BinFileFormat::from(dump.format),
+        })
+    }
+
+    pub fn metadata(&self) -> BinFileMetadata {
+        BinFileMetadata {
+            id: self.id.clone(),
+            original_filename: self.original_filename.clone(),
+            format: self.format,
+            hash: self.hash.clone(),
+            instruction_count: self.instruction_count,
+        }
+    }
+}
+
+impl From<BinFileDTO> for BinFile {
+    fn from(bin_file: BinFileDTO) -> BinFile {
+        BinFile {
+            id: bin_file.id,
+            file: bin_file.file,
+            original_filename: bin_file.original_filename,
+            format: bin_file.format,
+            hash: bin_file.hash,
+            instruction_count: bin_file.instruction_count,
+        }
+    }
+}
+
+// Files are checked on upload, but rows the metadata backfill could not read are stored as they were
+impl TryFrom<BinFile> for Program {
+    type Error = Vec<FieldError>;
+
+    fn try_from(bin_file: BinFile) -> Result<Program, Vec<FieldError>> {
+        Program::from_text(&bin_file.id, &bin_file.file).map_err(|errors| {
+            errors
+                .iter()
+                .map(|(field, message)| FieldError::new(field, message))
+                .collect()
+        })
+    }
 }
 
 impl BinFile {
     pub fn find_by_id(i: String, conn: &mut Connection) -> QueryResult<BinFile> {
         bin_files
-            .select((id, file))
+            .select((id, file, original_filename, format, hash, instruction_count))
             .find(i)
             .get_result::<BinFile>(conn)
     }
 
-    pub fn find_all(conn: &mut Connection) -> QueryResult<Vec<BinFile>> {
-        bin_files.select((id, file)).load::<BinFile>(conn)
+    // Content hash of the stored file, without loading the file itself
+    pub fn find_hash(i: String, conn: &mut Connection) -> QueryResult<String> {
+        bin_files.select(hash).find(i).get_result::<String>(conn)
     }
 
-    pub fn insert(i: String, new_file: Bytes, conn: &mut Connection) -> QueryResult<usize> {
-        let text_file = String::from_utf8(new_file.to_vec()).unwrap();
+    pub fn find_all(conn: &mut Connection) -> QueryResult<Vec<BinFile>> {
+        bin_files
+            .select((id, file, original_filename, format, hash, instruction_count))
+            .load::<BinFile>(conn)
+    }
 
-        let bin_file_dto = BinFileDTO {
-            id: i,
-            file: text_file,
-        };
+    pub fn exists(i: String, conn: &mut Connection) -> QueryResult<bool> {
+        diesel::select(diesel::dsl::exists(bin_files.find(i))).get_result::<bool>(conn)
+    }
 
+    pub fn insert(new_bin_file: &BinFileDTO, conn: &mut Connection) -> QueryResult<usize> {
         diesel::insert_into(bin_files)
-            .values(bin_file_dto)
+            .values(new_bin_file)
             .execute(conn)
     }
 
-    pub fn update(i: String, new_file: Bytes, conn: &mut Connection) -> QueryResult<usize> {
-        let text_file = String::from_utf8(new_file.to_vec()).unwrap();
-
-        let bin_file_dto = BinFileDTO {
-            id: i.clone(),
-            file: text_file,
-        };
-
+    pub fn update(
+        i: String,
+        update_bin_file: &BinFileDTO,
+        conn: &mut Connection,
+    ) -> QueryResult<usize> {
         diesel::update(bin_files.find(i))
-            .set(bin_file_dto)
+            .set(update_bin_file)
             .execute(conn)
     }
 
@@ -70,4 +250,145 @@ impl BinFile {
             Err(err) => Err(err),
         }
     }
+
+    // Files stored before bin files had metadata have an empty hash. They are checked like an
+    // upload, and the ones with invalid instructions keep the empty hash, so they are retried on the
+    // next start and the calculators answer 422 for them instead of panicking
+    pub fn backfill_metadata(conn: &mut Connection) -> QueryResult<Vec<String>> {
+        let legacy_files = bin_files
+            .filter(hash.eq(""))
+            .select((id, file))
+            .load::<(String, String)>(conn)?;
+
+        let mut invalid_files = Vec::new();
+        for (legacy_id, legacy_file) in legacy_files {
+            // Same normalization as BinFileDTO::from_upload, so equal programs get equal hashes
+            let dump = match Dump::parse(&legacy_file) {
+                Ok(dump) => dump,
+                Err(_) => {
+                    invalid_files.push(legacy_id);
+                    continue;
+                }
+            };
+
+            diesel::update(bin_files.find(&legacy_id))
+                .set((
+                    hash.eq(hash_file(&dump.text)),
+                    format.eq(BinFileFormat::from(dump.format)),
+                    instruction_count.eq(dump.instruction_count as i32),
+                    file.eq(dump.text),
+                ))
+                .execute(conn)?;
+        }
+
+        Ok(invalid_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::Connection as _;
+    use diesel_migrations::MigrationHarness;
+
+    use super::*;
+    use crate::config::db::MIGRATIONS;
+
+    // addi x5, x0, 5 and add x6, x5, x5
+    const BINARY: &str = "00000000010100000000001010010011\n00000000010100101000001100110011";
+    const HEXADECIMAL: &str = "0x00500293\r\n00528333\n";
+
+    fn upload(content: &str) -> Result<BinFileDTO, Vec<FieldError>> {
+        BinFileDTO::from_upload(
+            "upload".to_string(),
+            Some("upload.txt".to_string()),
+            &Bytes::from(content.to_string()),
+        )
+    }
+
+    #[test]
+    fn equal_programs_get_equal_hashes() {
+        let binary = upload(BINARY).unwrap();
+        let hexadecimal = upload(HEXADECIMAL).unwrap();
+
+        assert_eq!(binary.format, BinFileFormat::BinaryText);
+        assert_eq!(hexadecimal.format, BinFileFormat::HexadecimalText);
+        // Stored as the same binary text, whatever the upload looked like
+        assert_eq!(hexadecimal.file, BINARY);
+        assert_eq!(hexadecimal.hash, binary.hash);
+        assert_eq!(binary.instruction_count, 2);
+        assert_eq!(
+            binary.metadata().original_filename.as_deref(),
+            Some("upload.txt")
+        );
+
+        let other = upload("00000000010100000000001010010011").unwrap();
+        assert_ne!(other.hash, binary.hash);
+    }
+
+    #[test]
+    fn invalid_uploads_list_every_line() {
+        let fields = |content: &str| -> Vec<String> {
+            upload(content)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|error| error.field)
+                .collect()
+        };
+
+        assert_eq!(fields(" \n "), vec!["file"]);
+        // A short line and an unknown opcode
+        assert_eq!(
+            fields("00000000010100000000001010010011\n0101\n11111111111111111111111111111111"),
+            vec!["file[2]", "file[3]"]
+        );
+
+        let not_utf8 = BinFileDTO::from_upload(
+            "upload".to_string(),
+            None,
+            &Bytes::from_static(&[0xff, 0xfe]),
+        );
+        assert_eq!(
+            not_utf8.err().unwrap()[0].message,
+            "must be valid UTF-8 text"
+        );
+    }
+
+    #[test]
+    fn backfill_leaves_invalid_legacy_files_alone() {
+        let conn = &mut Connection::establish(":memory:").unwrap();
+        conn.run_pending_migrations(MIGRATIONS).unwrap();
+
+        // Rows as they were stored before the metadata columns
+        diesel::insert_into(bin_files)
+            .values(&vec![
+                (
+                    id.eq("good"),
+                    file.eq(" 00000000010100000000001010010011 \n"),
+                ),
+                (
+                    id.eq("bad"),
+                    file.eq("00000000010100000000001010010011\n0101"),
+                ),
+            ])
+            .execute(conn)
+            .unwrap();
+
+        assert_eq!(BinFile::backfill_metadata(conn).unwrap(), vec!["bad"]);
+
+        let good = BinFile::find_by_id("good".to_string(), conn).unwrap();
+        assert_eq!(good.file, "00000000010100000000001010010011");
+        assert_eq!(good.instruction_count, 1);
+        assert_eq!(
+            good.hash,
+            upload("00000000010100000000001010010011").unwrap().hash
+        );
+
+        // Retried on the next start, and refused by the calculators instead of panicking
+        let bad = BinFile::find_by_id("bad".to_string(), conn).unwrap();
+        assert_eq!(bad.hash, "");
+        let errors = Program::try_from(bad).err().unwrap();
+        assert_eq!(errors[0].field, "file[2]");
+        assert_eq!(BinFile::backfill_metadata(conn).unwrap(), vec!["bad"]);
+    }
 }
@@ -1,3 +1,5 @@
+pub mod benchmark_suite;
 pub mod bin_file;
 pub mod organization;
+pub mod performance_calculator;
 pub mod response;
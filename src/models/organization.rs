@@ -1,16 +1,74 @@
+use std::collections::BTreeMap;
+
 use diesel::{
-    Insertable, query_builder::AsChangeset, Queryable, QueryDsl, QueryResult, RunQueryDsl,
+    deserialize::{self, FromSql, FromSqlRow},
+    expression::AsExpression,
+    serialize::{self, IsNull, Output, ToSql},
+    sql_types::Text,
+    sqlite::{Sqlite, SqliteValue},
+    ExpressionMethods, Insertable,
+    query_builder::AsChangeset,
+    Queryable, QueryDsl, QueryResult, RunQueryDsl,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use riscv_engine::organization::{ClockUnit, OrganizationDefinition};
+
+use crate::{config::db::Connection, schema::organizations::dsl::*, utils::error::FieldError};
+
+// CPI overrides keyed by mnemonic (e.g. "mul", "lw", "jalr") or instruction class
+// ("mul", "div", "branch_taken", "branch_not_taken"), stored as a JSON object
+#[derive(Clone, Debug, Default, Serialize, Deserialize, AsExpression, FromSqlRow, ToSchema)]
+#[diesel(sql_type = Text)]
+#[serde(transparent)]
+pub struct CpiMap(pub BTreeMap<String, f32>);
+
+impl FromSql<Text, Sqlite> for CpiMap {
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+        let text = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+impl ToSql<Text, Sqlite> for CpiMap {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(serde_json::to_string(&self.0)?);
+        Ok(IsNull::No)
+    }
+}
+
+// Dynamic energy in picojoules keyed by mnemonic, instruction class or format ("r", "i", "l", "s",
+// "b", "u", "j"), stored as a JSON object
+#[derive(Clone, Debug, Default, Serialize, Deserialize, AsExpression, FromSqlRow, ToSchema)]
+#[diesel(sql_type = Text)]
+#[serde(transparent)]
+pub struct EnergyMap(pub BTreeMap<String, f32>);
 
-use crate::{config::db::Connection, schema::organizations::dsl::*};
+impl FromSql<Text, Sqlite> for EnergyMap {
+    fn from_sql(bytes: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+        let text = <String as FromSql<Text, Sqlite>>::from_sql(bytes)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+impl ToSql<Text, Sqlite> for EnergyMap {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(serde_json::to_string(&self.0)?);
+        Ok(IsNull::No)
+    }
+}
 
-#[derive(Clone, Queryable, Serialize, Deserialize)]
+#[derive(Clone, Queryable, Serialize, Deserialize, ToSchema)]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 #[diesel(table_name = crate::schema::organizations)]
 pub struct Organization {
     pub id: String,
     pub clock: f32,
+    // Stored as its name, e.g. "mhz"
+    #[serde(default)]
+    #[diesel(deserialize_as = String)]
+    pub clock_unit: ClockUnit,
     pub cpi_instruction_r: f32,
     pub cpi_instruction_i: f32,
     pub cpi_instruction_l: f32,
@@ -18,13 +76,25 @@ pub struct Organization {
     pub cpi_instruction_b: f32,
     pub cpi_instruction_u: f32,
     pub cpi_instruction_j: f32,
+    #[serde(default)]
+    pub cpi_map: Option<CpiMap>,
+    #[serde(default)]
+    pub energy_map: Option<EnergyMap>,
+    // Watts
+    #[serde(default)]
+    pub static_power: f32,
 }
 
-#[derive(Insertable, Serialize, Deserialize, AsChangeset)]
+#[derive(Insertable, Serialize, Deserialize, AsChangeset, ToSchema)]
 #[diesel(table_name = crate::schema::organizations)]
+#[diesel(treat_none_as_null = true)]
 pub struct OrganizationDTO {
     pub id: String,
     pub clock: f32,
+    // Stored as its name, e.g. "mhz"
+    #[serde(default)]
+    #[diesel(serialize_as = String)]
+    pub clock_unit: ClockUnit,
     pub cpi_instruction_r: f32,
     pub cpi_instruction_i: f32,
     pub cpi_instruction_l: f32,
@@ -32,6 +102,97 @@ pub struct OrganizationDTO {
     pub cpi_instruction_b: f32,
     pub cpi_instruction_u: f32,
     pub cpi_instruction_j: f32,
+    #[serde(default)]
+    pub cpi_map: Option<CpiMap>,
+    #[serde(default)]
+    pub energy_map: Option<EnergyMap>,
+    // Watts
+    #[serde(default)]
+    pub static_power: f32,
+}
+
+impl OrganizationDTO {
+    pub fn validate(&self) -> Vec<FieldError> {
+        OrganizationDefinition::from(self)
+            .validate()
+            .into_iter()
+            .map(|(field, message)| FieldError::new(&field, &message))
+            .collect()
+    }
+}
+
+impl From<&OrganizationDTO> for OrganizationDefinition {
+    fn from(organization: &OrganizationDTO) -> OrganizationDefinition {
+        OrganizationDefinition {
+            id: organization.id.clone(),
+            clock: organization.clock,
+            clock_unit: organization.clock_unit,
+            cpi_instruction_r: organization.cpi_instruction_r,
+            cpi_instruction_i: organization.cpi_instruction_i,
+            cpi_instruction_l: organization.cpi_instruction_l,
+            cpi_instruction_s: organization.cpi_instruction_s,
+            cpi_instruction_b: organization.cpi_instruction_b,
+            cpi_instruction_u: organization.cpi_instruction_u,
+            cpi_instruction_j: organization.cpi_instruction_j,
+            cpi_map: organization
+                .cpi_map
+                .clone()
+                .map(|CpiMap(overrides)| overrides)
+                .unwrap_or_default(),
+            energy_map: organization
+                .energy_map
+                .clone()
+                .map(|EnergyMap(costs)| costs)
+                .unwrap_or_default(),
+            static_power: organization.static_power,
+        }
+    }
+}
+
+impl From<OrganizationDTO> for Organization {
+    fn from(organization: OrganizationDTO) -> Organization {
+        Organization {
+            id: organization.id,
+            clock: organization.clock,
+            clock_unit: organization.clock_unit,
+            cpi_instruction_r: organization.cpi_instruction_r,
+            cpi_instruction_i: organization.cpi_instruction_i,
+            cpi_instruction_l: organization.cpi_instruction_l,
+            cpi_instruction_s: organization.cpi_instruction_s,
+            cpi_instruction_b: organization.cpi_instruction_b,
+            cpi_instruction_u: organization.cpi_instruction_u,
+            cpi_instruction_j: organization.cpi_instruction_j,
+            cpi_map: organization.cpi_map,
+            energy_map: organization.energy_map,
+            static_power: organization.static_power,
+        }
+    }
+}
+
+// The calculators get the clock already normalized to a period in seconds
+impl From<Organization> for riscv_engine::organization::Organization {
+    fn from(organization: Organization) -> riscv_engine::organization::Organization {
+        riscv_engine::organization::Organization {
+            clock_period: organization.clock_unit.to_period(organization.clock),
+            id: organization.id,
+            cpi_instruction_r: organization.cpi_instruction_r,
+            cpi_instruction_i: organization.cpi_instruction_i,
+            cpi_instruction_l: organization.cpi_instruction_l,
+            cpi_instruction_s: organization.cpi_instruction_s,
+            cpi_instruction_b: organization.cpi_instruction_b,
+            cpi_instruction_u: organization.cpi_instruction_u,
+            cpi_instruction_j: organization.cpi_instruction_j,
+            cpi_map: organization
+                .cpi_map
+                .map(|CpiMap(overrides)| overrides)
+                .unwrap_or_default(),
+            energy_map: organization
+                .energy_map
+                .map(|EnergyMap(costs)| costs)
+                .unwrap_or_default(),
+            static_power: organization.static_power,
+        }
+    }
 }
 
 impl Organization {
@@ -40,6 +201,7 @@ impl Organization {
             .select((
                 id,
                 clock,
+                clock_unit,
                 cpi_instruction_r,
                 cpi_instruction_i,
                 cpi_instruction_l,
@@ -47,6 +209,9 @@ impl Organization {
                 cpi_instruction_b,
                 cpi_instruction_u,
                 cpi_instruction_j,
+                cpi_map,
+                energy_map,
+                static_power,
             ))
             .find(i)
             .get_result::<Organization>(conn)
@@ -57,6 +222,7 @@ impl Organization {
             .select((
                 id,
                 clock,
+                clock_unit,
                 cpi_instruction_r,
                 cpi_instruction_i,
                 cpi_instruction_l,
@@ -64,13 +230,16 @@ impl Organization {
                 cpi_instruction_b,
                 cpi_instruction_u,
                 cpi_instruction_j,
+                cpi_map,
+                energy_map,
+                static_power,
             ))
             .load::<Organization>(conn)
     }
 
     pub fn insert(new_organization: OrganizationDTO, conn: &mut Connection) -> QueryResult<usize> {
         diesel::insert_into(organizations)
-            .values(&new_organization)
+            .values(new_organization)
             .execute(conn)
     }
 
@@ -80,7 +249,7 @@ impl Organization {
         conn: &mut Connection,
     ) -> QueryResult<usize> {
         diesel::update(organizations.find(i))
-            .set(&update_organization)
+            .set(update_organization)
             .execute(conn)
     }
 
@@ -93,3 +262,82 @@ impl Organization {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use diesel::Connection as _;
+    use diesel_migrations::MigrationHarness;
+
+    use super::*;
+    use crate::config::db::MIGRATIONS;
+
+    fn organization(name: &str, load_cpi: f32) -> OrganizationDTO {
+        serde_json::from_value(serde_json::json!({
+            "id": name,
+            "clock": 1.0,
+            "cpi_instruction_r": 1.0,
+            "cpi_instruction_i": 1.0,
+            "cpi_instruction_l": load_cpi,
+            "cpi_instruction_s": 1.0,
+            "cpi_instruction_b": 1.0,
+            "cpi_instruction_u": 1.0,
+            "cpi_instruction_j": 1.0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn the_database_caps_cpis_like_the_service() {
+        let conn = &mut Connection::establish(":memory:").unwrap();
+        conn.run_pending_migrations(MIGRATIONS).unwrap();
+
+        for (cpi, valid) in [(1000.0, true), (1000.5, false), (0.0, false)] {
+            let organization = organization(&format!("cpi {}", cpi), cpi);
+            assert_eq!(organization.validate().is_empty(), valid, "CPI {}", cpi);
+            assert_eq!(
+                Organization::insert(organization, conn).is_ok(),
+                valid,
+                "CPI {}",
+                cpi
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_organizations_list_every_field() {
+        let organization: OrganizationDTO = serde_json::from_value(serde_json::json!({
+            "id": " ",
+            "clock": 0.0,
+            "clock_unit": "ns",
+            "cpi_instruction_r": 1.0,
+            "cpi_instruction_i": -1.0,
+            "cpi_instruction_l": 1.0,
+            "cpi_instruction_s": 1.0,
+            "cpi_instruction_b": 1.0,
+            "cpi_instruction_u": 1.0,
+            "cpi_instruction_j": 1.0,
+            "cpi_map": { "mul": 0.0, "bogus": 2.0, "lw": 3.0 },
+            "energy_map": { "r": -1.0, "i": 5.0 },
+            "static_power": -0.5,
+        }))
+        .unwrap();
+
+        let fields: Vec<String> = organization
+            .validate()
+            .into_iter()
+            .map(|error| error.field)
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "id",
+                "clock",
+                "cpi_instruction_i",
+                "cpi_map.bogus",
+                "cpi_map.mul",
+                "energy_map.r",
+                "static_power",
+            ]
+        );
+    }
+}
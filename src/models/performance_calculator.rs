@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use riscv_engine::{
+    performance_calculator::{
+        design_space::{CostMetric, SweepValues},
+        memory_hierarchy::{Replacement, WritePolicy},
+    },
+    report::ReportFormat,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+// Accepted as a JSON body by the POST routes and as query parameters by the GET routes
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorDTO {
+    pub organization_a_name: String,
+    pub organization_b_name: String,
+    pub bin_file_name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorPipelineDTO {
+    pub organization_name: String,
+    pub bin_file_name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorLoopUnrollingDTO {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    // Copies of each loop body, 4 by default
+    pub factor: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorSuperscalarDTO {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    // Instructions issued per cycle, 2 by default
+    pub width: Option<usize>,
+    // Loads and stores per cycle, 1 by default
+    pub memory_ports: Option<usize>,
+    // Branches and jumps per cycle, 1 by default
+    pub branch_units: Option<usize>,
+    // Defaults to true
+    pub forwarding: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorTomasuloDTO {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    // Per functional unit kind (integer, multiplier, memory and branch), 3 by default
+    pub reservation_stations: Option<usize>,
+    // Per kind, 1 by default
+    pub functional_units: Option<usize>,
+    // 16 by default
+    pub rob_size: Option<usize>,
+    // Results broadcast per cycle, 1 by default
+    pub cdb_width: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorCacheDTO {
+    pub organization_name: String,
+    pub bin_file_name: String,
+    // Bytes, 1024 by default
+    pub icache_size: Option<usize>,
+    // Bytes, 16 by default
+    pub icache_block_size: Option<usize>,
+    // Ways per set, 1 (direct-mapped) by default
+    pub icache_associativity: Option<usize>,
+    // lru by default
+    pub icache_replacement: Option<Replacement>,
+    // Cycles, 20 by default
+    pub icache_miss_penalty: Option<usize>,
+    pub dcache_size: Option<usize>,
+    pub dcache_block_size: Option<usize>,
+    pub dcache_associativity: Option<usize>,
+    pub dcache_replacement: Option<Replacement>,
+    // write_back by default
+    pub dcache_write_policy: Option<WritePolicy>,
+    pub dcache_miss_penalty: Option<usize>,
+}
+
+// Only accepted as a JSON body: the parameters do not fit in a query string
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PerformanceCalculatorSweepDTO {
+    pub organization_name: String,
+    // Exactly one of bin_file_name and suite_name
+    pub bin_file_name: Option<String>,
+    pub suite_name: Option<String>,
+    // Parameter name to a list of values or a {start, end, step} range, e.g.
+    // {"cpi.b": [2, 3], "clock_scale": {"start": 1, "end": 1.1, "step": 0.1}}
+    pub parameters: BTreeMap<String, SweepValues>,
+    // energy by default
+    pub cost_metric: Option<CostMetric>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorSuiteDTO {
+    pub organization_a_name: String,
+    pub organization_b_name: String,
+    pub suite_name: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PerformanceCalculatorPipelineSuiteDTO {
+    pub organization_name: String,
+    pub suite_name: String,
+}
+
+// Read next to the calculator fields by the report routes
+#[derive(Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReportQuery {
+    // Defaults to markdown
+    #[param(inline)]
+    pub format: Option<ReportFormat>,
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::web::Query;
+
+    use super::*;
+
+    #[test]
+    fn query_strings_fill_the_same_dtos_as_json_bodies() {
+        let query = Query::<PerformanceCalculatorDTO>::from_query(
+            "organization_a_name=A&organization_b_name=B&bin_file_name=fatorial",
+        )
+        .unwrap()
+        .into_inner();
+        assert_eq!(query.organization_b_name, "B");
+        assert_eq!(query.bin_file_name, "fatorial");
+
+        // Optional fields are left out, enums go by their JSON name
+        let cache = Query::<PerformanceCalculatorCacheDTO>::from_query(
+            "organization_name=A&bin_file_name=fatorial&icache_size=2048&dcache_write_policy=write_through",
+        )
+        .unwrap()
+        .into_inner();
+        assert_eq!(cache.icache_size, Some(2048));
+        assert_eq!(cache.icache_replacement, None);
+        assert_eq!(cache.dcache_write_policy, Some(WritePolicy::WriteThrough));
+
+        assert!(Query::<PerformanceCalculatorPipelineDTO>::from_query("bin_file_name=x").is_err());
+        assert!(Query::<PerformanceCalculatorTomasuloDTO>::from_query(
+            "organization_name=A&bin_file_name=x&rob_size=many"
+        )
+        .is_err());
+    }
+}
@@ -1,3 +0,0 @@
-pub mod data_hazard;
-pub mod monocycle_calculator;
-pub mod pipeline_calculator;
@@ -1,9 +1,28 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    benchmark_suite_bin_files (suite_id, bin_file_id) {
+        suite_id -> Text,
+        bin_file_id -> Text,
+        weight -> Float,
+    }
+}
+
+diesel::table! {
+    benchmark_suites (id) {
+        id -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     bin_files (id) {
         id -> Text,
         file -> Text,
+        original_filename -> Nullable<Text>,
+        format -> Text,
+        hash -> Text,
+        instruction_count -> Integer,
     }
 }
 
@@ -12,6 +31,7 @@ diesel::table! {
         id -> Text,
         created_at -> Timestamp,
         clock -> Float,
+        clock_unit -> Text,
         cpi_instruction_r -> Float,
         cpi_instruction_i -> Float,
         cpi_instruction_l -> Float,
@@ -19,7 +39,18 @@ diesel::table! {
         cpi_instruction_b -> Float,
         cpi_instruction_u -> Float,
         cpi_instruction_j -> Float,
+        cpi_map -> Nullable<Text>,
+        energy_map -> Nullable<Text>,
+        static_power -> Float,
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(bin_files, organizations,);
+diesel::joinable!(benchmark_suite_bin_files -> benchmark_suites (suite_id));
+diesel::joinable!(benchmark_suite_bin_files -> bin_files (bin_file_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    benchmark_suite_bin_files,
+    benchmark_suites,
+    bin_files,
+    organizations,
+);
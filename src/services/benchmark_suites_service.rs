@@ -0,0 +1,101 @@
+use actix_web::web;
+use diesel::result::Error as DieselError;
+
+use crate::{
+    config::db::{Connection, Pool},
+    models::{
+        benchmark_suite::{BenchmarkSuite, BenchmarkSuiteDTO},
+        bin_file::BinFile,
+    },
+    utils::error::{FieldError, ServiceError},
+};
+
+fn not_found(id: &str) -> ServiceError {
+    ServiceError::not_found(format!("Benchmark suite {} not found", id))
+}
+
+// Validates the suite and makes sure every referenced bin file exists
+fn validate(
+    benchmark_suite_dto: &BenchmarkSuiteDTO,
+    conn: &mut Connection,
+) -> Result<(), ServiceError> {
+    let mut errors = benchmark_suite_dto.validate();
+
+    for (index, bin_file) in benchmark_suite_dto.bin_files.iter().enumerate() {
+        match BinFile::find_by_id(bin_file.bin_file_id.clone(), conn) {
+            Ok(_) => (),
+            Err(DieselError::NotFound) => errors.push(FieldError::new(
+                &format!("bin_files[{}].bin_file_id", index),
+                &format!("Bin file {} not found", bin_file.bin_file_id),
+            )),
+            Err(error) => return Err(ServiceError::from(error)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
+    }
+
+    Ok(())
+}
+
+pub async fn find_by_id(
+    id: String,
+    pool: &web::Data<Pool>,
+) -> Result<BenchmarkSuite, ServiceError> {
+    match BenchmarkSuite::find_by_id(id.clone(), &mut pool.get().unwrap()) {
+        Ok(benchmark_suite) => Ok(benchmark_suite),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+pub async fn find_all(pool: &web::Data<Pool>) -> Result<Vec<BenchmarkSuite>, ServiceError> {
+    match BenchmarkSuite::find_all(&mut pool.get().unwrap()) {
+        Ok(benchmark_suites) => Ok(benchmark_suites),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+pub async fn insert(
+    benchmark_suite_dto: BenchmarkSuiteDTO,
+    pool: &web::Data<Pool>,
+) -> Result<(), ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    validate(&benchmark_suite_dto, conn)?;
+
+    match BenchmarkSuite::insert(benchmark_suite_dto, conn) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+pub async fn update(
+    id: String,
+    benchmark_suite_dto: BenchmarkSuiteDTO,
+    pool: &web::Data<Pool>,
+) -> Result<(), ServiceError> {
+    if benchmark_suite_dto.id != id {
+        return Err(ServiceError::validation(vec![FieldError::new(
+            "id",
+            "must match the id in the path",
+        )]));
+    }
+
+    let conn = &mut pool.get().unwrap();
+    validate(&benchmark_suite_dto, conn)?;
+
+    match BenchmarkSuite::update(id.clone(), benchmark_suite_dto, conn) {
+        Ok(_) => Ok(()),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+pub async fn delete(id: String, pool: &web::Data<Pool>) -> Result<(), ServiceError> {
+    match BenchmarkSuite::delete(id.clone(), &mut pool.get().unwrap()) {
+        Ok(_) => Ok(()),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
@@ -2,40 +2,110 @@ use actix_web::{
     http::StatusCode,
     web::{self, Bytes},
 };
+use diesel::result::Error as DieselError;
+use riscv_engine::{
+    performance_calculator::{
+        data_hazard::{self, InstructionHazardReport},
+        liveness::{self, LivenessReport},
+        pipeline_calculator,
+    },
+    pipeline_diagram::{PipelineDiagram, ORIGINAL_TECHNIQUE},
+    program::Program,
+};
+
+use crate::{
+    config::db::Pool,
+    models::bin_file::{BinFile, BinFileDTO, BinFileMetadata, PipelineDiagramQuery},
+    utils::error::{FieldError, ServiceError},
+};
 
-use crate::{config::db::Pool, models::bin_file::BinFile, utils::error::ServiceError};
+fn not_found(id: &str) -> ServiceError {
+    ServiceError::not_found(format!("Bin file {} not found", id))
+}
 
 pub async fn find_by_id(id: String, pool: &web::Data<Pool>) -> Result<BinFile, ServiceError> {
-    match BinFile::find_by_id(id, &mut pool.get().unwrap()) {
+    match BinFile::find_by_id(id.clone(), &mut pool.get().unwrap()) {
         Ok(bin_file) => Ok(bin_file),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+// Every dependency of each instruction of the bin file
+pub async fn find_hazards(
+    id: String,
+    pool: &web::Data<Pool>,
+) -> Result<Vec<InstructionHazardReport>, ServiceError> {
+    let bin_file = find_by_id(id, pool).await?;
+    let program = Program::try_from(bin_file).map_err(ServiceError::validation)?;
+
+    Ok(data_hazard::hazard_report(&program.instructions))
+}
+
+// Live registers, def-use chains and unused writes of the bin file
+pub async fn liveness(id: String, pool: &web::Data<Pool>) -> Result<LivenessReport, ServiceError> {
+    let bin_file = find_by_id(id, pool).await?;
+    let program = Program::try_from(bin_file).map_err(ServiceError::validation)?;
+
+    Ok(liveness::liveness_report(&program.instructions))
+}
+
+// Cycle diagram of the bin file run with a technique, rendered in the requested format
+pub async fn pipeline_diagram(
+    id: String,
+    query: PipelineDiagramQuery,
+    pool: &web::Data<Pool>,
+) -> Result<String, ServiceError> {
+    let bin_file = find_by_id(id, pool).await?;
+    let program = Program::try_from(bin_file).map_err(ServiceError::validation)?;
+    let technique = query
+        .technique
+        .unwrap_or_else(|| ORIGINAL_TECHNIQUE.to_string());
+
+    match PipelineDiagram::for_technique(&technique, &program.instructions) {
+        Some(diagram) => Ok(diagram.render(query.format.unwrap_or_default())),
+        None => {
+            let mut names = vec![ORIGINAL_TECHNIQUE];
+            names.extend(pipeline_calculator::TECHNIQUE_NAMES);
+            Err(ServiceError::validation(vec![FieldError::new(
+                "technique",
+                &format!("must be one of: {}", names.join(", ")),
+            )]))
+        }
     }
 }
 
 pub async fn find_all(pool: &web::Data<Pool>) -> Result<Vec<BinFile>, ServiceError> {
     match BinFile::find_all(&mut pool.get().unwrap()) {
         Ok(bin_files) => Ok(bin_files),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
 pub async fn insert(
     id: String,
+    original_filename: Option<String>,
     bin_file_data: Bytes,
     pool: &web::Data<Pool>,
-) -> Result<(), ServiceError> {
-    match BinFile::insert(id, bin_file_data, &mut pool.get().unwrap()) {
-        Ok(_) => Ok(()),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+) -> Result<BinFileMetadata, ServiceError> {
+    let bin_file_dto = BinFileDTO::from_upload(id.clone(), original_filename, &bin_file_data)
+        .map_err(ServiceError::validation)?;
+
+    let conn = &mut pool.get().unwrap();
+    match BinFile::exists(id.clone(), conn) {
+        Ok(true) => {
+            return Err(ServiceError::new(
+                StatusCode::CONFLICT,
+                format!("Bin file {} already exists", id),
+            ))
+        }
+        Ok(false) => (),
+        Err(error) => return Err(ServiceError::from(error)),
+    }
+
+    match BinFile::insert(&bin_file_dto, conn) {
+        Ok(_) => Ok(bin_file_dto.metadata()),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
@@ -43,22 +113,21 @@ pub async fn update(
     id: String,
     bin_file_data: Bytes,
     pool: &web::Data<Pool>,
-) -> Result<(), ServiceError> {
-    match BinFile::update(id, bin_file_data, &mut pool.get().unwrap()) {
-        Ok(_) => Ok(()),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+) -> Result<BinFileMetadata, ServiceError> {
+    let bin_file_dto = BinFileDTO::from_upload(id.clone(), None, &bin_file_data)
+        .map_err(ServiceError::validation)?;
+
+    match BinFile::update(id.clone(), &bin_file_dto, &mut pool.get().unwrap()) {
+        Ok(0) => Err(not_found(&id)),
+        Ok(_) => Ok(bin_file_dto.metadata()),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
 pub async fn delete(id: String, pool: &web::Data<Pool>) -> Result<(), ServiceError> {
-    match BinFile::delete(id, &mut pool.get().unwrap()) {
+    match BinFile::delete(id.clone(), &mut pool.get().unwrap()) {
         Ok(_) => Ok(()),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
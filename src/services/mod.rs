@@ -1,3 +1,4 @@
+pub mod benchmark_suites_service;
 pub mod bin_files_service;
 pub mod organizations_service;
 pub mod performance_calculator_service;
@@ -1,28 +1,28 @@
-use actix_web::{http::StatusCode, web};
+use actix_web::web;
+use diesel::result::Error as DieselError;
 
 use crate::{
     config::db::Pool,
     models::organization::{Organization, OrganizationDTO},
-    utils::error::ServiceError,
+    utils::error::{FieldError, ServiceError},
 };
 
+fn not_found(id: &str) -> ServiceError {
+    ServiceError::not_found(format!("Organization {} not found", id))
+}
+
 pub async fn find_by_id(id: String, pool: &web::Data<Pool>) -> Result<Organization, ServiceError> {
-    match Organization::find_by_id(id, &mut pool.get().unwrap()) {
+    match Organization::find_by_id(id.clone(), &mut pool.get().unwrap()) {
         Ok(organization) => Ok(organization),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
 pub async fn find_all(pool: &web::Data<Pool>) -> Result<Vec<Organization>, ServiceError> {
     match Organization::find_all(&mut pool.get().unwrap()) {
         Ok(organizations) => Ok(organizations),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
@@ -30,12 +30,14 @@ pub async fn insert(
     organization_dto: OrganizationDTO,
     pool: &web::Data<Pool>,
 ) -> Result<(), ServiceError> {
+    let errors = organization_dto.validate();
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
+    }
+
     match Organization::insert(organization_dto, &mut pool.get().unwrap()) {
         Ok(_) => Ok(()),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
@@ -44,21 +46,25 @@ pub async fn update(
     organization_dto: OrganizationDTO,
     pool: &web::Data<Pool>,
 ) -> Result<(), ServiceError> {
-    match Organization::update(id, organization_dto, &mut pool.get().unwrap()) {
+    let mut errors = organization_dto.validate();
+    if organization_dto.id != id {
+        errors.push(FieldError::new("id", "must match the id in the path"));
+    }
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
+    }
+
+    match Organization::update(id.clone(), organization_dto, &mut pool.get().unwrap()) {
+        Ok(0) => Err(not_found(&id)),
         Ok(_) => Ok(()),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
 
 pub async fn delete(id: String, pool: &web::Data<Pool>) -> Result<(), ServiceError> {
-    match Organization::delete(id, &mut pool.get().unwrap()) {
+    match Organization::delete(id.clone(), &mut pool.get().unwrap()) {
         Ok(_) => Ok(()),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+        Err(DieselError::NotFound) => Err(not_found(&id)),
+        Err(error) => Err(ServiceError::from(error)),
     }
 }
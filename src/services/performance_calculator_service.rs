@@ -1,44 +1,593 @@
+use std::{path::Path, sync::Arc};
+
 use actix_web::{http::StatusCode, web};
+use diesel::result::Error as DieselError;
+use riscv_engine::{
+    i18n::Locale,
+    organization::Organization,
+    performance_calculator::{
+        design_space::{self, SweepCalculator},
+        loop_unrolling::{self, LoopUnrollingCalculator, DEFAULT_UNROLL_FACTOR},
+        memory_hierarchy::{CacheCalculator, CacheConfig},
+        monocycle_calculator,
+        pipeline_calculator::{self, TechniqueProgram},
+        suite_calculator::{MonocycleSuiteCalculator, PipelineSuiteCalculator},
+        superscalar::{IssueRules, SuperscalarCalculator},
+        tomasulo::{
+            TomasuloCalculator, TomasuloConfig, DEFAULT_CDB_WIDTH, DEFAULT_FUNCTIONAL_UNITS,
+            DEFAULT_RESERVATION_STATIONS, DEFAULT_ROB_SIZE,
+        },
+    },
+    program::Program,
+    report::{self, ReportFormat},
+};
 
 use crate::{
-    config::db::Pool,
-    performance_calculator::{
-        monocycle_calculator::PerformanceCalculatorDTO,
-        pipeline_calculator::PerformanceCalculatorPipelineDTO,
+    config::{
+        cache::{self, AnalysisCache, AnalysisKey},
+        db::{Connection, Pool},
+    },
+    models::{
+        benchmark_suite::BenchmarkSuite,
+        bin_file::BinFile,
+        organization,
+        performance_calculator::{
+            PerformanceCalculatorCacheDTO, PerformanceCalculatorDTO,
+            PerformanceCalculatorLoopUnrollingDTO, PerformanceCalculatorPipelineDTO,
+            PerformanceCalculatorPipelineSuiteDTO, PerformanceCalculatorSuiteDTO,
+            PerformanceCalculatorSuperscalarDTO, PerformanceCalculatorSweepDTO,
+            PerformanceCalculatorTomasuloDTO, ReportQuery,
+        },
+    },
+    utils::{
+        constants::PIPELINE_FILES_DIR,
+        error::{FieldError, ServiceError},
     },
-    utils::error::ServiceError,
 };
 
+fn find_organization(name: &str, conn: &mut Connection) -> Result<Organization, ServiceError> {
+    match organization::Organization::find_by_id(name.to_string(), conn) {
+        Ok(organization) => Ok(Organization::from(organization)),
+        Err(DieselError::NotFound) => Err(ServiceError::not_found(format!(
+            "Organization {} not found",
+            name
+        ))),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+fn bin_file_not_found(name: &str) -> ServiceError {
+    ServiceError::not_found(format!("Bin file {} not found", name))
+}
+
+fn find_program_hash(name: &str, conn: &mut Connection) -> Result<String, ServiceError> {
+    match BinFile::find_hash(name.to_string(), conn) {
+        Ok(hash) => Ok(hash),
+        Err(DieselError::NotFound) => Err(bin_file_not_found(name)),
+        Err(error) => Err(ServiceError::from(error)),
+    }
+}
+
+// The file is only read and decoded the first time its content is seen, later requests share
+// the cached instructions
+fn find_program(
+    name: &str,
+    program_hash: &str,
+    cache: &AnalysisCache,
+    conn: &mut Connection,
+) -> Result<Program, ServiceError> {
+    let instructions = cache
+        .programs
+        .get_or_try_insert_with(program_hash.to_string(), || {
+            match BinFile::find_by_id(name.to_string(), conn) {
+                Ok(bin_file) => Program::try_from(bin_file)
+                    .map(|program| program.instructions)
+                    .map_err(ServiceError::validation),
+                Err(DieselError::NotFound) => Err(bin_file_not_found(name)),
+                Err(error) => Err(ServiceError::from(error)),
+            }
+        })?;
+
+    Ok(Program {
+        id: name.to_string(),
+        instructions,
+    })
+}
+
+fn find_techniques(
+    name: &str,
+    program_hash: &str,
+    cache: &AnalysisCache,
+    conn: &mut Connection,
+) -> Result<Arc<Vec<TechniqueProgram>>, ServiceError> {
+    cache
+        .techniques
+        .get_or_try_insert_with(program_hash.to_string(), || {
+            let program = find_program(name, program_hash, cache, conn)?;
+            Ok(Arc::new(pipeline_calculator::techniques(
+                &program.instructions,
+            )))
+        })
+}
+
+fn empty_suite(suite_name: &str) -> ServiceError {
+    ServiceError::new(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        format!("Benchmark suite {} has no bin files", suite_name),
+    )
+}
+
+// Id, content hash and weight of every bin file of the suite
+fn find_suite_bin_files(
+    suite_name: &str,
+    conn: &mut Connection,
+) -> Result<Vec<(String, String, f32)>, ServiceError> {
+    let suite = match BenchmarkSuite::find_by_id(suite_name.to_string(), conn) {
+        Ok(suite) => suite,
+        Err(DieselError::NotFound) => {
+            return Err(ServiceError::not_found(format!(
+                "Benchmark suite {} not found",
+                suite_name
+            )))
+        }
+        Err(error) => return Err(ServiceError::from(error)),
+    };
+
+    if suite.bin_files.is_empty() {
+        return Err(empty_suite(suite_name));
+    }
+
+    suite
+        .bin_files
+        .iter()
+        .map(|suite_bin_file| {
+            let program_hash = find_program_hash(&suite_bin_file.bin_file_id, conn)?;
+            Ok((
+                suite_bin_file.bin_file_id.clone(),
+                program_hash,
+                suite_bin_file.weight,
+            ))
+        })
+        .collect()
+}
+
+// Every program of the suite with its weight
+fn find_suite_programs(
+    bin_files: &[(String, String, f32)],
+    cache: &AnalysisCache,
+    conn: &mut Connection,
+) -> Result<Vec<(Program, f32)>, ServiceError> {
+    bin_files
+        .iter()
+        .map(|(name, program_hash, weight)| {
+            let program = find_program(name, program_hash, cache, conn)?;
+            Ok((program, *weight))
+        })
+        .collect()
+}
+
 pub async fn calc_monocycle(
     performance_calculator_dto: PerformanceCalculatorDTO,
+    locale: Locale,
     pool: &web::Data<Pool>,
-) -> Result<crate::performance_calculator::monocycle_calculator::PerformanceCalculator, ServiceError>
-{
-    match crate::performance_calculator::monocycle_calculator::PerformanceCalculator::calc_monocycle(
-        performance_calculator_dto,
-        &mut pool.get().unwrap(),
-    ) {
-        Ok(performance) => Ok(performance),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
-    }
+    cache: &web::Data<AnalysisCache>,
+) -> Result<monocycle_calculator::PerformanceCalculator, ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    let organization_a = find_organization(&performance_calculator_dto.organization_a_name, conn)?;
+    let organization_b = find_organization(&performance_calculator_dto.organization_b_name, conn)?;
+    let name = &performance_calculator_dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+
+    let key = AnalysisKey::new(
+        name,
+        &program_hash,
+        &[&organization_a, &organization_b],
+        "monocycle",
+    );
+    let mut performance = cache.monocycle.get_or_try_insert_with(key, || {
+        let program = find_program(name, &program_hash, cache, conn)?;
+        Ok::<_, ServiceError>(monocycle_calculator::PerformanceCalculator::monocycle_from(
+            &organization_a,
+            &organization_b,
+            &program,
+        ))
+    })?;
+    performance.localize(locale);
+
+    Ok(performance)
 }
 
 pub async fn calc_pipeline(
     performance_calculator_pipeline_dto: PerformanceCalculatorPipelineDTO,
     pool: &web::Data<Pool>,
-) -> Result<crate::performance_calculator::pipeline_calculator::PerformanceCalculator, ServiceError>
-{
-    match crate::performance_calculator::pipeline_calculator::PerformanceCalculator::calc_pipeline(
-        performance_calculator_pipeline_dto,
-        &mut pool.get().unwrap(),
-    ) {
-        Ok(performance) => Ok(performance),
-        Err(message) => Err(ServiceError::new(
-            StatusCode::BAD_REQUEST,
-            message.to_string(),
-        )),
+    cache: &web::Data<AnalysisCache>,
+) -> Result<pipeline_calculator::PerformanceCalculator, ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    let organization =
+        find_organization(&performance_calculator_pipeline_dto.organization_name, conn)?;
+    let name = &performance_calculator_pipeline_dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+
+    let techniques = find_techniques(name, &program_hash, cache, conn)?;
+
+    if let Err(error) =
+        pipeline_calculator::write_technique_files(Path::new(PIPELINE_FILES_DIR), &techniques)
+    {
+        return Err(ServiceError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to write pipeline files: {}", error),
+        ));
+    }
+
+    let key = AnalysisKey::new(name, &program_hash, &[&organization], "pipeline");
+    cache.pipeline.get_or_try_insert_with(key, || {
+        let program = find_program(name, &program_hash, cache, conn)?;
+        Ok(pipeline_calculator::PerformanceCalculator::from_techniques(
+            &organization,
+            &program,
+            &techniques,
+        ))
+    })
+}
+
+// Cycles per iteration of every counted loop with each technique, before and after unrolling
+pub async fn calc_loop_unrolling(
+    performance_calculator_loop_unrolling_dto: PerformanceCalculatorLoopUnrollingDTO,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<LoopUnrollingCalculator, ServiceError> {
+    let factor = performance_calculator_loop_unrolling_dto
+        .factor
+        .unwrap_or(DEFAULT_UNROLL_FACTOR);
+    if let Err(message) = loop_unrolling::check_factor(factor) {
+        return Err(ServiceError::validation(vec![FieldError::new(
+            "factor", &message,
+        )]));
+    }
+
+    let conn = &mut pool.get().unwrap();
+    let organization = find_organization(
+        &performance_calculator_loop_unrolling_dto.organization_name,
+        conn,
+    )?;
+    let name = &performance_calculator_loop_unrolling_dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+
+    let key = AnalysisKey::new(name, &program_hash, &[&organization], "loop_unrolling")
+        .with_config(&factor);
+    cache.loop_unrolling.get_or_try_insert_with(key, || {
+        let program = find_program(name, &program_hash, cache, conn)?;
+        Ok(LoopUnrollingCalculator::unrolling_from(
+            &organization,
+            &program,
+            factor,
+        ))
+    })
+}
+
+// Issue log, IPC and unused issue slots of an in-order superscalar pipeline
+pub async fn calc_superscalar(
+    performance_calculator_superscalar_dto: PerformanceCalculatorSuperscalarDTO,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<SuperscalarCalculator, ServiceError> {
+    let defaults = IssueRules::default();
+    let rules = IssueRules {
+        width: performance_calculator_superscalar_dto
+            .width
+            .unwrap_or(defaults.width),
+        memory_ports: performance_calculator_superscalar_dto
+            .memory_ports
+            .unwrap_or(defaults.memory_ports),
+        branch_units: performance_calculator_superscalar_dto
+            .branch_units
+            .unwrap_or(defaults.branch_units),
+        forwarding: performance_calculator_superscalar_dto
+            .forwarding
+            .unwrap_or(defaults.forwarding),
+    };
+
+    let errors: Vec<FieldError> = rules
+        .check()
+        .iter()
+        .map(|(field, message)| FieldError::new(field, message))
+        .collect();
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
+    }
+
+    let conn = &mut pool.get().unwrap();
+    let organization = find_organization(
+        &performance_calculator_superscalar_dto.organization_name,
+        conn,
+    )?;
+    let name = &performance_calculator_superscalar_dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+
+    let key =
+        AnalysisKey::new(name, &program_hash, &[&organization], "superscalar").with_config(&rules);
+    cache.superscalar.get_or_try_insert_with(key, || {
+        let program = find_program(name, &program_hash, cache, conn)?;
+        Ok(SuperscalarCalculator::superscalar_from(
+            &organization,
+            &program,
+            rules,
+        ))
+    })
+}
+
+// Issue, execute, write result and commit cycles of each instruction on a Tomasulo pipeline with
+// a reorder buffer, next to the in-order techniques
+pub async fn calc_tomasulo(
+    performance_calculator_tomasulo_dto: PerformanceCalculatorTomasuloDTO,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<TomasuloCalculator, ServiceError> {
+    let dto = &performance_calculator_tomasulo_dto;
+    let config = TomasuloConfig::uniform(
+        dto.reservation_stations
+            .unwrap_or(DEFAULT_RESERVATION_STATIONS),
+        dto.functional_units.unwrap_or(DEFAULT_FUNCTIONAL_UNITS),
+        dto.rob_size.unwrap_or(DEFAULT_ROB_SIZE),
+        dto.cdb_width.unwrap_or(DEFAULT_CDB_WIDTH),
+    );
+
+    let errors: Vec<FieldError> = config
+        .check()
+        .iter()
+        .map(|(field, message)| FieldError::new(field, message))
+        .collect();
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
     }
+
+    let conn = &mut pool.get().unwrap();
+    let organization = find_organization(&dto.organization_name, conn)?;
+    let name = &dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+
+    let key =
+        AnalysisKey::new(name, &program_hash, &[&organization], "tomasulo").with_config(&config);
+    cache.tomasulo.get_or_try_insert_with(key, || {
+        let program = find_program(name, &program_hash, cache, conn)?;
+        Ok(TomasuloCalculator::tomasulo_from(
+            &organization,
+            &program,
+            config.clone(),
+        ))
+    })
+}
+
+// Hit rates and AMAT of the L1 instruction and data caches over the executed address trace, and
+// the miss stalls they add to the CPI and execution time
+pub async fn calc_cache(
+    performance_calculator_cache_dto: PerformanceCalculatorCacheDTO,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<CacheCalculator, ServiceError> {
+    let dto = &performance_calculator_cache_dto;
+    let defaults = CacheConfig::default();
+    let instruction_cache = CacheConfig {
+        size: dto.icache_size.unwrap_or(defaults.size),
+        block_size: dto.icache_block_size.unwrap_or(defaults.block_size),
+        associativity: dto.icache_associativity.unwrap_or(defaults.associativity),
+        replacement: dto.icache_replacement.unwrap_or(defaults.replacement),
+        // Never written
+        write_policy: defaults.write_policy,
+        miss_penalty: dto.icache_miss_penalty.unwrap_or(defaults.miss_penalty),
+    };
+    let data_cache = CacheConfig {
+        size: dto.dcache_size.unwrap_or(defaults.size),
+        block_size: dto.dcache_block_size.unwrap_or(defaults.block_size),
+        associativity: dto.dcache_associativity.unwrap_or(defaults.associativity),
+        replacement: dto.dcache_replacement.unwrap_or(defaults.replacement),
+        write_policy: dto.dcache_write_policy.unwrap_or(defaults.write_policy),
+        miss_penalty: dto.dcache_miss_penalty.unwrap_or(defaults.miss_penalty),
+    };
+
+    let errors: Vec<FieldError> = [("icache", &instruction_cache), ("dcache", &data_cache)]
+        .iter()
+        .flat_map(|(prefix, config)| {
+            config.validate().into_iter().map(move |(field, message)| {
+                FieldError::new(&format!("{}_{}", prefix, field), &message)
+            })
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
+    }
+
+    let conn = &mut pool.get().unwrap();
+    let organization = find_organization(&dto.organization_name, conn)?;
+    let name = &dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+
+    let key = AnalysisKey::new(name, &program_hash, &[&organization], "cache")
+        .with_config(&(instruction_cache, data_cache));
+    cache.cache.get_or_try_insert_with(key, || {
+        let program = find_program(name, &program_hash, cache, conn)?;
+        Ok(CacheCalculator::cache_from(
+            &organization,
+            &program,
+            instruction_cache,
+            data_cache,
+        ))
+    })
+}
+
+// Execution time and energy of every combination of the parameter values on a bin file or a
+// suite, with the Pareto front of execution time against the cost metric
+pub async fn calc_sweep(
+    performance_calculator_sweep_dto: PerformanceCalculatorSweepDTO,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<SweepCalculator, ServiceError> {
+    let dto = &performance_calculator_sweep_dto;
+    let mut errors = Vec::new();
+    if dto.bin_file_name.is_some() == dto.suite_name.is_some() {
+        errors.push(FieldError::new(
+            "bin_file_name",
+            "give either bin_file_name or suite_name",
+        ));
+    }
+    let points = match design_space::design_points(&dto.parameters) {
+        Ok(points) => points,
+        Err(parameter_errors) => {
+            errors.extend(parameter_errors.iter().map(|(name, message)| {
+                let field = match name.is_empty() {
+                    true => "parameters".to_string(),
+                    false => format!("parameters.{}", name),
+                };
+                FieldError::new(&field, message)
+            }));
+            Vec::new()
+        }
+    };
+    if !errors.is_empty() {
+        return Err(ServiceError::validation(errors));
+    }
+
+    let conn = &mut pool.get().unwrap();
+    let organization = find_organization(&dto.organization_name, conn)?;
+    // A bin file is a suite of one, hashed by its content like in the other calculators
+    let (workload_name, workload_hash, bin_files) = match (&dto.bin_file_name, &dto.suite_name) {
+        (Some(name), _) => {
+            let program_hash = find_program_hash(name, conn)?;
+            (
+                name,
+                program_hash.clone(),
+                vec![(name.clone(), program_hash, 1.0)],
+            )
+        }
+        (None, Some(suite_name)) => {
+            let bin_files = find_suite_bin_files(suite_name, conn)?;
+            (suite_name, cache::suite_hash(&bin_files), bin_files)
+        }
+        (None, None) => unreachable!("checked with the other fields"),
+    };
+    let cost_metric = dto.cost_metric.unwrap_or_default();
+
+    let key = AnalysisKey::new(workload_name, &workload_hash, &[&organization], "sweep")
+        .with_config(&(&points, cost_metric));
+    cache.sweep.get_or_try_insert_with(key, || {
+        let programs = find_suite_programs(&bin_files, cache, conn)?;
+        Ok(SweepCalculator::sweep_from(
+            &organization,
+            workload_name,
+            &programs,
+            &points,
+            cost_metric,
+        ))
+    })
+}
+
+// Written analysis of calc_monocycle
+pub async fn report_monocycle(
+    performance_calculator_dto: PerformanceCalculatorDTO,
+    report_query: ReportQuery,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<String, ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    let organization_a = find_organization(&performance_calculator_dto.organization_a_name, conn)?;
+    let organization_b = find_organization(&performance_calculator_dto.organization_b_name, conn)?;
+    let name = &performance_calculator_dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+    let program = find_program(name, &program_hash, cache, conn)?;
+
+    let performance = monocycle_calculator::PerformanceCalculator::monocycle_from(
+        &organization_a,
+        &organization_b,
+        &program,
+    );
+    let analysis =
+        report::monocycle::report(&organization_a, &organization_b, &program, &performance);
+
+    Ok(analysis.render(report_query.format.unwrap_or(ReportFormat::Markdown)))
+}
+
+// Written analysis of calc_pipeline, the technique programs are listed instead of written to files
+pub async fn report_pipeline(
+    performance_calculator_pipeline_dto: PerformanceCalculatorPipelineDTO,
+    report_query: ReportQuery,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<String, ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    let organization =
+        find_organization(&performance_calculator_pipeline_dto.organization_name, conn)?;
+    let name = &performance_calculator_pipeline_dto.bin_file_name;
+    let program_hash = find_program_hash(name, conn)?;
+    let program = find_program(name, &program_hash, cache, conn)?;
+    let techniques = find_techniques(name, &program_hash, cache, conn)?;
+
+    let performance = pipeline_calculator::PerformanceCalculator::from_techniques(
+        &organization,
+        &program,
+        &techniques,
+    );
+    let analysis = report::pipeline::report(&organization, &program, &techniques, &performance);
+
+    Ok(analysis.render(report_query.format.unwrap_or(ReportFormat::Markdown)))
+}
+
+pub async fn calc_monocycle_suite(
+    performance_calculator_suite_dto: PerformanceCalculatorSuiteDTO,
+    locale: Locale,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<MonocycleSuiteCalculator, ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    let organization_a =
+        find_organization(&performance_calculator_suite_dto.organization_a_name, conn)?;
+    let organization_b =
+        find_organization(&performance_calculator_suite_dto.organization_b_name, conn)?;
+    let suite_name = &performance_calculator_suite_dto.suite_name;
+    let bin_files = find_suite_bin_files(suite_name, conn)?;
+
+    let key = AnalysisKey::new(
+        suite_name,
+        &cache::suite_hash(&bin_files),
+        &[&organization_a, &organization_b],
+        "monocycle_suite",
+    );
+    let mut performance = cache.monocycle_suite.get_or_try_insert_with(key, || {
+        let programs = find_suite_programs(&bin_files, cache, conn)?;
+        MonocycleSuiteCalculator::monocycle_suite_from(
+            &organization_a,
+            &organization_b,
+            suite_name,
+            &programs,
+        )
+        .ok_or_else(|| empty_suite(suite_name))
+    })?;
+    performance.localize(locale);
+
+    Ok(performance)
+}
+
+pub async fn calc_pipeline_suite(
+    performance_calculator_pipeline_suite_dto: PerformanceCalculatorPipelineSuiteDTO,
+    pool: &web::Data<Pool>,
+    cache: &web::Data<AnalysisCache>,
+) -> Result<PipelineSuiteCalculator, ServiceError> {
+    let conn = &mut pool.get().unwrap();
+    let organization = find_organization(
+        &performance_calculator_pipeline_suite_dto.organization_name,
+        conn,
+    )?;
+    let suite_name = &performance_calculator_pipeline_suite_dto.suite_name;
+    let bin_files = find_suite_bin_files(suite_name, conn)?;
+
+    let key = AnalysisKey::new(
+        suite_name,
+        &cache::suite_hash(&bin_files),
+        &[&organization],
+        "pipeline_suite",
+    );
+    cache.pipeline_suite.get_or_try_insert_with(key, || {
+        let programs = find_suite_programs(&bin_files, cache, conn)?;
+        PipelineSuiteCalculator::pipeline_suite_from(&organization, suite_name, &programs)
+            .ok_or_else(|| empty_suite(suite_name))
+    })
 }
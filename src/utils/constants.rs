@@ -1,5 +1,6 @@
 // Messages
 pub const MESSAGE_OK: &str = "ok";
+pub const MESSAGE_VALIDATION_FAILED: &str = "validation_failed";
 // pub const MESSAGE_CAN_NOT_FETCH_DATA: &str = "cannot_fetch_data";
 // pub const MESSAGE_CAN_NOT_INSERT_DATA: &str = "cannot_insert_data";
 // pub const MESSAGE_CAN_NOT_UPDATE_DATA: &str = "cannot_update_data";
@@ -9,4 +10,5 @@ pub const MESSAGE_OK: &str = "ok";
 // Misc
 pub const EMPTY: &str = "";
 
-pub const NOP_INST: &str = "00000000000000000000000000010011";
+// Where calc_pipeline writes the program produced by each technique
+pub const PIPELINE_FILES_DIR: &str = "./pipeline_files";
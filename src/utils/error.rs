@@ -1,10 +1,29 @@
 use actix_web::{http::StatusCode, HttpResponse};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
 
-use crate::models::response::ResponseBody;
+use crate::{models::response::ResponseBody, utils::constants::MESSAGE_VALIDATION_FAILED};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: &str) -> FieldError {
+        FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
 
 pub struct ServiceError {
     pub http_status: StatusCode,
-    pub body: ResponseBody<String>,
+    pub body: ResponseBody<Value>,
 }
 
 impl ServiceError {
@@ -13,7 +32,22 @@ impl ServiceError {
             http_status,
             body: ResponseBody {
                 message,
-                data: String::new(),
+                data: Value::String(String::new()),
+            },
+        }
+    }
+
+    pub fn not_found(message: String) -> ServiceError {
+        ServiceError::new(StatusCode::NOT_FOUND, message)
+    }
+
+    // 422 with one entry per invalid field in the response data
+    pub fn validation(errors: Vec<FieldError>) -> ServiceError {
+        ServiceError {
+            http_status: StatusCode::UNPROCESSABLE_ENTITY,
+            body: ResponseBody {
+                message: MESSAGE_VALIDATION_FAILED.to_string(),
+                data: serde_json::to_value(errors).unwrap_or_default(),
             },
         }
     }
@@ -22,3 +56,68 @@ impl ServiceError {
         HttpResponse::build(self.http_status).json(&self.body)
     }
 }
+
+impl From<DieselError> for ServiceError {
+    fn from(error: DieselError) -> ServiceError {
+        let http_status = match &error {
+            DieselError::NotFound => StatusCode::NOT_FOUND,
+            DieselError::DatabaseError(kind, _) => match kind {
+                DatabaseErrorKind::UniqueViolation | DatabaseErrorKind::ForeignKeyViolation => {
+                    StatusCode::CONFLICT
+                }
+                DatabaseErrorKind::CheckViolation | DatabaseErrorKind::NotNullViolation => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        ServiceError::new(http_status, error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database_error(kind: DatabaseErrorKind) -> DieselError {
+        DieselError::DatabaseError(kind, Box::new(String::from("constraint failed")))
+    }
+
+    #[test]
+    fn diesel_errors_map_to_status_codes() {
+        let status = |error| ServiceError::from(error).http_status;
+
+        assert_eq!(status(DieselError::NotFound), StatusCode::NOT_FOUND);
+        assert_eq!(
+            status(database_error(DatabaseErrorKind::UniqueViolation)),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            status(database_error(DatabaseErrorKind::ForeignKeyViolation)),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            status(database_error(DatabaseErrorKind::CheckViolation)),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(DieselError::RollbackTransaction),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn validation_errors_are_listed_in_the_data() {
+        let error =
+            ServiceError::validation(vec![FieldError::new("clock", "must be greater than zero")]);
+
+        assert_eq!(error.http_status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.body.message, MESSAGE_VALIDATION_FAILED);
+        assert_eq!(
+            error.body.data,
+            serde_json::json!([{ "field": "clock", "message": "must be greater than zero" }])
+        );
+    }
+}
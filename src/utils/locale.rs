@@ -0,0 +1,105 @@
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, http::header::ACCEPT_LANGUAGE, web, FromRequest, HttpRequest};
+use riscv_engine::i18n::Locale;
+use serde::Deserialize;
+use utoipa::{
+    openapi::{
+        path::{Parameter, ParameterBuilder, ParameterIn},
+        schema::{ObjectBuilder, Type},
+        Required,
+    },
+    IntoParams,
+};
+
+#[derive(Deserialize)]
+struct LangQuery {
+    lang: Option<String>,
+}
+
+// Language of the result messages: the lang query parameter, then Accept-Language, then pt-BR
+pub struct RequestLocale(pub Locale);
+
+impl RequestLocale {
+    fn from_request(req: &HttpRequest) -> Locale {
+        let query_locale = web::Query::<LangQuery>::from_query(req.query_string())
+            .ok()
+            .and_then(|query| query.into_inner().lang)
+            .and_then(|lang| Locale::parse(&lang));
+
+        let header_locale = || {
+            req.headers()
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|header| header.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        };
+
+        query_locale.or_else(header_locale).unwrap_or_default()
+    }
+}
+
+impl FromRequest for RequestLocale {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<RequestLocale, actix_web::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(RequestLocale(RequestLocale::from_request(req))))
+    }
+}
+
+impl IntoParams for RequestLocale {
+    fn into_params(_: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let languages = [Locale::En.tag(), Locale::PtBr.tag()];
+
+        vec![
+            ParameterBuilder::new()
+                .name("lang")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .description(Some(
+                    "Language of best_performance, takes precedence over Accept-Language",
+                ))
+                .schema(Some(
+                    ObjectBuilder::new()
+                        .schema_type(Type::String)
+                        .enum_values(Some(languages)),
+                ))
+                .build(),
+            ParameterBuilder::new()
+                .name("Accept-Language")
+                .parameter_in(ParameterIn::Header)
+                .required(Required::False)
+                .description(Some(
+                    "e.g. \"en-US,en;q=0.9\", pt-BR when no supported language is found",
+                ))
+                .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+                .build(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    fn locale(uri: &str, accept_language: Option<&str>) -> Locale {
+        let mut request = TestRequest::default().uri(uri);
+        if let Some(accept_language) = accept_language {
+            request = request.insert_header((ACCEPT_LANGUAGE, accept_language));
+        }
+
+        RequestLocale::from_request(&request.to_http_request())
+    }
+
+    #[test]
+    fn lang_then_accept_language_then_portuguese() {
+        assert_eq!(locale("/calc?lang=en", Some("pt-BR")), Locale::En);
+        assert_eq!(locale("/calc", Some("en-US,en;q=0.9")), Locale::En);
+        // An unsupported lang falls back to the header
+        assert_eq!(locale("/calc?lang=fr", Some("en")), Locale::En);
+        assert_eq!(locale("/calc", Some("fr")), Locale::PtBr);
+        assert_eq!(locale("/calc", None), Locale::PtBr);
+    }
+}